@@ -0,0 +1,107 @@
+use derive_more::Display;
+use reqwest::{header, StatusCode};
+use std::time::Duration;
+use sugarfunge_api_types::error::RequestError;
+use tokio_tungstenite::tungstenite;
+
+#[derive(Debug, Display)]
+pub enum ClientError {
+    /// The API or the chain rejected the request
+    #[display(fmt = "Bad request: {}", _0)]
+    BadRequest(RequestError),
+    /// Missing or invalid credentials
+    #[display(fmt = "Unauthorized: {}", _0)]
+    Unauthorized(RequestError),
+    /// The credentials lack the scope of the route
+    #[display(fmt = "Forbidden: {}", _0)]
+    Forbidden(RequestError),
+    #[display(fmt = "Rate limited: {}", error)]
+    RateLimited {
+        retry_after: Option<Duration>,
+        error: RequestError,
+    },
+    #[display(fmt = "HTTP {}: {}", status, error)]
+    Status { status: u16, error: RequestError },
+    #[display(fmt = "HTTP error: {}", _0)]
+    Http(reqwest::Error),
+    #[display(fmt = "Invalid response: {}", _0)]
+    Decode(serde_json::Error),
+    #[display(fmt = "Websocket error: {}", _0)]
+    WebSocket(tungstenite::Error),
+}
+
+impl std::error::Error for ClientError {}
+
+impl From<reqwest::Error> for ClientError {
+    fn from(e: reqwest::Error) -> Self {
+        ClientError::Http(e)
+    }
+}
+
+impl From<serde_json::Error> for ClientError {
+    fn from(e: serde_json::Error) -> Self {
+        ClientError::Decode(e)
+    }
+}
+
+impl From<tungstenite::Error> for ClientError {
+    fn from(e: tungstenite::Error) -> Self {
+        ClientError::WebSocket(e)
+    }
+}
+
+impl ClientError {
+    /// Decode an error response. Every error body of the API is a
+    /// `RequestError`, anything else (e.g. from a proxy) is kept as the message.
+    pub(crate) async fn from_response(response: reqwest::Response) -> ClientError {
+        let status = response.status().as_u16();
+        let retry_after = response
+            .headers()
+            .get(header::RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<u64>().ok())
+            .map(Duration::from_secs);
+        match response.bytes().await {
+            Ok(body) => ClientError::from_body(status, retry_after, &body),
+            Err(e) => ClientError::Http(e),
+        }
+    }
+
+    /// Decode an error body that did not come through `Client`, e.g. from
+    /// routes served in-process
+    pub fn from_body(status: u16, retry_after: Option<Duration>, body: &[u8]) -> ClientError {
+        let error = serde_json::from_slice::<RequestError>(body).unwrap_or_else(|_| RequestError {
+            message: serde_json::Value::String(String::from_utf8_lossy(body).into()),
+            description: StatusCode::from_u16(status)
+                .ok()
+                .and_then(|status| status.canonical_reason())
+                .unwrap_or_default()
+                .into(),
+        });
+
+        match status {
+            400 => ClientError::BadRequest(error),
+            401 => ClientError::Unauthorized(error),
+            403 => ClientError::Forbidden(error),
+            429 => ClientError::RateLimited { retry_after, error },
+            status => ClientError::Status { status, error },
+        }
+    }
+
+    /// Whether repeating the same idempotent call may succeed
+    pub fn is_transient(&self) -> bool {
+        match self {
+            ClientError::RateLimited { .. } => true,
+            ClientError::Status { status, .. } => *status >= 500,
+            ClientError::Http(e) => e.is_timeout() || e.is_connect(),
+            _ => false,
+        }
+    }
+
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            ClientError::RateLimited { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use crate::{Client, ClientError};
+use futures::{future::ready, Stream, StreamExt};
+use serde_json::Value;
+use sugarfunge_api_types::endpoints::{API_KEY_HEADER, EVENTS_PATH};
+use tokio_tungstenite::tungstenite::{
+    client::IntoClientRequest,
+    http::{header, HeaderValue},
+    Message,
+};
+
+/// Chain event forwarded by the `/ws` subscription
+#[derive(Debug, Clone)]
+pub struct Event {
+    /// e.g. `Balance Transfer` or `Asset Mint`
+    pub kind: String,
+    pub data: Value,
+}
+
+impl Client {
+    /// Subscribe to the chain events of the API. The stream ends when the
+    /// server closes the connection.
+    pub async fn events(
+        &self,
+    ) -> Result<impl Stream<Item = Result<Event, ClientError>>, ClientError> {
+        let mut url = self.url(EVENTS_PATH);
+        let scheme = if url.scheme() == "https" { "wss" } else { "ws" };
+        // Both schemes are special, so the change cannot fail
+        let _ = url.set_scheme(scheme);
+
+        let mut request = url.as_str().into_client_request()?;
+        let headers = request.headers_mut();
+        if let Some(api_key) = &self.api_key {
+            headers.insert(API_KEY_HEADER, header_value(api_key)?);
+        }
+        if let Some(token) = &self.bearer_token {
+            headers.insert(
+                header::AUTHORIZATION,
+                header_value(&format!("Bearer {}", token))?,
+            );
+        }
+
+        let (stream, _) = tokio_tungstenite::connect_async(request).await?;
+        Ok(stream.filter_map(|message| {
+            ready(match message {
+                Ok(Message::Text(text)) => parse_event(&text).map(Ok),
+                Ok(_) => None,
+                Err(e) => Some(Err(e.into())),
+            })
+        }))
+    }
+}
+
+fn header_value(value: &str) -> Result<HeaderValue, ClientError> {
+    HeaderValue::from_str(value).map_err(|e| {
+        ClientError::WebSocket(tokio_tungstenite::tungstenite::Error::HttpFormat(e.into()))
+    })
+}
+
+/// Events are sent as the debug representation of `"<kind>: <json>"`,
+/// text messages from the client are echoed back
+fn parse_event(text: &str) -> Option<Event> {
+    let text = serde_json::from_str::<String>(text).unwrap_or_else(|_| text.to_string());
+    let (kind, data) = text.split_once(": ")?;
+    if kind == "echo" {
+        return None;
+    }
+    Some(Event {
+        kind: kind.into(),
+        data: serde_json::from_str(data).unwrap_or_else(|_| Value::String(data.into())),
+    })
+}
@@ -0,0 +1,131 @@
+//! Async client for the SugarFunge API. Every route of the server is a
+//! method here, generated from `sugarfunge_api_types::for_each_endpoint`:
+//!
+//! ```no_run
+//! # async fn run() -> Result<(), sugarfunge_api_client::ClientError> {
+//! use sugarfunge_api_client::{types::pool::GetAllPoolInput, Client};
+//!
+//! let client = Client::new("http://127.0.0.1:4000".parse().unwrap()).with_api_key("<key>");
+//! let pools = client.pools(&GetAllPoolInput { region: None }).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use reqwest::header;
+use std::time::Duration;
+use sugarfunge_api_types::endpoints::{self, Endpoint, API_KEY_HEADER};
+use url::Url;
+
+mod error;
+mod events;
+
+pub use error::ClientError;
+pub use events::Event;
+pub use sugarfunge_api_types as types;
+
+pub struct Client {
+    http: reqwest::Client,
+    base_url: Url,
+    api_key: Option<String>,
+    bearer_token: Option<String>,
+    max_retries: u32,
+    retry_backoff: Duration,
+}
+
+impl Client {
+    pub fn new(base_url: Url) -> Client {
+        Client {
+            http: reqwest::Client::new(),
+            base_url,
+            api_key: None,
+            bearer_token: None,
+            max_retries: 3,
+            retry_backoff: Duration::from_millis(500),
+        }
+    }
+
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    pub fn with_bearer_token(mut self, token: impl Into<String>) -> Self {
+        self.bearer_token = Some(token.into());
+        self
+    }
+
+    /// How often idempotent reads are retried on transient failures, waiting
+    /// `backoff` doubled on every attempt or the `Retry-After` of the server
+    pub fn with_retries(mut self, max_retries: u32, backoff: Duration) -> Self {
+        self.max_retries = max_retries;
+        self.retry_backoff = backoff;
+        self
+    }
+
+    /// Call any endpoint, the generated methods are shorthands for this
+    pub async fn call<E: Endpoint>(&self, input: &E::Input) -> Result<E::Output, ClientError> {
+        let mut attempt = 0;
+        loop {
+            match self.send::<E>(input).await {
+                Err(e) if E::IDEMPOTENT && e.is_transient() && attempt < self.max_retries => {
+                    let backoff = self.retry_backoff * 2u32.pow(attempt);
+                    tokio::time::sleep(e.retry_after().unwrap_or(backoff)).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn send<E: Endpoint>(&self, input: &E::Input) -> Result<E::Output, ClientError> {
+        let mut request = self.http.post(self.url(E::PATH)).json(input);
+        if let Some(api_key) = &self.api_key {
+            request = request.header(API_KEY_HEADER, api_key);
+        }
+        if let Some(token) = &self.bearer_token {
+            request = request.header(header::AUTHORIZATION, format!("Bearer {}", token));
+        }
+
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            return Err(ClientError::from_response(response).await);
+        }
+        let body = response.bytes().await?;
+        Ok(serde_json::from_slice(&body)?)
+    }
+
+    fn url(&self, path: &str) -> Url {
+        let mut url = self.base_url.clone();
+        url.set_path(&format!(
+            "{}{}",
+            self.base_url.path().trim_end_matches('/'),
+            path
+        ));
+        url
+    }
+}
+
+/// One method per endpoint, routes without a request body take no input
+macro_rules! client_methods {
+    () => {};
+    (($method:ident, $name:ident, $path:literal, (), $output:ty, $kind:ident) $(, $($rest:tt)*)?) => {
+        #[doc = concat!("`POST ", $path, "`")]
+        pub async fn $method(&self) -> Result<$output, ClientError> {
+            self.call::<endpoints::$name>(&()).await
+        }
+
+        client_methods!($($($rest)*)?);
+    };
+    (($method:ident, $name:ident, $path:literal, $input:ty, $output:ty, $kind:ident) $(, $($rest:tt)*)?) => {
+        #[doc = concat!("`POST ", $path, "`")]
+        pub async fn $method(&self, input: &$input) -> Result<$output, ClientError> {
+            self.call::<endpoints::$name>(input).await
+        }
+
+        client_methods!($($($rest)*)?);
+    };
+}
+
+impl Client {
+    sugarfunge_api_types::for_each_endpoint!(client_methods);
+}
@@ -1,57 +1,259 @@
+use crate::asset::{AssetInfo, ClassInfo};
+use crate::challenge::{ClaimData, OpenChallenge};
+use crate::fula::UploaderData;
+use crate::pool::{PoolRequestData, PoolUserData};
 use crate::primitives::*;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 pub const REFUND_SEED: &str = "//Alice";
 pub const REFUND_FEE_VALUE: u128 = 20000000000000000;
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Default, ToSchema)]
+pub struct CreateAccountInput {
+    /// Import this BIP39 mnemonic instead of generating a new one
+    pub mnemonic: Option<String>,
+    /// Hard (`//name`) and soft (`/name`) junctions applied to the mnemonic
+    pub derivation_path: Option<String>,
+    #[serde(default)]
+    pub scheme: KeyScheme,
+    /// Network prefix of `address`, [`SS58_PREFIX`] when empty
+    pub ss58_prefix: Option<u16>,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct CreateAccountOutput {
+    /// Secret URI, the mnemonic followed by the derivation path
     pub seed: Seed,
     pub account: Account,
+    pub mnemonic: String,
+    pub derivation_path: String,
+    pub scheme: KeyScheme,
+    /// The account encoded with the requested network prefix
+    pub address: String,
+    pub ss58_prefix: u16,
+}
+
+/// Signature scheme of a key pair. The gateway only signs extrinsics with
+/// sr25519 seeds, ed25519 accounts have to sign elsewhere
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, ToSchema)]
+pub enum KeyScheme {
+    #[default]
+    Sr25519,
+    Ed25519,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct FundAccountInput {
     pub seed: Seed,
     pub to: Account,
     pub amount: Balance,
+    /// Use `transfer_keep_alive`, which fails rather than reap the sender
+    #[serde(default)]
+    pub keep_alive: bool,
+    /// Submit even when the transfer raises warnings
+    #[serde(default)]
+    pub force: bool,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct FundAccountOutput {
     pub from: Account,
     pub to: Account,
     pub amount: Balance,
+    /// Warnings the transfer was forced through with
+    #[serde(default)]
+    pub warnings: Vec<TransferWarning>,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct TransferAllInput {
+    pub seed: Seed,
+    pub to: Account,
+    /// Leave the existential deposit on the sender
+    #[serde(default)]
+    pub keep_alive: bool,
+    /// Submit even when the transfer raises warnings
+    #[serde(default)]
+    pub force: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct BatchTransferInput {
+    pub seed: Seed,
+    pub transfers: Vec<TransferItem>,
+    /// Use `transfer_keep_alive` for every transfer
+    #[serde(default)]
+    pub keep_alive: bool,
+    /// Submit even when a transfer raises warnings
+    #[serde(default)]
+    pub force: bool,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct TransferItem {
+    pub to: Account,
+    pub amount: Balance,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct BatchTransferOutput {
+    pub from: Account,
+    pub transfers: Vec<FundAccountOutput>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, ToSchema)]
+pub enum TransferWarning {
+    /// The sender is left below the existential deposit, so it is reaped and
+    /// loses what remains
+    ReapsSender,
+    /// The recipient would hold less than the existential deposit, which the
+    /// chain refuses
+    BelowExistentialDeposit,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct AccountBalanceInput {
     pub account: Account,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct AccountBalanceOutput {
+    /// Same as `free`
     pub balance: Balance,
+    pub free: Balance,
+    pub reserved: Balance,
+    pub misc_frozen: Balance,
+    pub fee_frozen: Balance,
+    pub nonce: u32,
+    pub providers: u32,
+    pub consumers: u32,
+    pub sufficients: u32,
+    pub existential_deposit: Balance,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct AccountExistsInput {
     pub account: Account,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct AccountExistsOutput {
     pub account: Account,
     pub exists: bool,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct SeededAccountInput {
     pub seed: Seed,
+    #[serde(default)]
+    pub scheme: KeyScheme,
+    /// Network prefix of `address`, [`SS58_PREFIX`] when empty
+    pub ss58_prefix: Option<u16>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct SeededAccountOutput {
     pub seed: Seed,
     pub account: Account,
+    pub scheme: KeyScheme,
+    /// The account encoded with the requested network prefix
+    pub address: String,
+    pub ss58_prefix: u16,
+}
+
+/// Message signed the way Polkadot wallets sign raw payloads, wrapped in
+/// `<Bytes>` and `</Bytes>` so that it can never be a valid extrinsic
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct SignMessageInput {
+    pub seed: Seed,
+    pub message: String,
+    #[serde(default)]
+    pub scheme: KeyScheme,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct SignMessageOutput {
+    pub account: Account,
+    pub message: String,
+    /// Hex encoded signature of the wrapped message
+    pub signature: String,
+    pub scheme: KeyScheme,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct VerifyMessageInput {
+    pub account: Account,
+    pub message: String,
+    /// Hex encoded signature of the message, wrapped in `<Bytes>` unless
+    /// `raw` is set
+    pub signature: String,
+    #[serde(default)]
+    pub scheme: KeyScheme,
+    /// The signature covers the message as is, as some tools sign it
+    #[serde(default)]
+    pub raw: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct VerifyMessageOutput {
+    pub account: Account,
+    pub valid: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct AccountPortfolioInput {
+    pub account: Account,
+}
+
+/// Everything an account holds, read at a single block
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct AccountPortfolioOutput {
+    pub account: Account,
+    pub block_number: u32,
+    pub block_hash: String,
+    pub balance: NativeBalance,
+    pub assets: Vec<PortfolioAsset>,
+    /// Pool the account participates in or requested to join
+    pub pool: Option<PoolUserData>,
+    /// Open request of the account to join a pool
+    pub pool_request: Option<PoolRequestData>,
+    pub uploaded_manifests: Vec<PortfolioManifest>,
+    pub stored_manifests: Vec<PortfolioManifest>,
+    pub claim: Option<ClaimData>,
+    pub open_challenges: Vec<OpenChallenge>,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct NativeBalance {
+    pub free: Balance,
+    pub reserved: Balance,
+    /// Larger of the misc and fee frozen amounts
+    pub frozen: Balance,
+    /// Free balance above the frozen amount
+    pub transferable: Balance,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct PortfolioAsset {
+    pub class_id: ClassId,
+    pub asset_id: AssetId,
+    pub amount: Balance,
+    /// Class and asset info, none when they are not registered
+    pub class: Option<ClassInfo>,
+    pub asset: Option<AssetInfo>,
+}
+
+/// Manifest the account uploaded or stores
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct PortfolioManifest {
+    pub pool_id: PoolId,
+    pub cid: Cid,
+    pub uploaders: Vec<UploaderData>,
+    pub manifest_metadata: serde_json::Value,
+    /// Set when the stored metadata is not JSON, `manifest_metadata` then
+    /// holds its bytes hex encoded
+    #[serde(default)]
+    pub metadata_undecodable: bool,
+    pub size: Option<u64>,
 }
@@ -0,0 +1,186 @@
+use crate::primitives::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+// ACCOUNT ACTIVITY
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct GetAccountActivityInput {
+    pub account: Account,
+    /// `next` of the previous page, the latest entries when empty
+    pub before: Option<u64>,
+    /// Entries per page, 50 when empty and at most 500
+    pub limit: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct GetAccountActivityOutput {
+    pub account: Account,
+    /// Latest first
+    pub activity: Vec<ActivityEntry>,
+    /// Cursor of the next page, none on the last one
+    pub next: Option<u64>,
+    /// Unix time in seconds of the first block the gateway indexed, earlier
+    /// activity is not listed
+    pub indexed_since: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct ActivityEntry {
+    /// Block number in the high 32 bits, event index in the low ones
+    pub id: u64,
+    pub block_number: u32,
+    /// Unix time in seconds of the block
+    pub timestamp: u64,
+    /// Extrinsic that emitted the event, none for block initialization and
+    /// finalization events
+    pub extrinsic_index: Option<u32>,
+    /// `Pallet.Event` the entry was built from
+    pub event_type: String,
+    pub payload: ActivityPayload,
+}
+
+/// Events involving an account. Single and batch asset, manifest and storage
+/// events share a variant.
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub enum ActivityPayload {
+    Transfer {
+        from: Account,
+        to: Account,
+        amount: Balance,
+    },
+    ClassCreated {
+        who: Account,
+        class_id: ClassId,
+    },
+    AssetCreated {
+        who: Account,
+        class_id: ClassId,
+        asset_id: AssetId,
+    },
+    AssetMinted {
+        who: Account,
+        to: Account,
+        class_id: ClassId,
+        asset_ids: Vec<AssetId>,
+        amounts: Vec<Balance>,
+    },
+    AssetBurned {
+        who: Account,
+        from: Account,
+        class_id: ClassId,
+        asset_ids: Vec<AssetId>,
+        amounts: Vec<Balance>,
+    },
+    AssetTransferred {
+        who: Account,
+        from: Account,
+        to: Account,
+        class_id: ClassId,
+        asset_ids: Vec<AssetId>,
+        amounts: Vec<Balance>,
+    },
+    BagCreated {
+        who: Account,
+        bag: Account,
+        class_id: ClassId,
+        asset_id: AssetId,
+        owners: Vec<Account>,
+    },
+    BagDeposit {
+        who: Account,
+        bag: Account,
+    },
+    BagSwept {
+        who: Account,
+        bag: Account,
+        to: Account,
+    },
+    BundleMinted {
+        who: Account,
+        from: Account,
+        to: Account,
+        bundle_id: BundleId,
+        amount: Balance,
+    },
+    BundleBurned {
+        who: Account,
+        from: Account,
+        to: Account,
+        bundle_id: BundleId,
+        amount: Balance,
+    },
+    MarketDeposit {
+        who: Account,
+        market_id: MarketId,
+        market_rate_id: MarketId,
+        amount: Balance,
+        success: bool,
+    },
+    MarketExchange {
+        buyer: Account,
+        market_id: MarketId,
+        market_rate_id: MarketId,
+        amount: Balance,
+        success: bool,
+    },
+    PoolCreated {
+        owner: Option<Account>,
+        pool_id: PoolId,
+    },
+    JoinRequested {
+        account: Account,
+        pool_id: PoolId,
+    },
+    JoinRequestWithdrawn {
+        account: Account,
+        pool_id: PoolId,
+    },
+    /// Outcome of the votes on a join request, individual votes emit no event
+    JoinVoteResult {
+        account: Account,
+        pool_id: PoolId,
+        result: String,
+    },
+    PoolLeft {
+        account: Account,
+        pool_id: PoolId,
+    },
+    ManifestsUploaded {
+        uploader: Account,
+        pool_ids: Vec<PoolId>,
+        manifest_metadata: Vec<serde_json::Value>,
+    },
+    ManifestsRemoved {
+        uploader: Account,
+        pool_ids: Vec<PoolId>,
+        cids: Vec<Cid>,
+    },
+    ManifestsStored {
+        storer: Account,
+        pool_id: PoolId,
+        cids: Vec<Cid>,
+    },
+    ManifestsReleased {
+        storer: Account,
+        pool_id: PoolId,
+        cids: Vec<Cid>,
+    },
+    ChallengeOpened {
+        challenger: Account,
+        challenged: Account,
+        cid: Cid,
+    },
+    ChallengeVerified {
+        challenged: Account,
+        successful: Vec<Cid>,
+        failed: Vec<Cid>,
+    },
+    LaborTokensMinted {
+        account: Account,
+        class_id: ClassId,
+        asset_id: AssetId,
+        amount: Balance,
+        calculated_amount: Balance,
+    },
+}
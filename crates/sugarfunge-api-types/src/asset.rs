@@ -1,7 +1,8 @@
 use crate::primitives::*;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct CreateClassInput {
     pub seed: Seed,
     pub class_id: ClassId,
@@ -9,30 +10,30 @@ pub struct CreateClassInput {
     pub owner: Account,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct CreateClassOutput {
     pub class_id: ClassId,
     pub who: Account,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct ClassInfoInput {
     pub class_id: ClassId,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct ClassInfo {
     pub class_id: ClassId,
     pub owner: Account,
     pub metadata: serde_json::Value,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct ClassInfoOutput {
     pub info: Option<ClassInfo>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct CreateInput {
     pub seed: Seed,
     pub class_id: ClassId,
@@ -40,32 +41,32 @@ pub struct CreateInput {
     pub metadata: serde_json::Value,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct CreateOutput {
     pub class_id: ClassId,
     pub asset_id: AssetId,
     pub who: Account,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct AssetInfoInput {
     pub class_id: ClassId,
     pub asset_id: AssetId,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct AssetInfo {
     pub class_id: ClassId,
     pub asset_id: AssetId,
     pub metadata: serde_json::Value,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct AssetInfoOutput {
     pub info: Option<AssetInfo>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct UpdateMetadataInput {
     pub seed: Seed,
     pub class_id: ClassId,
@@ -73,7 +74,7 @@ pub struct UpdateMetadataInput {
     pub metadata: serde_json::Value,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct UpdateMetadataOutput {
     pub class_id: ClassId,
     pub asset_id: AssetId,
@@ -81,7 +82,7 @@ pub struct UpdateMetadataOutput {
     pub metadata: serde_json::Value,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct MintInput {
     pub seed: Seed,
     pub to: Account,
@@ -90,7 +91,7 @@ pub struct MintInput {
     pub amount: Balance,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct MintOutput {
     pub to: Account,
     pub class_id: ClassId,
@@ -99,7 +100,7 @@ pub struct MintOutput {
     pub who: Account,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct BurnInput {
     pub seed: Seed,
     pub from: Account,
@@ -108,7 +109,7 @@ pub struct BurnInput {
     pub amount: Balance,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct BurnOutput {
     pub from: Account,
     pub class_id: ClassId,
@@ -117,38 +118,38 @@ pub struct BurnOutput {
     pub who: Account,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct AssetBalanceInput {
     pub account: Account,
     pub class_id: ClassId,
     pub asset_id: AssetId,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct AssetBalanceOutput {
     pub amount: Balance,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct AssetBalancesInput {
     pub account: Account,
     pub class_id: Option<ClassId>,
     pub asset_id: Option<AssetId>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct AssetBalancesOutput {
     pub balances: Vec<AssetBalanceItemOutput>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct AssetBalanceItemOutput {
     pub class_id: ClassId,
     pub asset_id: AssetId,
     pub amount: Balance,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct TransferFromInput {
     pub seed: Seed,
     pub from: Account,
@@ -158,7 +159,7 @@ pub struct TransferFromInput {
     pub amount: Balance,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct TransferFromOutput {
     pub from: Account,
     pub to: Account,
@@ -1,20 +1,22 @@
 use crate::primitives::*;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct RegisterInput {
     pub seed: Seed,
     pub class_id: ClassId,
     pub metadata: serde_json::Value,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct RegisterOutput {
     pub who: Account,
     pub class_id: ClassId,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+#[schema(as = bag::CreateInput)]
 pub struct CreateInput {
     pub seed: Seed,
     pub class_id: ClassId,
@@ -22,7 +24,8 @@ pub struct CreateInput {
     pub shares: Vec<Balance>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+#[schema(as = bag::CreateOutput)]
 pub struct CreateOutput {
     pub bag: Account,
     pub class_id: ClassId,
@@ -30,21 +33,21 @@ pub struct CreateOutput {
     pub owners: Vec<Account>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct SweepInput {
     pub seed: Seed,
     pub bag: Account,
     pub to: Account,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct SweepOutput {
     pub bag: Account,
     pub who: Account,
     pub to: Account,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct DepositInput {
     pub seed: Seed,
     pub bag: Account,
@@ -53,7 +56,7 @@ pub struct DepositInput {
     pub amounts: Vec<Vec<Balance>>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct DepositOutput {
     pub bag: Account,
     pub who: Account,
@@ -1,14 +1,15 @@
 use crate::primitives::*;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct BundleSchema {
     pub class_ids: Vec<ClassId>,
     pub asset_ids: Vec<Vec<AssetId>>,
     pub amounts: Vec<Vec<Balance>>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct RegisterBundleInput {
     pub seed: Seed,
     pub class_id: ClassId,
@@ -17,7 +18,7 @@ pub struct RegisterBundleInput {
     pub metadata: serde_json::Value,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct RegisterBundleOutput {
     pub bundle_id: BundleId,
     pub who: Account,
@@ -25,7 +26,7 @@ pub struct RegisterBundleOutput {
     pub asset_id: AssetId,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct MintBundleInput {
     pub seed: Seed,
     pub from: Account,
@@ -34,7 +35,7 @@ pub struct MintBundleInput {
     pub amount: Balance,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct MintBundleOutput {
     pub who: Account,
     pub from: Account,
@@ -43,7 +44,7 @@ pub struct MintBundleOutput {
     pub amount: Balance,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct BurnBundleInput {
     pub seed: Seed,
     pub from: Account,
@@ -52,7 +53,7 @@ pub struct BurnBundleInput {
     pub amount: Balance,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct BurnBundleOutput {
     pub who: Account,
     pub from: Account,
@@ -61,24 +62,24 @@ pub struct BurnBundleOutput {
     pub amount: Balance,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct GetBundles {
     pub bundles: Vec<BundleItem>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct BundleItem {
     pub class_id: ClassId,
     pub asset_id: AssetId,
     pub bundle_id: BundleId,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct GetBundlesData {
     pub bundles: Vec<BundleDataItem>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct BundleDataItem {
     pub bundle_id: BundleId,
     pub creator: Account,
@@ -1,8 +1,9 @@
 use crate::primitives::*;
 use crate::sugarfunge::runtime_types::functionland_fula::ChallengeState;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub enum ChallengeStateValue {
     Open,
     Failed,
@@ -21,12 +22,12 @@ impl Into<ChallengeStateValue> for ChallengeState {
 
 // GENERATE CHALLENGE STRUCTS
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct GenerateChallengeInput {
     pub seed: Seed,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct GenerateChallengeOutput {
     pub challenger: Account,
     pub challenged: Account,
@@ -36,7 +37,7 @@ pub struct GenerateChallengeOutput {
 
 // VERIFY CHALLENGE STRUCTS
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct VerifyChallengeInput {
     pub seed: Seed,
     pub pool_id: PoolId,
@@ -45,7 +46,7 @@ pub struct VerifyChallengeInput {
     pub asset_id: AssetId,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct VerifyChallengeOutput {
     pub account: Account,
     pub successful_cids: Vec<Cid>,
@@ -54,7 +55,7 @@ pub struct VerifyChallengeOutput {
 
 // CALCULATE AND MINT LABOR TOKENS
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct MintLaborTokensInput {
     pub seed: Seed,
     pub class_id: ClassId,
@@ -62,7 +63,7 @@ pub struct MintLaborTokensInput {
     pub amount: Balance,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct MintLaborTokensOutput {
     pub account: Account,
     pub class_id: ClassId,
@@ -73,24 +74,24 @@ pub struct MintLaborTokensOutput {
 
 // Verify Pending Challenge Input
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct VerifyPendingChallengeInput {
     pub account: Account,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct VerifyPendingChallengeOutput {
     pub account: Account,
     pub pending: bool,
 }
 
 // Verify if there is a File size available to update
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct VerifyFileSizeInput {
     pub account: Account,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct VerifyFileSizeOutput {
     pub account: Account,
     pub cids: Vec<Cid>,
@@ -98,7 +99,7 @@ pub struct VerifyFileSizeOutput {
 
 // Verify Pending Challenge Input
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct ProvideFileSizeInput {
     pub seed: Seed,
     pub pool_id: PoolId,
@@ -106,7 +107,7 @@ pub struct ProvideFileSizeInput {
     pub sizes: Vec<u64>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct ProvideFileSizeOutput {
     pub account: Account,
     pub pool_id: PoolId,
@@ -116,12 +117,12 @@ pub struct ProvideFileSizeOutput {
 
 // Get Challenges
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct GetChallengesOutput {
     pub challenges: Vec<ChallengeData>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct ChallengeData {
     pub challenger: Account,
     pub challenged: Account,
@@ -130,15 +131,144 @@ pub struct ChallengeData {
 
 // Get Claim Data
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct GetClaimDataOutput {
     pub claims: Vec<ClaimData>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct ClaimData {
     pub account: Account,
     pub minted_labor_tokens: Balance,
     pub expected_labor_tokens: Balance,
     pub minted_challenge_tokens: Balance,
 }
+
+// Open challenges of an account
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct GetOpenChallengesInput {
+    pub account: Account,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct GetOpenChallengesOutput {
+    pub account: Account,
+    pub challenges: Vec<OpenChallenge>,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct OpenChallenge {
+    pub challenger: Account,
+    pub cid: Cid,
+}
+
+// Respond to the open challenges of an account
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct RespondChallengeInput {
+    pub seed: Seed,
+    /// Pool of the challenged manifests, the pool of the account by default
+    pub pool_id: Option<PoolId>,
+    /// Cids the storer holds. When missing each challenged cid is looked up
+    /// on the IPFS node of the gateway.
+    pub held_cids: Option<Vec<Cid>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct RespondChallengeOutput {
+    pub account: Account,
+    pub pool_id: PoolId,
+    pub successful_cids: Vec<Cid>,
+    pub failed_cids: Vec<Cid>,
+    pub class_id: ClassId,
+    pub asset_id: AssetId,
+    /// Tokens of `class_id` and `asset_id` minted for the successful cids
+    pub minted: Balance,
+}
+
+// Storer reliability
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct GetStorerReliabilityInput {
+    /// Every storer with stored manifests or challenge history by default
+    pub account: Option<Account>,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct GetStorerReliabilityOutput {
+    /// Unix time in seconds of the first block the gateway indexed challenge
+    /// events from
+    pub indexed_since: u64,
+    pub storers: Vec<StorerReliability>,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct StorerReliability {
+    pub account: Account,
+    pub challenges_received: u64,
+    pub challenges_successful: u64,
+    pub challenges_failed: u64,
+    /// Share of the verified challenges that were successful, none before any verification
+    pub success_rate: Option<f64>,
+    pub manifests_stored: u64,
+    pub active_cycles: u64,
+    pub missed_cycles: u64,
+    pub active_days: i64,
+    pub missed_cycles_trend: Vec<MissedCyclesSample>,
+    /// Cids whose last challenge failed, or that failed since indexing started
+    pub failed_cids: Vec<Cid>,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct MissedCyclesSample {
+    /// Unix time in seconds of the block the missed cycles changed in
+    pub timestamp: u64,
+    pub missed_cycles: u64,
+}
+
+// Rewards preview
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct GetRewardsInput {
+    pub account: Account,
+    pub pool_id: Option<PoolId>,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct GetRewardsOutput {
+    pub account: Account,
+    /// One labor token per active cycle of every stored cid
+    pub expected_labor_tokens: Balance,
+    /// One challenge token per stored cid whose last challenge succeeded
+    pub expected_challenge_tokens: Balance,
+    pub minted_labor_tokens: Balance,
+    pub minted_challenge_tokens: Balance,
+    /// Expected labor tokens not minted yet
+    pub pending_labor_tokens: Balance,
+    /// Expected challenge tokens not minted yet
+    pub pending_challenge_tokens: Balance,
+    /// Claimed tokens the expected labor and challenge tokens convert to, at
+    /// `labor_token_value` labor and `challenge_token_value` challenge tokens each
+    pub claimable_tokens: Balance,
+    pub pools: Vec<PoolRewards>,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct PoolRewards {
+    pub pool_id: PoolId,
+    pub labor_tokens: Balance,
+    pub challenge_tokens: Balance,
+    pub cids: Vec<CidRewards>,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct CidRewards {
+    pub cid: Cid,
+    pub active_days: i32,
+    pub active_cycles: u16,
+    pub missed_cycles: u16,
+    pub state: ChallengeStateValue,
+    pub labor_tokens: Balance,
+    pub challenge_tokens: Balance,
+}
@@ -1,30 +1,31 @@
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 use crate::primitives::*;
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct ContractTransactionInput {
     pub account_address: String,
     pub amount: u128,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct ContractTotalSupplyOutput {
     pub total_supply: u128,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct ContractAllowanceInput {
     pub owner_address: String,
     pub spender_address: String,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct ContractAllowanceOutput {
     pub allowance: u128,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct ConvertFulaInput {
     pub seed: Seed,
     pub wallet_account: String,
@@ -0,0 +1,247 @@
+use serde::{de::DeserializeOwned, Serialize};
+
+pub use serde_json::Value;
+
+/// A route of the API together with its request and response bodies
+pub trait Endpoint {
+    const PATH: &'static str;
+    /// Calls that do not sign anything and can be retried safely
+    const IDEMPOTENT: bool;
+    type Input: Serialize + DeserializeOwned;
+    type Output: Serialize + DeserializeOwned;
+}
+
+/// Path of the websocket event stream
+pub const EVENTS_PATH: &str = "/ws";
+
+/// Header carrying a static API key
+pub const API_KEY_HEADER: &str = "X-API-Key";
+
+/// Expands `$callback!` with one `(method, Endpoint, "path", Input, Output, read|write)`
+/// entry per route. The server registers its handlers and the client
+/// generates its methods from this list, so neither can miss a route.
+/// Routes without a request body take `()` as input.
+#[macro_export]
+macro_rules! for_each_endpoint {
+    ($callback:ident) => {
+        $callback! {
+            (health, Health, "/health",
+                (), $crate::endpoints::Value, read),
+            (account_seeded, AccountSeeded, "/account/seeded",
+                $crate::account::SeededAccountInput, $crate::account::SeededAccountOutput, read),
+            (account_exists, AccountExists, "/account/exists",
+                $crate::account::AccountExistsInput, $crate::account::AccountExistsOutput, read),
+            (account_create, AccountCreate, "/account/create",
+                $crate::account::CreateAccountInput, $crate::account::CreateAccountOutput, read),
+            (account_fund, AccountFund, "/account/fund",
+                $crate::account::FundAccountInput, $crate::account::FundAccountOutput, write),
+            (account_transfer_all, AccountTransferAll, "/account/transfer_all",
+                $crate::account::TransferAllInput, $crate::account::FundAccountOutput, write),
+            (account_batch_transfer, AccountBatchTransfer, "/account/batch_transfer",
+                $crate::account::BatchTransferInput, $crate::account::BatchTransferOutput, write),
+            (account_balance, AccountBalance, "/account/balance",
+                $crate::account::AccountBalanceInput, $crate::account::AccountBalanceOutput, read),
+            (account_portfolio, AccountPortfolio, "/account/portfolio",
+                $crate::account::AccountPortfolioInput, $crate::account::AccountPortfolioOutput, read),
+            (account_activity, AccountActivity, "/account/activity",
+                $crate::activity::GetAccountActivityInput, $crate::activity::GetAccountActivityOutput, read),
+            (account_sign, AccountSign, "/account/sign",
+                $crate::account::SignMessageInput, $crate::account::SignMessageOutput, read),
+            (account_verify, AccountVerify, "/account/verify",
+                $crate::account::VerifyMessageInput, $crate::account::VerifyMessageOutput, read),
+            (session_nonce, SessionNonce, "/session/nonce",
+                $crate::session::SessionNonceInput, $crate::session::SessionNonceOutput, read),
+            (session_sign_in, SessionSignIn, "/session/sign_in",
+                $crate::session::SignInInput, $crate::session::SignInOutput, write),
+            (session_info, SessionInfo, "/session/info",
+                (), $crate::session::SessionInfoOutput, read),
+            (asset_create_class, AssetCreateClass, "/asset/create_class",
+                $crate::asset::CreateClassInput, $crate::asset::CreateClassOutput, write),
+            (asset_class_info, AssetClassInfo, "/asset/class_info",
+                $crate::asset::ClassInfoInput, $crate::asset::ClassInfoOutput, read),
+            (asset_create, AssetCreate, "/asset/create",
+                $crate::asset::CreateInput, $crate::asset::CreateOutput, write),
+            (asset_info, AssetInfo, "/asset/info",
+                $crate::asset::AssetInfoInput, $crate::asset::AssetInfoOutput, read),
+            (asset_update_metadata, AssetUpdateMetadata, "/asset/update_metadata",
+                $crate::asset::UpdateMetadataInput, $crate::asset::UpdateMetadataOutput, write),
+            (asset_mint, AssetMint, "/asset/mint",
+                $crate::asset::MintInput, $crate::asset::MintOutput, write),
+            (asset_burn, AssetBurn, "/asset/burn",
+                $crate::asset::BurnInput, $crate::asset::BurnOutput, write),
+            (asset_balance, AssetBalance, "/asset/balance",
+                $crate::asset::AssetBalanceInput, $crate::asset::AssetBalanceOutput, read),
+            (asset_balances, AssetBalances, "/asset/balances",
+                $crate::asset::AssetBalancesInput, $crate::asset::AssetBalancesOutput, read),
+            (asset_transfer_from, AssetTransferFrom, "/asset/transfer_from",
+                $crate::asset::TransferFromInput, $crate::asset::TransferFromOutput, write),
+            (bag_register, BagRegister, "/bag/register",
+                $crate::bag::RegisterInput, $crate::bag::RegisterOutput, write),
+            (bag_create, BagCreate, "/bag/create",
+                $crate::bag::CreateInput, $crate::bag::CreateOutput, write),
+            (bag_sweep, BagSweep, "/bag/sweep",
+                $crate::bag::SweepInput, $crate::bag::SweepOutput, write),
+            (bag_deposit, BagDeposit, "/bag/deposit",
+                $crate::bag::DepositInput, $crate::bag::DepositOutput, write),
+            (bundle_register, BundleRegister, "/bundle/register",
+                $crate::bundle::RegisterBundleInput, $crate::bundle::RegisterBundleOutput, write),
+            (bundle_mint, BundleMint, "/bundle/mint",
+                $crate::bundle::MintBundleInput, $crate::bundle::MintBundleOutput, write),
+            (bundle_burn, BundleBurn, "/bundle/burn",
+                $crate::bundle::BurnBundleInput, $crate::bundle::BurnBundleOutput, write),
+            (bundles, Bundles, "/bundle",
+                (), $crate::bundle::GetBundles, read),
+            (bundles_data, BundlesData, "/bundle/data",
+                (), $crate::bundle::GetBundlesData, read),
+            (validator_add, ValidatorAdd, "/validator/add_validator",
+                $crate::validator::AddValidatorInput, $crate::validator::AddValidatorOutput, write),
+            (validator_remove, ValidatorRemove, "/validator/remove_validator",
+                $crate::validator::RemoveValidatorInput, $crate::validator::RemoveValidatorOutput, write),
+            (market_create, MarketCreate, "/market/create_market",
+                $crate::market::CreateMarketInput, $crate::market::CreateMarketOutput, write),
+            (market_create_rate, MarketCreateRate, "/market/create_market_rate",
+                $crate::market::CreateMarketRateInput, $crate::market::CreateMarketRateOutput, write),
+            (market_deposit_assets, MarketDepositAssets, "/market/deposit_assets",
+                $crate::market::DepositAssetsInput, $crate::market::DepositAssetsOutput, write),
+            (market_exchange_assets, MarketExchangeAssets, "/market/exchange_assets",
+                $crate::market::ExchangeAssetsInput, $crate::market::ExchangeAssetsOutput, write),
+            (manifest_update, ManifestUpdate, "/fula/manifest/update",
+                $crate::fula::UpdateManifestInput, $crate::fula::UpdatedManifestOutput, write),
+            (manifests, Manifests, "/fula/manifest",
+                $crate::fula::GetAllManifestsInput, $crate::fula::GetAllManifestsOutput, read),
+            (manifests_alter, ManifestsAlter, "/fula/manifest/alter",
+                $crate::fula::GetAllManifestsInput, $crate::fula::GetAllManifestsOutput, write),
+            (manifest_remove, ManifestRemove, "/fula/manifest/remove",
+                $crate::fula::RemoveManifestInput, $crate::fula::RemoveManifestOutput, write),
+            (manifest_batch_remove, ManifestBatchRemove, "/fula/manifest/batch_remove",
+                $crate::fula::BatchRemoveManifestInput, $crate::fula::BatchRemoveManifestOutput, write),
+            (manifest_remove_stored, ManifestRemoveStored, "/fula/manifest/remove_stored_manifest",
+                $crate::fula::RemoveStoringManifestInput, $crate::fula::RemoveStoringManifestOutput, write),
+            (manifest_batch_remove_stored, ManifestBatchRemoveStored, "/fula/manifest/batch_remove_stored_manifest",
+                $crate::fula::BatchRemoveStoringManifestInput, $crate::fula::BatchRemoveStoringManifestOutput, write),
+            (manifest_upload, ManifestUpload, "/fula/manifest/upload",
+                $crate::fula::UploadManifestInput, $crate::fula::UploadManifestOutput, write),
+            (manifest_batch_upload, ManifestBatchUpload, "/fula/manifest/batch_upload",
+                $crate::fula::BatchUploadManifestInput, $crate::fula::BatchUploadManifestOutput, write),
+            (manifest_available, ManifestAvailable, "/fula/manifest/available",
+                $crate::fula::GetAvailableManifestsInput, $crate::fula::GetAvailableManifestsOutput, read),
+            (manifest_available_alter, ManifestAvailableAlter, "/fula/manifest/available/alter",
+                $crate::fula::GetAvailableManifestsInput, $crate::fula::GetAvailableManifestsOutput, write),
+            (manifest_storage, ManifestStorage, "/fula/manifest/storage",
+                $crate::fula::StorageManifestInput, $crate::fula::StorageManifestOutput, write),
+            (manifest_batch_storage, ManifestBatchStorage, "/fula/manifest/batch_storage",
+                $crate::fula::BatchStorageManifestInput, $crate::fula::BatchStorageManifestOutput, write),
+            (manifest_storer_data, ManifestStorerData, "/fula/manifest/storer_data",
+                $crate::fula::GetAllManifestsStorerDataInput, $crate::fula::GetAllManifestsStorerDataOutput, read),
+            (manifest_storer_data_alter, ManifestStorerDataAlter, "/fula/manifest/storer_data/alter",
+                $crate::fula::GetAllManifestsStorerDataInput, $crate::fula::GetAllManifestsStorerDataOutput, write),
+            (manifest_verify, ManifestVerify, "/fula/manifest/verify",
+                $crate::fula::VerifyManifestsInput, $crate::fula::VerifyManifestsOutput, write),
+            (pool_create, PoolCreate, "/fula/pool/create",
+                $crate::pool::CreatePoolInput, $crate::pool::CreatePoolOutput, write),
+            (pool_leave, PoolLeave, "/fula/pool/leave",
+                $crate::pool::LeavePoolInput, $crate::pool::LeavePoolOutput, write),
+            (pool_join, PoolJoin, "/fula/pool/join",
+                $crate::pool::JoinPoolInput, $crate::pool::JoinPoolOutput, write),
+            (pool_cancel_join, PoolCancelJoin, "/fula/pool/cancel_join",
+                $crate::pool::CancelJoinPoolInput, $crate::pool::CancelJoinPoolOutput, write),
+            (pool_vote, PoolVote, "/fula/pool/vote",
+                $crate::pool::VoteInput, $crate::pool::VoteOutput, write),
+            (pools, Pools, "/fula/pool",
+                $crate::pool::GetAllPoolInput, $crate::pool::GetAllPoolsOutput, read),
+            (pool_stats, PoolStats, "/fula/pool/stats",
+                $crate::pool::GetPoolStatsInput, $crate::pool::GetPoolStatsOutput, read),
+            (pool_tree, PoolTree, "/fula/pool/tree",
+                $crate::pool::GetPoolTreeInput, $crate::pool::GetPoolTreeOutput, read),
+            (pool_ancestry, PoolAncestry, "/fula/pool/ancestry",
+                $crate::pool::GetPoolAncestryInput, $crate::pool::GetPoolAncestryOutput, read),
+            (pool_peer, PoolPeer, "/fula/pool/peer",
+                $crate::pool::GetPeerInput, $crate::pool::GetPeerOutput, read),
+            (pool_peers, PoolPeers, "/fula/pool/peers",
+                $crate::pool::GetPoolPeersInput, $crate::pool::GetPoolPeersOutput, read),
+            (pool_requests, PoolRequests, "/fula/pool/poolrequests",
+                $crate::pool::GetAllPoolRequestInput, $crate::pool::GetAllPoolRequestsOutput, read),
+            (pool_governance, PoolGovernance, "/fula/pool/governance",
+                $crate::pool::GetPoolGovernanceInput, $crate::pool::GetPoolGovernanceOutput, read),
+            (pool_pending_votes, PoolPendingVotes, "/fula/pool/pending_votes",
+                $crate::pool::GetPendingVotesInput, $crate::pool::GetPendingVotesOutput, read),
+            (pool_users, PoolUsers, "/fula/pool/users",
+                $crate::pool::GetAllPoolUsersInput, $crate::pool::GetAllPoolUsersOutput, read),
+            (contract_mint, ContractMint, "/fula/contract/mint",
+                $crate::contract::ContractTransactionInput, $crate::endpoints::Value, write),
+            (contract_supply, ContractSupply, "/fula/contract/supply",
+                (), $crate::contract::ContractTotalSupplyOutput, read),
+            (contract_allowance, ContractAllowance, "/fula/contract/allowance",
+                $crate::contract::ContractAllowanceInput, $crate::contract::ContractAllowanceOutput, read),
+            (contract_increase_allowance, ContractIncreaseAllowance, "/fula/contract/increase_allowance",
+                $crate::contract::ContractTransactionInput, $crate::endpoints::Value, write),
+            (contract_decrease_allowance, ContractDecreaseAllowance, "/fula/contract/decrease_allowance",
+                $crate::contract::ContractTransactionInput, $crate::endpoints::Value, write),
+            (contract_burn, ContractBurn, "/fula/contract/burn",
+                $crate::contract::ContractTransactionInput, $crate::endpoints::Value, write),
+            (contract_transfer, ContractTransfer, "/fula/contract/transfer",
+                $crate::contract::ContractTransactionInput, $crate::endpoints::Value, write),
+            (challenge_generate, ChallengeGenerate, "/fula/challenge/generate",
+                $crate::challenge::GenerateChallengeInput, $crate::challenge::GenerateChallengeOutput, write),
+            (challenge_verify, ChallengeVerify, "/fula/challenge/verify",
+                $crate::challenge::VerifyChallengeInput, $crate::challenge::VerifyChallengeOutput, write),
+            (mint_labor_tokens, MintLaborTokens, "/fula/mint_labor_tokens",
+                $crate::challenge::MintLaborTokensInput, $crate::challenge::MintLaborTokensOutput, write),
+            (challenge_pending, ChallengePending, "/fula/challenge/pending",
+                $crate::challenge::VerifyPendingChallengeInput, $crate::challenge::VerifyPendingChallengeOutput, read),
+            (challenge_open, ChallengeOpen, "/fula/challenge/open",
+                $crate::challenge::GetOpenChallengesInput, $crate::challenge::GetOpenChallengesOutput, read),
+            (challenge_respond, ChallengeRespond, "/fula/challenge/respond",
+                $crate::challenge::RespondChallengeInput, $crate::challenge::RespondChallengeOutput, write),
+            (challenge_reliability, ChallengeReliability, "/fula/challenge/reliability",
+                $crate::challenge::GetStorerReliabilityInput, $crate::challenge::GetStorerReliabilityOutput, read),
+            (file_verify, FileVerify, "/fula/file/verify",
+                $crate::challenge::VerifyFileSizeInput, $crate::challenge::VerifyFileSizeOutput, read),
+            (file_provide, FileProvide, "/fula/file/provide",
+                $crate::challenge::ProvideFileSizeInput, $crate::challenge::ProvideFileSizeOutput, write),
+            (challenges, Challenges, "/fula/challenge",
+                (), $crate::challenge::GetChallengesOutput, read),
+            (claims, Claims, "/fula/claims",
+                (), $crate::challenge::GetClaimDataOutput, read),
+            (rewards, Rewards, "/fula/rewards",
+                $crate::challenge::GetRewardsInput, $crate::challenge::GetRewardsOutput, read),
+            (replication_report, ReplicationReport, "/fula/replication/report",
+                $crate::replication::GetReplicationReportInput, $crate::replication::GetReplicationReportOutput, read),
+            (goerli_convert_tokens, GoerliConvertTokens, "/fula/goerli/convert_tokens",
+                $crate::contract::ConvertFulaInput, $crate::endpoints::Value, write),
+            (mumbai_convert_tokens, MumbaiConvertTokens, "/fula/mumbai/convert_tokens",
+                $crate::contract::ConvertFulaInput, $crate::endpoints::Value, write),
+            (rate_limits, RateLimits, "/admin/rate_limits",
+                $crate::rate_limit::GetRateLimitsInput, $crate::rate_limit::GetRateLimitsOutput, read),
+        }
+    };
+}
+
+macro_rules! idempotent {
+    (read) => {
+        true
+    };
+    (write) => {
+        false
+    };
+}
+
+macro_rules! define_endpoints {
+    ($(($method:ident, $name:ident, $path:literal, $input:ty, $output:ty, $kind:ident)),* $(,)?) => {
+        $(
+            pub struct $name;
+
+            impl Endpoint for $name {
+                const PATH: &'static str = $path;
+                const IDEMPOTENT: bool = idempotent!($kind);
+                type Input = $input;
+                type Output = $output;
+            }
+        )*
+
+        /// Path of every endpoint
+        pub const PATHS: &[&str] = &[$($path),*];
+    };
+}
+
+for_each_endpoint!(define_endpoints);
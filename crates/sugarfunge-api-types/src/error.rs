@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use utoipa::ToSchema;
+
+/// Body of every error response of the API
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct RequestError {
+    pub message: serde_json::Value,
+    pub description: String,
+}
+
+impl fmt::Display for RequestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} {:?}", self.message, self.description)
+    }
+}
+
+impl std::error::Error for RequestError {}
@@ -1,14 +1,83 @@
 use crate::{challenge::ChallengeStateValue, primitives::*};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+// MANIFEST METADATA
+
+/// Metadata Fula clients attach to a manifest, checked on upload when the
+/// gateway runs with `manifest.strict_metadata`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ManifestMetadata {
+    pub job: ManifestJob,
+    pub name: Option<String>,
+    /// Size of the content in bytes
+    pub size: Option<u64>,
+    /// MIME type of the content, e.g. `image/png`
+    pub content_type: Option<String>,
+    pub encryption: Option<ManifestEncryption>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ManifestJob {
+    /// Kind of job, e.g. `Storage`
+    pub work: String,
+    /// Engine running the job, e.g. `IPFS`
+    pub engine: String,
+    /// Content the job is about, the cid of the manifest
+    pub uri: Cid,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ManifestEncryption {
+    /// Cipher of the content, e.g. `AES-256-GCM`
+    pub algorithm: String,
+    /// Identifier of the key the content is encrypted with
+    pub key_id: Option<String>,
+}
+
+impl ManifestMetadata {
+    /// Decode `value` as the metadata of the manifest of `cid`
+    pub fn validate(value: &serde_json::Value, cid: &Cid) -> Result<ManifestMetadata, String> {
+        let metadata: ManifestMetadata =
+            serde_json::from_value(value.clone()).map_err(|e| e.to_string())?;
+        if metadata.job.uri.as_str() != cid.as_str() {
+            return Err(format!(
+                "job.uri: {} does not match the manifest cid {}",
+                metadata.job.uri.as_str(),
+                cid.as_str()
+            ));
+        }
+        if metadata.job.work.is_empty() || metadata.job.engine.is_empty() {
+            return Err("job: work and engine must not be empty".into());
+        }
+        if metadata.name.as_ref().map_or(false, |name| name.is_empty()) {
+            return Err("name: must not be empty".into());
+        }
+        if let Some(content_type) = &metadata.content_type {
+            let mut parts = content_type.splitn(2, '/');
+            let valid = matches!(
+                (parts.next(), parts.next()),
+                (Some(kind), Some(subtype)) if !kind.is_empty() && !subtype.is_empty()
+            );
+            if !valid {
+                return Err(format!("content_type: {} is not a MIME type", content_type));
+            }
+        }
+        Ok(metadata)
+    }
+}
 
 // VERIFY MANIFEST STRUCTS
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct VerifyManifestsInput {
     pub seed: Seed,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct VerifyManifestsOutput {
     pub storer: Account,
     pub valid_manifests: Vec<Cid>,
@@ -17,44 +86,54 @@ pub struct VerifyManifestsOutput {
 
 // UPLOAD MANIFEST STRUCTS
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct UploadManifestInput {
     pub seed: Seed,
+    /// Any JSON, or a `ManifestMetadata` when the gateway validates metadata
     pub manifest_metadata: serde_json::Value,
     pub cid: Cid,
     pub pool_id: PoolId,
     pub replication_factor: ReplicationFactor,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct UploadManifestOutput {
     pub uploader: Account,
     pub storers: Vec<Account>,
     pub manifest_metadata: serde_json::Value,
+    /// Set when the stored metadata is not JSON, `manifest_metadata` then
+    /// holds its bytes hex encoded
+    #[serde(default)]
+    pub metadata_undecodable: bool,
     pub pool_id: PoolId,
 }
 
 // BATCH UPLOAD MANIFEST STRUCTS
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct BatchUploadManifestInput {
     pub seed: Seed,
+    /// Any JSON, or a `ManifestMetadata` when the gateway validates metadata
     pub manifest_metadata: Vec<serde_json::Value>,
     pub cid: Vec<Cid>,
     pub pool_id: Vec<PoolId>,
     pub replication_factor: Vec<ReplicationFactor>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct BatchUploadManifestOutput {
     pub uploader: Account,
     pub pool_id: Vec<PoolId>,
     pub manifest_metadata: Vec<serde_json::Value>,
+    /// Per manifest, set when the stored metadata is not JSON and is
+    /// returned hex encoded instead
+    #[serde(default)]
+    pub metadata_undecodable: Vec<bool>,
 }
 
 //UPDATE MANIFESTS STRUCTS
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct UpdateManifestInput {
     pub seed: Seed,
     pub cid: Cid,
@@ -64,7 +143,7 @@ pub struct UpdateManifestInput {
     pub missed_cycles: u16,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct UpdatedManifestOutput {
     pub storer: Account,
     pub pool_id: PoolId,
@@ -76,14 +155,14 @@ pub struct UpdatedManifestOutput {
 
 // STORAGE MANIFEST STRUCTS
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct StorageManifestInput {
     pub seed: Seed,
     pub cid: Cid,
     pub pool_id: PoolId,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct StorageManifestOutput {
     pub storer: Account,
     pub cid: Cid,
@@ -92,14 +171,14 @@ pub struct StorageManifestOutput {
 
 // BATCH STORAGE MANIFEST STRUCTS
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct BatchStorageManifestInput {
     pub seed: Seed,
     pub pool_id: PoolId,
     pub cid: Vec<Cid>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct BatchStorageManifestOutput {
     pub storer: Account,
     pub pool_id: PoolId,
@@ -108,13 +187,13 @@ pub struct BatchStorageManifestOutput {
 
 // REMOVE MANIFEST STRUCTS
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct RemoveManifestInput {
     pub seed: Seed,
     pub cid: Cid,
     pub pool_id: PoolId,
 }
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct RemoveManifestOutput {
     pub uploader: Account,
     pub cid: Cid,
@@ -123,13 +202,13 @@ pub struct RemoveManifestOutput {
 
 // BATCH REMOVE MANIFEST STRUCTS
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct BatchRemoveManifestInput {
     pub seed: Seed,
     pub pool_id: Vec<PoolId>,
     pub cid: Vec<Cid>,
 }
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct BatchRemoveManifestOutput {
     pub uploader: Account,
     pub pool_id: Vec<PoolId>,
@@ -138,13 +217,13 @@ pub struct BatchRemoveManifestOutput {
 
 // REMOVE STORING A MANIFEST STRUCTS
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct RemoveStoringManifestInput {
     pub seed: Seed,
     pub cid: Cid,
     pub pool_id: PoolId,
 }
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct RemoveStoringManifestOutput {
     pub storer: Option<Account>,
     pub cid: Cid,
@@ -153,13 +232,13 @@ pub struct RemoveStoringManifestOutput {
 
 // BATCH REMOVE STORING A MANIFEST STRUCTS
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct BatchRemoveStoringManifestInput {
     pub seed: Seed,
     pub pool_id: PoolId,
     pub cid: Vec<Cid>,
 }
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct BatchRemoveStoringManifestOutput {
     pub storer: Account,
     pub pool_id: PoolId,
@@ -168,31 +247,31 @@ pub struct BatchRemoveStoringManifestOutput {
 
 // GET MANIFESTS STRUCTS
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct GetAllManifestsInput {
     pub pool_id: Option<PoolId>,
     pub uploader: Option<Account>,
     pub storer: Option<Account>,
 }
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct GetAllManifestsOutput {
     pub manifests: Vec<Manifest>,
 }
 
 // GET MANIFESTS STORER DATA STRUCTS
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct GetAllManifestsStorerDataInput {
     pub pool_id: Option<PoolId>,
     pub storer: Option<Account>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct GetAllManifestsStorerDataOutput {
     pub manifests: Vec<ManifestStorageData>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct ManifestStorageData {
     pub pool_id: PoolId,
     pub account: Account,
@@ -205,34 +284,42 @@ pub struct ManifestStorageData {
 
 // GET AVAILABLE MANIFEST STRUCTS
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct GetAvailableManifestsInput {
     pub pool_id: Option<PoolId>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct GetAvailableManifestsOutput {
     pub manifests: Vec<ManifestAvailable>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct ManifestAvailable {
     pub pool_id: PoolId,
     pub manifest_metadata: serde_json::Value,
+    /// Set when the stored metadata is not JSON, `manifest_metadata` then
+    /// holds its bytes hex encoded
+    #[serde(default)]
+    pub metadata_undecodable: bool,
     pub replication_available: ReplicationFactor,
 }
 
 // GENERAL MANIFEST STRUCTS
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
 pub struct Manifest {
     pub pool_id: PoolId,
     pub uploaders: Vec<UploaderData>,
     pub manifest_metadata: serde_json::Value,
+    /// Set when the stored metadata is not JSON, `manifest_metadata` then
+    /// holds its bytes hex encoded
+    #[serde(default)]
+    pub metadata_undecodable: bool,
     pub size: Option<u64>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
 pub struct UploaderData {
     pub uploader: Account,
     pub storers: Vec<Account>,
@@ -10,13 +10,19 @@
 )]
 pub mod sugarfunge {}
 pub mod account;
+pub mod activity;
 pub mod asset;
 pub mod bag;
 pub mod bundle;
 pub mod challenge;
 pub mod contract;
+pub mod endpoints;
+pub mod error;
 pub mod fula;
 pub mod market;
 pub mod pool;
 pub mod primitives;
+pub mod rate_limit;
+pub mod replication;
+pub mod session;
 pub mod validator;
@@ -1,8 +1,9 @@
 use crate::primitives::*;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 use crate::sugarfunge::runtime_types::sugarfunge_market;
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, ToSchema)]
 pub enum AmountOp {
     Equal,
     LessThan,
@@ -35,7 +36,7 @@ impl From<sugarfunge_market::AmountOp> for AmountOp {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, ToSchema)]
 pub enum AMM {
     Constant,
 }
@@ -56,7 +57,7 @@ impl From<sugarfunge_market::AMM> for AMM {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
 pub enum RateAction {
     Transfer(Amount),
     MarketTransfer(AMM, ClassId, AssetId),
@@ -106,7 +107,7 @@ impl From<sugarfunge_market::RateAction<u64, u64>> for RateAction {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
 pub enum RateAccount {
     Market,
     Account(Account),
@@ -139,7 +140,7 @@ impl From<sugarfunge_market::RateAccount<subxt::utils::AccountId32>> for RateAcc
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
 pub struct AssetRate {
     pub class_id: ClassId,
     pub asset_id: AssetId,
@@ -175,7 +176,7 @@ impl From<sugarfunge_market::AssetRate<subxt::utils::AccountId32, u64, u64>> for
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
 pub struct RateBalance {
     pub rate: AssetRate,
     pub balance: Amount,
@@ -202,39 +203,39 @@ impl From<Account> for RateAccount {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct Rates {
     pub rates: Vec<AssetRate>,
     pub metadata: serde_json::Value,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct CreateMarketInput {
     pub seed: Seed,
     pub market_id: MarketId,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct CreateMarketOutput {
     pub market_id: MarketId,
     pub who: Account,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct CreateMarketRateInput {
     pub seed: Seed,
     pub market_id: MarketId,
     pub market_rate_id: MarketId,
     pub rates: Rates,
 }
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct CreateMarketRateOutput {
     pub market_id: MarketId,
     pub market_rate_id: MarketId,
     pub who: Account,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct DepositAssetsInput {
     pub seed: Seed,
     pub market_id: MarketId,
@@ -242,7 +243,7 @@ pub struct DepositAssetsInput {
     pub amount: Balance,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct DepositAssetsOutput {
     pub who: Account,
     pub market_id: MarketId,
@@ -252,7 +253,7 @@ pub struct DepositAssetsOutput {
     pub success: bool,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct ExchangeAssetsInput {
     pub seed: Seed,
     pub market_id: MarketId,
@@ -260,7 +261,7 @@ pub struct ExchangeAssetsInput {
     pub amount: Balance,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct ExchangeAssetsOutput {
     pub buyer: Account,
     pub market_id: MarketId,
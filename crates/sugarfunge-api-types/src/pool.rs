@@ -1,17 +1,20 @@
 use crate::primitives::*;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 // CREATE POOL
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct CreatePoolInput {
     pub seed: Seed,
     pub pool_name: Name,
     pub peer_id: PeerId,
+    /// ISO 3166 country or subdivision code, or a region configured on the
+    /// gateway
     pub region: String,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct CreatePoolOutput {
     pub owner: Option<Account>,
     pub pool_id: PoolId,
@@ -19,28 +22,41 @@ pub struct CreatePoolOutput {
 
 // LEAVE POOL
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct LeavePoolInput {
     pub seed: Seed,
     pub pool_id: PoolId,
+    /// Remove the account as storer of its manifests in the pool before
+    /// leaving
+    #[serde(default)]
+    pub release_storage: bool,
+    /// Only list the manifests that would be released, nothing is submitted
+    #[serde(default)]
+    pub preview: bool,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct LeavePoolOutput {
     pub pool_id: PoolId,
     pub account: Account,
+    /// Manifests the account stopped storing, or would stop storing on a
+    /// preview
+    #[serde(default)]
+    pub released_cids: Vec<Cid>,
+    #[serde(default)]
+    pub preview: bool,
 }
 
 // JOIN POOL
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct JoinPoolInput {
     pub seed: Seed,
     pub pool_id: PoolId,
     pub peer_id: PeerId,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct JoinPoolOutput {
     pub pool_id: PoolId,
     pub account: Account,
@@ -48,13 +64,13 @@ pub struct JoinPoolOutput {
 
 // CANCEL JOIN POOL
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct CancelJoinPoolInput {
     pub seed: Seed,
     pub pool_id: PoolId,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct CancelJoinPoolOutput {
     pub pool_id: PoolId,
     pub account: Account,
@@ -62,7 +78,7 @@ pub struct CancelJoinPoolOutput {
 
 // VOTE
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct VoteInput {
     pub seed: Seed,
     pub pool_id: PoolId,
@@ -71,7 +87,7 @@ pub struct VoteInput {
     pub peer_id: PeerId,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct VoteOutput {
     pub pool_id: PoolId,
     pub account: Account,
@@ -80,17 +96,18 @@ pub struct VoteOutput {
 
 // GET POOLS
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct GetAllPoolInput {
+    /// Also matches the regions within it, a country matches its subdivisions
     pub region: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct GetAllPoolsOutput {
     pub pools: Vec<PoolData>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct PoolData {
     pub pool_id: PoolId,
     pub creator: Option<Account>,
@@ -100,19 +117,107 @@ pub struct PoolData {
     pub participants: Vec<Account>,
 }
 
+// POOL STATS
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct GetPoolStatsInput {
+    pub pool_id: Option<PoolId>,
+    /// Pool id order when empty
+    pub sort_by: Option<PoolStatsSort>,
+    #[serde(default)]
+    pub descending: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, ToSchema)]
+pub enum PoolStatsSort {
+    Participants,
+    PendingRequests,
+    Manifests,
+    TotalSize,
+    UnderReplicated,
+    OpenChallenges,
+    Reliability,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct GetPoolStatsOutput {
+    pub pools: Vec<PoolStats>,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct PoolStats {
+    pub pool_id: PoolId,
+    pub pool_name: Name,
+    pub region: String,
+    pub participants: u64,
+    pub pending_requests: u64,
+    pub manifests: u64,
+    /// Sum of the known manifest sizes
+    pub total_size: u64,
+    /// Manifests whose size is not reported yet
+    pub unsized_manifests: u64,
+    pub under_replicated: u64,
+    /// Open challenges sent to participants
+    pub open_challenges: u64,
+    /// Mean challenge success rate of the participants with verified
+    /// challenges, none when there are none
+    pub average_reliability: Option<f64>,
+}
+
+// POOL HIERARCHY
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct GetPoolTreeInput {
+    /// Root of the subtree, every tree of the forest when empty
+    pub pool_id: Option<PoolId>,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct GetPoolTreeOutput {
+    pub roots: Vec<PoolNode>,
+    /// Pools whose chain of parents loops. The forest has no root for them
+    /// and leaves them out, a subtree lists those it holds.
+    pub cyclic: Vec<PoolId>,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct PoolNode {
+    pub pool: PoolData,
+    pub children: Vec<PoolNode>,
+    /// Pools in the subtree, this one included
+    pub subtree_pools: u64,
+    /// Participants of every pool in the subtree
+    pub subtree_participants: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct GetPoolAncestryInput {
+    pub pool_id: PoolId,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct GetPoolAncestryOutput {
+    pub pool_id: PoolId,
+    /// Parent first, root last
+    pub ancestors: Vec<PoolData>,
+    /// Set when the chain of parents loops, `ancestors` then ends before
+    /// the first pool seen twice
+    pub cyclic: bool,
+}
+
 // GET USERS
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct GetAllPoolUsersInput {
     pub account: Option<Account>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct GetAllPoolUsersOutput {
     pub users: Vec<PoolUserData>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct PoolUserData {
     pub account: Account,
     pub pool_id: Option<PoolId>,
@@ -120,20 +225,74 @@ pub struct PoolUserData {
     pub peer_id: PeerId,
 }
 
+// PEER DIRECTORY
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct GetPeerInput {
+    pub peer_id: PeerId,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct GetPeerOutput {
+    pub peer_id: PeerId,
+    /// Accounts claiming the peer id, empty when it is unknown
+    pub accounts: Vec<PeerRecord>,
+    /// More than one account claims the peer id
+    pub duplicate: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct GetPoolPeersInput {
+    pub pool_id: PoolId,
+    /// Also list the peers of the open requests to join the pool
+    #[serde(default)]
+    pub include_requests: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct GetPoolPeersOutput {
+    pub pool_id: PoolId,
+    pub peers: Vec<PeerRecord>,
+    /// Peer ids of the pool also claimed by other accounts
+    pub duplicates: Vec<PeerId>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, ToSchema)]
+pub enum PeerStatus {
+    Participant,
+    Requested,
+    /// Neither in a pool nor requesting to join one
+    Unaffiliated,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct PeerRecord {
+    pub peer_id: PeerId,
+    pub account: Account,
+    pub status: PeerStatus,
+    /// Pool the account participates in or requested to join
+    pub pool_id: Option<PoolId>,
+    /// Votes on the open join request, when the account requested to join
+    pub positive_votes: Option<u16>,
+    pub voted: Vec<Account>,
+    /// Another account claims the same peer id
+    pub duplicate: bool,
+}
+
 // GET POOLREQUESTS
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct GetAllPoolRequestInput {
     pub pool_id: Option<PoolId>,
     pub account: Option<Account>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct GetAllPoolRequestsOutput {
     pub poolrequests: Vec<PoolRequestData>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct PoolRequestData {
     pub pool_id: PoolId,
     pub account: Account,
@@ -142,65 +301,45 @@ pub struct PoolRequestData {
     pub peer_id: PeerId,
 }
 
-// FUNCTIONS TO MANAGE THE REGIONS
-
-#[derive(Serialize, Deserialize, Debug)]
-/// An enum that represents the region of the pool
-pub enum Region {
-    Alberta,
-    BritishColumbia,
-    Manitoba,
-    NewBrunswick,
-    NewfoundlandAndLabrador,
-    NovaScotia,
-    Ontario,
-    PrinceEdwardIsland,
-    Quebec,
-    Saskatchewan,
-    NorthwestTerritories,
-    Nunavut,
-    Yukon,
-    Other,
-}
-
-impl Into<Region> for &String {
-    fn into(self) -> Region {
-        match self.as_str() {
-            "Alberta" => Region::Alberta,
-            "BritishColumbia" => Region::BritishColumbia,
-            "Manitoba" => Region::Manitoba,
-            "NewBrunswick" => Region::NewBrunswick,
-            "NewfoundlandAndLabrador" => Region::NewfoundlandAndLabrador,
-            "NovaScotia" => Region::NovaScotia,
-            "Ontario" => Region::Ontario,
-            "PrinceEdwardIsland" => Region::PrinceEdwardIsland,
-            "Quebec" => Region::Quebec,
-            "Saskatchewan" => Region::Saskatchewan,
-            "NorthwestTerritories" => Region::NorthwestTerritories,
-            "Nunavut" => Region::Nunavut,
-            "Yukon" => Region::Yukon,
-            _ => Region::Other,
-        }
-    }
-}
-
-impl Into<Vec<u8>> for Region {
-    fn into(self) -> Vec<u8> {
-        match self {
-            Region::Alberta => "Alberta".as_bytes().to_vec(),
-            Region::BritishColumbia => "BritishColumbia".as_bytes().to_vec(),
-            Region::Manitoba => "Manitoba".as_bytes().to_vec(),
-            Region::NewBrunswick => "NewBrunswick".as_bytes().to_vec(),
-            Region::NewfoundlandAndLabrador => "NewfoundlandAndLabrador".as_bytes().to_vec(),
-            Region::NovaScotia => "NovaScotia".as_bytes().to_vec(),
-            Region::Ontario => "Ontario".as_bytes().to_vec(),
-            Region::PrinceEdwardIsland => "PrinceEdwardIsland".as_bytes().to_vec(),
-            Region::Quebec => "Quebec".as_bytes().to_vec(),
-            Region::Saskatchewan => "Saskatchewan".as_bytes().to_vec(),
-            Region::NorthwestTerritories => "NorthwestTerritories".as_bytes().to_vec(),
-            Region::Nunavut => "Nunavut".as_bytes().to_vec(),
-            Region::Yukon => "Yukon".as_bytes().to_vec(),
-            Region::Other => "Other".as_bytes().to_vec(),
-        }
-    }
+// POOL GOVERNANCE
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct GetPoolGovernanceInput {
+    pub pool_id: Option<PoolId>,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct GetPoolGovernanceOutput {
+    pub requests: Vec<PoolRequestVotes>,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct GetPendingVotesInput {
+    pub account: Account,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct GetPendingVotesOutput {
+    pub account: Account,
+    /// Requests to pools of the account it has not voted on yet
+    pub requests: Vec<PoolRequestVotes>,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct PoolRequestVotes {
+    pub pool_id: PoolId,
+    pub account: Account,
+    pub peer_id: PeerId,
+    /// Participants of the pool
+    pub eligible_voters: Vec<Account>,
+    pub voted: Vec<Account>,
+    pub not_voted: Vec<Account>,
+    pub positive_votes: u16,
+    pub negative_votes: u16,
+    /// Positive votes accepting the request, more than half the participants
+    pub approvals_needed: u16,
+    pub remaining_approvals: u16,
+    /// Block the request was made in, unknown for requests older than the
+    /// blocks the gateway indexed
+    pub requested_at: Option<u32>,
 }
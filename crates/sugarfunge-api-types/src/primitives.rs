@@ -1,14 +1,59 @@
-use std::{ops::Div, str::FromStr};
+use std::{cell::Cell, ops::Div, str::FromStr};
 
-use serde::{Deserialize, Serialize};
+use serde::{de, Deserialize, Deserializer, Serialize};
+use utoipa::ToSchema;
 
 use sp_core;
 
+use sp_core::crypto::Ss58Codec;
+
 use sp_core::U256;
 
 use bevy_derive::{Deref, DerefMut};
 
-#[derive(Serialize, Deserialize, Clone, Debug, Deref, DerefMut)]
+/// SS58 address format of the SugarFunge runtime (`System::SS58Prefix`)
+pub const SS58_PREFIX: u16 = 42;
+
+/// `StringLimit` of the pool pallet, bounding pool names
+pub const MAX_NAME_LENGTH: usize = 128;
+
+/// `MaxPeerIdLength` of the runtime
+pub const MAX_PEER_ID_LENGTH: usize = 128;
+
+thread_local! {
+    static VALIDATE: Cell<bool> = Cell::new(false);
+}
+
+/// Run `deserialize` validating the accounts, cids, names, peer ids and
+/// bundle ids it reads, as request bodies are. Elsewhere they are read as
+/// is, outputs carry whatever the chain stores, empty cids included.
+pub fn validated<R>(deserialize: impl FnOnce() -> R) -> R {
+    struct Reset(bool);
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            VALIDATE.with(|validate| validate.set(self.0));
+        }
+    }
+    let _reset = Reset(VALIDATE.with(|validate| validate.replace(true)));
+    deserialize()
+}
+
+/// Deserialize a string, validated with the type's `FromStr` within
+/// [`validated`]
+fn deserialize_validated<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr<Err = String> + From<String>,
+{
+    let value = String::deserialize(deserializer)?;
+    if VALIDATE.with(Cell::get) {
+        value.parse().map_err(de::Error::custom)
+    } else {
+        Ok(T::from(value))
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Deref, DerefMut, ToSchema)]
 pub struct Seed(String);
 
 impl From<String> for Seed {
@@ -29,9 +74,35 @@ impl Seed {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, Deref, DerefMut)]
+/// SS58 address, checked against [`SS58_PREFIX`] when parsed or read from a
+/// request
+#[derive(Serialize, Clone, Debug, Deref, ToSchema)]
 pub struct Account(String);
 
+impl FromStr for Account {
+    type Err = String;
+
+    fn from_str(account: &str) -> Result<Account, String> {
+        let (_, format) = sp_core::crypto::AccountId32::from_ss58check_with_version(account)
+            .map_err(|e| format!("invalid SS58 address {}: {:?}", account, e))?;
+        let prefix = u16::from(format);
+        if prefix != SS58_PREFIX {
+            return Err(format!(
+                "SS58 address {} has network prefix {}, expected {}",
+                account, prefix, SS58_PREFIX
+            ));
+        }
+        Ok(Account(account.to_string()))
+    }
+}
+
+impl<'de> Deserialize<'de> for Account {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Account, D::Error> {
+        deserialize_validated(deserializer)
+    }
+}
+
+/// Unchecked, for values read from the chain. Parse inputs instead.
 impl From<String> for Account {
     fn from(account: String) -> Account {
         Account(account)
@@ -92,7 +163,7 @@ impl Account {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Copy, Debug, Deref, DerefMut)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Deref, DerefMut, ToSchema)]
 pub struct MarketId(u64);
 
 impl From<u64> for MarketId {
@@ -107,7 +178,7 @@ impl From<MarketId> for u64 {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Copy, Debug, Deref, DerefMut)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Deref, DerefMut, ToSchema)]
 pub struct ClassId(u64);
 
 impl From<u64> for ClassId {
@@ -122,7 +193,7 @@ impl From<ClassId> for u64 {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Copy, Debug, Deref, DerefMut)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Deref, DerefMut, ToSchema)]
 pub struct AssetId(u64);
 
 impl From<u64> for AssetId {
@@ -137,7 +208,7 @@ impl From<AssetId> for u64 {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Copy, Debug, Deref, DerefMut)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Deref, DerefMut, ToSchema)]
 pub struct Balance(u128);
 
 impl From<u128> for Balance {
@@ -152,7 +223,7 @@ impl From<Balance> for u128 {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Copy, Debug, Deref, DerefMut)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Deref, DerefMut, ToSchema)]
 pub struct Amount(i128);
 
 impl From<i128> for Amount {
@@ -167,9 +238,32 @@ impl From<Amount> for i128 {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, Deref, DerefMut)]
+/// Hex encoded 32 byte bundle hash, with or without `0x`
+#[derive(Serialize, Clone, Debug, Deref, ToSchema)]
 pub struct BundleId(String);
 
+impl FromStr for BundleId {
+    type Err = String;
+
+    fn from_str(bundle_id: &str) -> Result<BundleId, String> {
+        let hex = bundle_id.strip_prefix("0x").unwrap_or(bundle_id);
+        if hex.len() != 64 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!(
+                "invalid bundle id {}: expected 32 bytes of hex",
+                bundle_id
+            ));
+        }
+        Ok(BundleId(bundle_id.to_string()))
+    }
+}
+
+impl<'de> Deserialize<'de> for BundleId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<BundleId, D::Error> {
+        deserialize_validated(deserializer)
+    }
+}
+
+/// Unchecked, for values read from the chain. Parse inputs instead.
 impl From<String> for BundleId {
     fn from(bundleid: String) -> BundleId {
         BundleId(bundleid)
@@ -196,7 +290,7 @@ impl FromIterator<char> for BundleId {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, Deref, DerefMut)]
+#[derive(Serialize, Deserialize, Clone, Debug, Deref, DerefMut, ToSchema)]
 pub struct ValidatorId(String);
 
 impl From<String> for ValidatorId {
@@ -217,8 +311,23 @@ impl ValidatorId {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, Deref, DerefMut)]
+/// CIDv0 (base58btc) or multibase encoded CIDv1
+#[derive(Serialize, Clone, Debug, Deref, ToSchema)]
 pub struct Cid(String);
+impl FromStr for Cid {
+    type Err = String;
+
+    fn from_str(cid: &str) -> Result<Cid, String> {
+        cid::Cid::from_str(cid).map_err(|e| format!("invalid cid {}: {}", cid, e))?;
+        Ok(Cid(cid.to_string()))
+    }
+}
+impl<'de> Deserialize<'de> for Cid {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Cid, D::Error> {
+        deserialize_validated(deserializer)
+    }
+}
+/// Unchecked, for values read from the chain. Parse inputs instead.
 impl From<String> for Cid {
     fn from(cid: String) -> Cid {
         Cid(cid.clone())
@@ -235,8 +344,29 @@ impl Cid {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, Deref, DerefMut)]
+/// Name stored on chain, at most [`MAX_NAME_LENGTH`] bytes
+#[derive(Serialize, Clone, Debug, Deref, ToSchema)]
 pub struct Name(String);
+impl FromStr for Name {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Name, String> {
+        if name.len() > MAX_NAME_LENGTH {
+            return Err(format!(
+                "name is {} bytes long, the limit is {}",
+                name.len(),
+                MAX_NAME_LENGTH
+            ));
+        }
+        Ok(Name(name.to_string()))
+    }
+}
+impl<'de> Deserialize<'de> for Name {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Name, D::Error> {
+        deserialize_validated(deserializer)
+    }
+}
+/// Unchecked, for values read from the chain. Parse inputs instead.
 impl From<String> for Name {
     fn from(name: String) -> Name {
         Name(name.clone())
@@ -253,8 +383,31 @@ impl Name {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, Deref, DerefMut)]
+/// Base58 encoded libp2p peer id, at most [`MAX_PEER_ID_LENGTH`] bytes
+#[derive(Serialize, Clone, Debug, Deref, ToSchema)]
 pub struct PeerId(String);
+impl FromStr for PeerId {
+    type Err = String;
+
+    fn from_str(peer_id: &str) -> Result<PeerId, String> {
+        if peer_id.len() > MAX_PEER_ID_LENGTH {
+            return Err(format!(
+                "peer id is {} bytes long, the limit is {}",
+                peer_id.len(),
+                MAX_PEER_ID_LENGTH
+            ));
+        }
+        libp2p_identity::PeerId::from_str(peer_id)
+            .map_err(|e| format!("invalid peer id {}: {}", peer_id, e))?;
+        Ok(PeerId(peer_id.to_string()))
+    }
+}
+impl<'de> Deserialize<'de> for PeerId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<PeerId, D::Error> {
+        deserialize_validated(deserializer)
+    }
+}
+/// Unchecked, for values read from the chain. Parse inputs instead.
 impl From<String> for PeerId {
     fn from(peer_id: String) -> PeerId {
         PeerId(peer_id.clone())
@@ -271,7 +424,7 @@ impl PeerId {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Copy, Debug, Deref, DerefMut)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Deref, DerefMut, ToSchema)]
 pub struct PoolId(u32);
 impl From<u32> for PoolId {
     fn from(id: u32) -> PoolId {
@@ -284,7 +437,7 @@ impl From<PoolId> for u32 {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Copy, Debug, Deref, DerefMut)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Deref, DerefMut, ToSchema)]
 pub struct ReplicationFactor(u16);
 impl From<u16> for ReplicationFactor {
     fn from(id: u16) -> ReplicationFactor {
@@ -355,3 +508,70 @@ pub fn transform_storage_output(storers: Vec<subxt::utils::AccountId32>) -> Vec<
 pub fn remove_decimals_from_u256(value: U256, decimals: u32) -> u128 {
     return value.div(10_u128.pow(decimals) as u128).as_u128();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALICE: &str = "5GrwvaEF5zXb26Fz9rcQpDWS5C5ZK1oKxPhM6AwGgHyPtb6t";
+    const CID_V0: &str = "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG";
+    const CID_V1: &str = "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi";
+    const PEER_ID: &str = "QmNnooDu7bfjPFoTZYxMNLWUQJyrVwtbZg5gBMjTezGAJN";
+
+    fn from_json<T: serde::de::DeserializeOwned>(value: &str) -> Result<T, String> {
+        validated(|| serde_json::from_str(&format!("\"{}\"", value))).map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn accounts() {
+        assert!(Account::from_str(ALICE).is_ok());
+        assert!(from_json::<Account>(ALICE).is_ok());
+        // Polkadot prefix
+        let polkadot = "15oF4uVJwmo4TdGW7VfQxNLavjCXviqxT9S1MgbjMNHr6Sp5";
+        let err = Account::from_str(polkadot).unwrap_err();
+        assert!(err.contains("network prefix 0"), "{}", err);
+        assert!(Account::from_str("").is_err());
+        assert!(from_json::<Account>("not an address").is_err());
+    }
+
+    #[test]
+    fn cids() {
+        assert!(Cid::from_str(CID_V0).is_ok());
+        assert!(Cid::from_str(CID_V1).is_ok());
+        assert!(Cid::from_str("").is_err());
+        assert!(from_json::<Cid>("Qm123").is_err());
+    }
+
+    #[test]
+    fn peer_ids() {
+        assert!(PeerId::from_str(PEER_ID).is_ok());
+        assert!(from_json::<PeerId>(PEER_ID).is_ok());
+        assert!(PeerId::from_str("not a peer id").is_err());
+        let long = "1".repeat(MAX_PEER_ID_LENGTH + 1);
+        let err = PeerId::from_str(&long).unwrap_err();
+        assert!(err.contains("limit"), "{}", err);
+    }
+
+    #[test]
+    fn names_and_bundle_ids() {
+        assert!(Name::from_str(&"a".repeat(MAX_NAME_LENGTH)).is_ok());
+        assert!(from_json::<Name>(&"a".repeat(MAX_NAME_LENGTH + 1)).is_err());
+        let hash = "ab".repeat(32);
+        assert!(BundleId::from_str(&hash).is_ok());
+        assert!(BundleId::from_str(&format!("0x{}", hash)).is_ok());
+        assert!(BundleId::from_str(&hash[2..]).is_err());
+        assert!(BundleId::from_str(&"zz".repeat(32)).is_err());
+    }
+
+    #[test]
+    fn outputs_read_as_is() {
+        // Chain values, like the empty cid of a manifest, do not validate
+        let cid: Cid = serde_json::from_str("\"\"").unwrap();
+        assert_eq!(cid.as_str(), "");
+        let account: Account = serde_json::from_str("\"not an address\"").unwrap();
+        assert_eq!(account.as_str(), "not an address");
+        // The validation only lasts for the deserialization it wraps
+        assert!(from_json::<Cid>("").is_err());
+        assert!(serde_json::from_str::<Cid>("\"\"").is_ok());
+    }
+}
@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct GetRateLimitsInput {
+    pub key: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct GetRateLimitsOutput {
+    pub buckets: Vec<RateLimitBucket>,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct RateLimitBucket {
+    /// One of `api_key`, `ip` or `account`
+    pub key_type: String,
+    pub key: String,
+    /// One of `read` or `sign`
+    pub route_class: String,
+    pub available: u32,
+    pub capacity: u32,
+}
@@ -0,0 +1,54 @@
+use crate::primitives::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct GetReplicationReportInput {
+    pub pool_id: Option<PoolId>,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct GetReplicationReportOutput {
+    pub pools: Vec<PoolReplication>,
+    /// Manifests that need more storers
+    pub manifests: Vec<ManifestReplication>,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct PoolReplication {
+    pub pool_id: PoolId,
+    pub manifests: u64,
+    pub under_replicated: u64,
+    /// Manifests without any storer
+    pub unstored: u64,
+    /// Storers missing over all the manifests of the pool
+    pub deficit: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct ManifestReplication {
+    pub pool_id: PoolId,
+    pub cid: Cid,
+    /// Sum of the replication factors requested by the uploaders
+    pub replication_factor: u64,
+    pub storers: u64,
+    /// Storers missing to reach the replication factor of every uploader
+    pub deficit: u64,
+}
+
+/// Sent on `/ws` when a manifest loses storers
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct ReplicationAlert {
+    pub kind: ReplicationAlertKind,
+    pub manifest: ManifestReplication,
+    /// Storers before the drop
+    pub previous_storers: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, ToSchema)]
+pub enum ReplicationAlertKind {
+    /// Fell below its replication factor
+    UnderReplicated,
+    /// Lost all its storers
+    Unstored,
+}
@@ -0,0 +1,50 @@
+use crate::account::KeyScheme;
+use crate::primitives::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+// SIGN-IN NONCE
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct SessionNonceInput {
+    pub account: Account,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct SessionNonceOutput {
+    pub account: Account,
+    pub nonce: String,
+    /// Message to sign with the account, as `/account/sign` does
+    pub message: String,
+    /// Unix timestamp after which the nonce cannot sign in anymore
+    pub expires_at: u64,
+}
+
+// SIGN IN
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct SignInInput {
+    pub account: Account,
+    pub nonce: String,
+    /// Hex encoded signature of the nonce message
+    pub signature: String,
+    #[serde(default)]
+    pub scheme: KeyScheme,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct SignInOutput {
+    pub account: Account,
+    /// Bearer token whose subject is the account
+    pub token: String,
+    /// Unix timestamp the token expires at
+    pub expires_at: u64,
+}
+
+// SESSION INFO
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct SessionInfoOutput {
+    /// Account the bearer token was issued to
+    pub account: Account,
+}
@@ -1,24 +1,25 @@
 use crate::primitives::*;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct AddValidatorInput {
     pub seed: Seed,
     pub validator_id: ValidatorId,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct AddValidatorOutput {
     pub validator_id: ValidatorId,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct RemoveValidatorInput {
     pub seed: Seed,
     pub validator_id: ValidatorId,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct RemoveValidatorOutput {
     pub validator_id: ValidatorId,
 }
@@ -0,0 +1,68 @@
+use actix_web::{web::Data, App, HttpServer};
+use rand::distributions::{Alphanumeric, DistString};
+use sugarfunge_api::auth::Scope;
+use sugarfunge_api::config::{ApiKeyConfig, Config};
+use sugarfunge_api::{args::Args, routes, state::AppState};
+use sugarfunge_api_client::{Client, ClientError};
+use sugarfunge_api_types::endpoints::Endpoint;
+use url::Url;
+
+/// Where requests go: a running gateway, or the gateway routes served
+/// in-process against a node
+pub struct Backend {
+    client: Client,
+}
+
+impl Backend {
+    pub fn gateway(url: Url, api_key: Option<String>) -> Backend {
+        let client = Client::new(url);
+        Backend {
+            client: match api_key {
+                Some(api_key) => client.with_api_key(api_key),
+                None => client,
+            },
+        }
+    }
+
+    /// Connect to the node with the server configuration (file and
+    /// environment) and serve the routes on a loopback port for the time of
+    /// the command, behind an API key only this process knows
+    pub async fn node(url: Url, config: Option<std::path::PathBuf>) -> std::io::Result<Backend> {
+        let args = Args {
+            config,
+            node_server: Some(url),
+            listen: None,
+            agent: false,
+            key: None,
+            pool: None,
+        };
+        let mut config = Config::load(&args)?;
+        let api_key = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+        config.auth.enabled = true;
+        config.auth.api_keys = vec![ApiKeyConfig {
+            name: "cli".into(),
+            key: api_key.clone(),
+            scopes: vec![Scope::Admin],
+        }];
+        config.export_contract_env();
+        let state = Data::new(AppState::connect(config).await?);
+
+        let server = HttpServer::new(move || {
+            App::new()
+                .app_data(state.clone())
+                .configure(routes::configure)
+        })
+        .workers(1)
+        .bind(("127.0.0.1", 0))?;
+        let addr = server.addrs()[0];
+        actix_web::rt::spawn(server.run());
+
+        let url = Url::parse(&format!("http://{}", addr))
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+        Ok(Backend::gateway(url, Some(api_key)))
+    }
+
+    pub async fn call<E: Endpoint>(&self, input: &E::Input) -> Result<E::Output, ClientError> {
+        self.client.call::<E>(input).await
+    }
+}
@@ -0,0 +1,418 @@
+use crate::backend::Backend;
+use crate::keyring::Keyring;
+use crate::output::Format;
+use clap::{Subcommand, ValueEnum};
+use std::error::Error;
+use sugarfunge_api_types::account::*;
+use sugarfunge_api_types::asset::*;
+use sugarfunge_api_types::bundle::*;
+use sugarfunge_api_types::challenge::*;
+use sugarfunge_api_types::endpoints;
+use sugarfunge_api_types::fula::*;
+use sugarfunge_api_types::pool::*;
+use sugarfunge_api_types::primitives::*;
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    #[command(subcommand)]
+    Pool(PoolCommand),
+    #[command(subcommand)]
+    Manifest(ManifestCommand),
+    #[command(subcommand)]
+    Challenge(ChallengeCommand),
+    #[command(subcommand)]
+    Asset(AssetCommand),
+    #[command(subcommand)]
+    Bundle(BundleCommand),
+    #[command(subcommand)]
+    Account(AccountCommand),
+}
+
+#[derive(Subcommand, Debug)]
+pub enum PoolCommand {
+    /// Create a pool owned by the signing account
+    Create {
+        #[arg(long)]
+        name: Name,
+        #[arg(long)]
+        peer_id: PeerId,
+        #[arg(long)]
+        region: String,
+    },
+    /// Request to join a pool
+    Join {
+        #[arg(long)]
+        pool_id: u32,
+        #[arg(long)]
+        peer_id: PeerId,
+    },
+    /// Vote on the join request of an account
+    Vote {
+        #[arg(long)]
+        pool_id: u32,
+        #[arg(long)]
+        account: Account,
+        #[arg(long)]
+        peer_id: PeerId,
+        #[arg(value_enum)]
+        vote: Vote,
+    },
+    List {
+        #[arg(long)]
+        region: Option<String>,
+    },
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum Vote {
+    Approve,
+    Reject,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ManifestCommand {
+    /// Upload the manifest of a cid to a pool
+    Upload {
+        #[arg(long)]
+        cid: Cid,
+        #[arg(long)]
+        pool_id: u32,
+        #[arg(long, default_value_t = 1)]
+        replication_factor: u16,
+        /// JSON metadata stored with the manifest
+        #[arg(long, value_parser = parse_json, default_value = "{}")]
+        metadata: serde_json::Value,
+    },
+    /// Claim the storage of an uploaded manifest
+    Store {
+        #[arg(long)]
+        cid: Cid,
+        #[arg(long)]
+        pool_id: u32,
+    },
+    /// Remove a manifest uploaded by the signing account
+    Remove {
+        #[arg(long)]
+        cid: Cid,
+        #[arg(long)]
+        pool_id: u32,
+    },
+    List {
+        #[arg(long)]
+        pool_id: Option<u32>,
+        #[arg(long)]
+        uploader: Option<Account>,
+        #[arg(long)]
+        storer: Option<Account>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ChallengeCommand {
+    /// Challenge a random storer
+    Generate,
+    /// Answer a challenge with the cids stored by the signing account
+    Verify {
+        #[arg(long)]
+        pool_id: u32,
+        #[arg(long, required = true, value_delimiter = ',')]
+        cids: Vec<Cid>,
+        #[arg(long)]
+        class_id: u64,
+        #[arg(long)]
+        asset_id: u64,
+    },
+    List,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum AssetCommand {
+    Mint {
+        #[arg(long)]
+        to: Account,
+        #[arg(long)]
+        class_id: u64,
+        #[arg(long)]
+        asset_id: u64,
+        #[arg(long)]
+        amount: u128,
+    },
+    Balance {
+        #[arg(long)]
+        account: Account,
+        #[arg(long)]
+        class_id: u64,
+        #[arg(long)]
+        asset_id: u64,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum BundleCommand {
+    Register {
+        #[arg(long)]
+        class_id: u64,
+        #[arg(long)]
+        asset_id: u64,
+        /// JSON `{"class_ids": [..], "asset_ids": [[..]], "amounts": [[..]]}`
+        #[arg(long, value_parser = parse_json)]
+        schema: serde_json::Value,
+        #[arg(long, value_parser = parse_json, default_value = "{}")]
+        metadata: serde_json::Value,
+    },
+    Mint {
+        #[arg(long)]
+        from: Account,
+        #[arg(long)]
+        to: Account,
+        #[arg(long)]
+        bundle_id: BundleId,
+        #[arg(long)]
+        amount: u128,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum AccountCommand {
+    /// Create a new account, printing its seed unless saved to the keyring
+    Create {
+        /// Store the seed in the keyring under this name
+        #[arg(long)]
+        save: Option<String>,
+        /// Hard (`//name`) and soft (`/name`) junctions applied to the mnemonic
+        #[arg(long)]
+        derivation_path: Option<String>,
+        /// Network prefix of the printed address
+        #[arg(long)]
+        ss58_prefix: Option<u16>,
+    },
+    /// Transfer native balance from the signing account
+    Fund {
+        #[arg(long)]
+        to: Account,
+        #[arg(long)]
+        amount: u128,
+        /// Fail rather than reap the signing account
+        #[arg(long)]
+        keep_alive: bool,
+        /// Transfer even when it would reap an account
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+fn parse_json(value: &str) -> Result<serde_json::Value, serde_json::Error> {
+    serde_json::from_str(value)
+}
+
+impl Command {
+    pub async fn run(
+        self,
+        backend: &Backend,
+        keyring: &Keyring,
+        format: Format,
+    ) -> Result<(), Box<dyn Error>> {
+        match self {
+            Command::Pool(PoolCommand::Create {
+                name,
+                peer_id,
+                region,
+            }) => {
+                let input = CreatePoolInput {
+                    seed: keyring.seed()?,
+                    pool_name: name,
+                    peer_id,
+                    region,
+                };
+                format.print(&backend.call::<endpoints::PoolCreate>(&input).await?)?;
+            }
+            Command::Pool(PoolCommand::Join { pool_id, peer_id }) => {
+                let input = JoinPoolInput {
+                    seed: keyring.seed()?,
+                    pool_id: pool_id.into(),
+                    peer_id,
+                };
+                format.print(&backend.call::<endpoints::PoolJoin>(&input).await?)?;
+            }
+            Command::Pool(PoolCommand::Vote {
+                pool_id,
+                account,
+                peer_id,
+                vote,
+            }) => {
+                let input = VoteInput {
+                    seed: keyring.seed()?,
+                    pool_id: pool_id.into(),
+                    account,
+                    vote_value: matches!(vote, Vote::Approve),
+                    peer_id,
+                };
+                format.print(&backend.call::<endpoints::PoolVote>(&input).await?)?;
+            }
+            Command::Pool(PoolCommand::List { region }) => {
+                let input = GetAllPoolInput { region };
+                format.print(&backend.call::<endpoints::Pools>(&input).await?)?;
+            }
+            Command::Manifest(ManifestCommand::Upload {
+                cid,
+                pool_id,
+                replication_factor,
+                metadata,
+            }) => {
+                let input = UploadManifestInput {
+                    seed: keyring.seed()?,
+                    manifest_metadata: metadata,
+                    cid,
+                    pool_id: pool_id.into(),
+                    replication_factor: replication_factor.into(),
+                };
+                format.print(&backend.call::<endpoints::ManifestUpload>(&input).await?)?;
+            }
+            Command::Manifest(ManifestCommand::Store { cid, pool_id }) => {
+                let input = StorageManifestInput {
+                    seed: keyring.seed()?,
+                    cid,
+                    pool_id: pool_id.into(),
+                };
+                format.print(&backend.call::<endpoints::ManifestStorage>(&input).await?)?;
+            }
+            Command::Manifest(ManifestCommand::Remove { cid, pool_id }) => {
+                let input = RemoveManifestInput {
+                    seed: keyring.seed()?,
+                    cid,
+                    pool_id: pool_id.into(),
+                };
+                format.print(&backend.call::<endpoints::ManifestRemove>(&input).await?)?;
+            }
+            Command::Manifest(ManifestCommand::List {
+                pool_id,
+                uploader,
+                storer,
+            }) => {
+                let input = GetAllManifestsInput {
+                    pool_id: pool_id.map(PoolId::from),
+                    uploader,
+                    storer,
+                };
+                format.print(&backend.call::<endpoints::Manifests>(&input).await?)?;
+            }
+            Command::Challenge(ChallengeCommand::Generate) => {
+                let input = GenerateChallengeInput {
+                    seed: keyring.seed()?,
+                };
+                format.print(&backend.call::<endpoints::ChallengeGenerate>(&input).await?)?;
+            }
+            Command::Challenge(ChallengeCommand::Verify {
+                pool_id,
+                cids,
+                class_id,
+                asset_id,
+            }) => {
+                let input = VerifyChallengeInput {
+                    seed: keyring.seed()?,
+                    pool_id: pool_id.into(),
+                    cids,
+                    class_id: class_id.into(),
+                    asset_id: asset_id.into(),
+                };
+                format.print(&backend.call::<endpoints::ChallengeVerify>(&input).await?)?;
+            }
+            Command::Challenge(ChallengeCommand::List) => {
+                format.print(&backend.call::<endpoints::Challenges>(&()).await?)?;
+            }
+            Command::Asset(AssetCommand::Mint {
+                to,
+                class_id,
+                asset_id,
+                amount,
+            }) => {
+                let input = MintInput {
+                    seed: keyring.seed()?,
+                    to,
+                    class_id: class_id.into(),
+                    asset_id: asset_id.into(),
+                    amount: amount.into(),
+                };
+                format.print(&backend.call::<endpoints::AssetMint>(&input).await?)?;
+            }
+            Command::Asset(AssetCommand::Balance {
+                account,
+                class_id,
+                asset_id,
+            }) => {
+                let input = AssetBalanceInput {
+                    account,
+                    class_id: class_id.into(),
+                    asset_id: asset_id.into(),
+                };
+                format.print(&backend.call::<endpoints::AssetBalance>(&input).await?)?;
+            }
+            Command::Bundle(BundleCommand::Register {
+                class_id,
+                asset_id,
+                schema,
+                metadata,
+            }) => {
+                let input = RegisterBundleInput {
+                    seed: keyring.seed()?,
+                    class_id: class_id.into(),
+                    asset_id: asset_id.into(),
+                    schema: serde_json::from_value(schema)
+                        .map_err(|e| format!("invalid --schema: {}", e))?,
+                    metadata,
+                };
+                format.print(&backend.call::<endpoints::BundleRegister>(&input).await?)?;
+            }
+            Command::Bundle(BundleCommand::Mint {
+                from,
+                to,
+                bundle_id,
+                amount,
+            }) => {
+                let input = MintBundleInput {
+                    seed: keyring.seed()?,
+                    from,
+                    to,
+                    bundle_id,
+                    amount: amount.into(),
+                };
+                format.print(&backend.call::<endpoints::BundleMint>(&input).await?)?;
+            }
+            Command::Account(AccountCommand::Create {
+                save,
+                derivation_path,
+                ss58_prefix,
+            }) => {
+                let input = CreateAccountInput {
+                    derivation_path,
+                    ss58_prefix,
+                    ..Default::default()
+                };
+                let output = backend.call::<endpoints::AccountCreate>(&input).await?;
+                match save {
+                    Some(name) => {
+                        keyring.save(&name, &output.seed)?;
+                        format.print(&serde_json::json!({ "account": output.account }))?;
+                    }
+                    None => format.print(&output)?,
+                }
+            }
+            Command::Account(AccountCommand::Fund {
+                to,
+                amount,
+                keep_alive,
+                force,
+            }) => {
+                let input = FundAccountInput {
+                    seed: keyring.seed()?,
+                    to,
+                    amount: amount.into(),
+                    keep_alive,
+                    force,
+                };
+                format.print(&backend.call::<endpoints::AccountFund>(&input).await?)?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,101 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use sugarfunge_api_types::primitives::Seed;
+
+/// Seed used instead of the keyring, for scripts and CI
+pub const SEED_ENV: &str = "SUGARFUNGE_SEED";
+
+/// TOML file mapping key names to seeds, e.g. `alice = "//Alice"`.
+/// Seeds never go through argv, where other users could read them.
+pub struct Keyring {
+    path: PathBuf,
+    key: String,
+}
+
+impl Keyring {
+    pub fn new(path: Option<PathBuf>, key: String) -> Keyring {
+        let path = path.unwrap_or_else(|| {
+            dirs::config_dir()
+                .unwrap_or_default()
+                .join("sugarfunge")
+                .join("keyring.toml")
+        });
+        Keyring { path, key }
+    }
+
+    /// Seed of the selected key, `SUGARFUNGE_SEED` taking precedence
+    pub fn seed(&self) -> Result<Seed, String> {
+        if let Ok(seed) = std::env::var(SEED_ENV) {
+            return Ok(Seed::from(seed));
+        }
+        self.read()?
+            .remove(&self.key)
+            .map(Seed::from)
+            .ok_or_else(|| {
+                format!(
+                    "no key {} in {}, add it or set {}",
+                    self.key,
+                    self.path.display(),
+                    SEED_ENV
+                )
+            })
+    }
+
+    /// Store a seed under `name`, creating the file readable by its owner only
+    pub fn save(&self, name: &str, seed: &Seed) -> Result<(), String> {
+        let mut keys = if self.path.exists() {
+            self.read()?
+        } else {
+            BTreeMap::new()
+        };
+        if keys.contains_key(name) {
+            return Err(format!(
+                "key {} already exists in {}",
+                name,
+                self.path.display()
+            ));
+        }
+        keys.insert(name.to_string(), seed.as_str().to_string());
+
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).map_err(|e| self.error(e))?;
+        }
+        let content = toml::to_string(&keys).map_err(|e| self.error(e))?;
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        options
+            .open(&self.path)
+            .and_then(|mut file| file.write_all(content.as_bytes()))
+            .map_err(|e| self.error(e))
+    }
+
+    fn read(&self) -> Result<BTreeMap<String, String>, String> {
+        warn_if_shared(&self.path);
+        let content = fs::read_to_string(&self.path).map_err(|e| self.error(e))?;
+        toml::from_str(&content).map_err(|e| self.error(e))
+    }
+
+    fn error(&self, e: impl std::fmt::Display) -> String {
+        format!("keyring {}: {}", self.path.display(), e)
+    }
+}
+
+#[cfg(unix)]
+fn warn_if_shared(path: &Path) {
+    use std::os::unix::fs::PermissionsExt;
+    if let Ok(metadata) = fs::metadata(path) {
+        if metadata.permissions().mode() & 0o077 != 0 {
+            eprintln!(
+                "warning: keyring {} is readable by other users, run chmod 600 on it",
+                path.display()
+            );
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn warn_if_shared(_path: &Path) {}
@@ -0,0 +1,79 @@
+use backend::Backend;
+use clap::Parser;
+use commands::Command;
+use keyring::Keyring;
+use output::Format;
+use std::path::PathBuf;
+use url::Url;
+
+mod backend;
+mod commands;
+mod keyring;
+mod output;
+
+/// Operate pools, manifests and assets through the SugarFunge API
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Cli {
+    /// SugarFunge API gateway
+    #[arg(
+        long,
+        env = "SUGARFUNGE_API_URL",
+        default_value = "http://127.0.0.1:4000",
+        global = true
+    )]
+    api: Url,
+    /// API key sent to the gateway
+    #[arg(
+        long,
+        env = "SUGARFUNGE_API_KEY",
+        hide_env_values = true,
+        global = true
+    )]
+    api_key: Option<String>,
+    /// Talk directly to this node instead of the gateway, takes precedence over --api
+    #[arg(long, global = true)]
+    node: Option<Url>,
+    /// Server TOML configuration used with --node (token ids, refunds)
+    #[arg(long, requires = "node", global = true)]
+    config: Option<PathBuf>,
+    /// Keyring file [default: <config dir>/sugarfunge/keyring.toml]
+    #[arg(long, env = "SUGARFUNGE_KEYRING", global = true)]
+    keyring: Option<PathBuf>,
+    /// Name of the keyring entry signing the request, ignored when SUGARFUNGE_SEED is set
+    #[arg(
+        short,
+        long,
+        env = "SUGARFUNGE_KEY",
+        default_value = "default",
+        global = true
+    )]
+    key: String,
+    #[arg(short, long, value_enum, default_value = "table", global = true)]
+    output: Format,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[actix_web::main]
+async fn main() {
+    let cli = Cli::parse();
+
+    let backend = match cli.node {
+        Some(node) => match Backend::node(node, cli.config).await {
+            Ok(backend) => backend,
+            Err(e) => exit(e),
+        },
+        None => Backend::gateway(cli.api, cli.api_key),
+    };
+    let keyring = Keyring::new(cli.keyring, cli.key);
+
+    if let Err(e) = cli.command.run(&backend, &keyring, cli.output).await {
+        exit(e);
+    }
+}
+
+fn exit(e: impl std::fmt::Display) -> ! {
+    eprintln!("error: {}", e);
+    std::process::exit(1)
+}
@@ -0,0 +1,73 @@
+use clap::ValueEnum;
+use comfy_table::{presets::UTF8_FULL_CONDENSED, Table};
+use serde::Serialize;
+use serde_json::Value;
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum Format {
+    Table,
+    Json,
+}
+
+impl Format {
+    pub fn print<T: Serialize>(&self, output: &T) -> Result<(), serde_json::Error> {
+        let value = serde_json::to_value(output)?;
+        match self {
+            Format::Json => println!("{}", serde_json::to_string_pretty(&value)?),
+            Format::Table => println!("{}", table(&value)),
+        }
+        Ok(())
+    }
+}
+
+/// Lists (or outputs wrapping a single list, like `{"pools": [...]}`) become
+/// one row per item, anything else one row per field
+fn table(value: &Value) -> Table {
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL_CONDENSED);
+
+    let rows = match value {
+        Value::Array(rows) => Some(rows),
+        Value::Object(fields) if fields.len() == 1 => fields.values().next().unwrap().as_array(),
+        _ => None,
+    };
+    match rows {
+        Some(rows) if rows.iter().all(Value::is_object) => {
+            let mut columns: Vec<&String> = Vec::new();
+            for row in rows.iter().filter_map(Value::as_object) {
+                for key in row.keys() {
+                    if !columns.contains(&key) {
+                        columns.push(key);
+                    }
+                }
+            }
+            table.set_header(columns.iter().map(|column| column.as_str()));
+            for row in rows.iter() {
+                table.add_row(columns.iter().map(|column| cell(&row[column.as_str()])));
+            }
+        }
+        _ => match value {
+            Value::Object(fields) => {
+                table.set_header(vec!["field", "value"]);
+                for (key, value) in fields.iter() {
+                    table.add_row(vec![key.clone(), cell(value)]);
+                }
+            }
+            value => {
+                table.add_row(vec![cell(value)]);
+            }
+        },
+    }
+    table
+}
+
+fn cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(value) => value.clone(),
+        Value::Array(items) if !items.iter().any(|item| item.is_object() || item.is_array()) => {
+            items.iter().map(cell).collect::<Vec<_>>().join(", ")
+        }
+        value => value.to_string(),
+    }
+}
@@ -1,63 +1,374 @@
+use crate::chain::{Signer, Storage};
+use crate::challenge::open_challenges_at;
+use crate::fula::{
+    decode_manifest_metadata, transform_vec_uploader_data_runtime_to_vec_uploader_data,
+};
+use crate::replication::fetch_manifests_at;
 use crate::state::*;
 use crate::util::*;
-use actix_web::{error, web, HttpRequest, HttpResponse};
-use rand::prelude::*;
+use actix_web::{error, web, HttpResponse};
+use bip39::{Language, Mnemonic};
+use codec::Decode;
 use serde_json::json;
+use sp_core::crypto::{Ss58AddressFormat, Ss58Codec};
 use sp_core::Pair;
 use sp_runtime::traits::IdentifyAccount;
+use std::collections::HashMap;
 use subxt::tx::PairSigner;
+use subxt::utils::AccountId32;
 use sugarfunge_api_types::account::*;
+use sugarfunge_api_types::activity::{GetAccountActivityInput, GetAccountActivityOutput};
+use sugarfunge_api_types::asset::{AssetInfo, ClassInfo};
+use sugarfunge_api_types::challenge::{ClaimData, OpenChallenge};
+use sugarfunge_api_types::pool::{PoolRequestData, PoolUserData};
 use sugarfunge_api_types::primitives::*;
 use sugarfunge_api_types::sugarfunge;
 
-/// Generate a unique seed and its associated account
-pub async fn create(_req: HttpRequest) -> error::Result<HttpResponse> {
-    let seed = rand::thread_rng().gen::<[u8; 32]>();
-    let seed = hex::encode(seed);
-    let seed = format!("//{}", seed);
-    let seed = Seed::from(seed);
-    let pair = get_pair_from_seed(&seed)?;
-    let account: sp_core::sr25519::Public = pair.public();
-    let account = account.into_account();
+/// Generate a mnemonic, or import one, and derive its account. Without a
+/// body an sr25519 account of a new mnemonic is created.
+#[utoipa::path(
+    post,
+    path = "/account/create",
+    tag = "account",
+    request_body = CreateAccountInput,
+    responses(
+        (status = 200, body = CreateAccountOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["read"]), ("bearer" = ["read"])),
+)]
+pub async fn create(req: Json<Option<CreateAccountInput>>) -> error::Result<HttpResponse> {
+    let req = req.0.unwrap_or_default();
+    let mnemonic = match &req.mnemonic {
+        Some(mnemonic) => mnemonic.split_whitespace().collect::<Vec<_>>().join(" "),
+        None => sp_core::sr25519::Pair::generate_with_phrase(None).1,
+    };
+    if let Err(e) = Mnemonic::validate(&mnemonic, Language::English) {
+        return Ok(HttpResponse::BadRequest().json(RequestError {
+            message: json!("Invalid mnemonic"),
+            description: format!("Not an English BIP39 mnemonic: {}", e),
+        }));
+    }
+    let derivation_path = req.derivation_path.clone().unwrap_or_default();
+    if !derivation_path.is_empty()
+        && (!derivation_path.starts_with('/') || derivation_path.contains("///"))
+    {
+        return Ok(HttpResponse::BadRequest().json(RequestError {
+            message: json!("Invalid derivation path"),
+            description: format!(
+                "{} is not a sequence of //hard and /soft junctions",
+                derivation_path
+            ),
+        }));
+    }
+    let seed = Seed::from(format!("{}{}", mnemonic, derivation_path));
+    let account = derive_account(&seed, req.scheme)?;
+    let (address, ss58_prefix) = ss58_address(&account, req.ss58_prefix)?;
     Ok(HttpResponse::Ok().json(CreateAccountOutput {
         seed,
-        account: Account::from(format!("{}", account)),
+        account: Account::from(account),
+        mnemonic,
+        derivation_path,
+        scheme: req.scheme,
+        address,
+        ss58_prefix,
     }))
 }
 
 /// Compute account from seed
-pub async fn seeded(req: web::Json<SeededAccountInput>) -> error::Result<HttpResponse> {
-    let pair = get_pair_from_seed(&req.seed)?;
-    let account = pair.public().into_account();
+#[utoipa::path(
+    post,
+    path = "/account/seeded",
+    tag = "account",
+    request_body = SeededAccountInput,
+    responses(
+        (status = 200, body = SeededAccountOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["read"]), ("bearer" = ["read"])),
+)]
+pub async fn seeded(req: Json<SeededAccountInput>) -> error::Result<HttpResponse> {
+    let account = derive_account(&req.seed, req.scheme)?;
+    let (address, ss58_prefix) = ss58_address(&account, req.ss58_prefix)?;
     Ok(HttpResponse::Ok().json(SeededAccountOutput {
         seed: req.seed.clone(),
-        account: Account::from(format!("{}", account)),
+        account: Account::from(account),
+        scheme: req.scheme,
+        address,
+        ss58_prefix,
+    }))
+}
+
+/// Activity of an account, latest first, from the finalized blocks indexed
+#[utoipa::path(
+    post,
+    path = "/account/activity",
+    tag = "account",
+    request_body = GetAccountActivityInput,
+    responses(
+        (status = 200, body = GetAccountActivityOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["read"]), ("bearer" = ["read"])),
+)]
+pub async fn activity(
+    data: web::Data<AppState>,
+    req: Json<GetAccountActivityInput>,
+) -> error::Result<HttpResponse> {
+    let account = AccountId32::try_from(&req.account).map_err(map_account_err)?;
+    let limit = req.limit.unwrap_or(50).clamp(1, 500) as usize;
+    let (activity, next) = data.activity.page(&account, req.before, limit);
+    Ok(HttpResponse::Ok().json(GetAccountActivityOutput {
+        account: req.account.clone(),
+        activity,
+        next,
+        indexed_since: data.activity.since(),
     }))
 }
 
+/// Sign a message with a seed, wrapped in `<Bytes>` as Polkadot wallets do
+#[utoipa::path(
+    post,
+    path = "/account/sign",
+    tag = "account",
+    request_body = SignMessageInput,
+    responses(
+        (status = 200, body = SignMessageOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["read"]), ("bearer" = ["read"])),
+)]
+pub async fn sign(req: Json<SignMessageInput>) -> error::Result<HttpResponse> {
+    let message = wrap_bytes(req.message.as_bytes());
+    let (public, signature) = match req.scheme {
+        KeyScheme::Sr25519 => {
+            let pair = sp_core::sr25519::Pair::from_string(req.seed.as_str(), None)
+                .map_err(map_secret_err)?;
+            (pair.public().0, pair.sign(&message).0)
+        }
+        KeyScheme::Ed25519 => {
+            let pair = sp_core::ed25519::Pair::from_string(req.seed.as_str(), None)
+                .map_err(map_secret_err)?;
+            (pair.public().0, pair.sign(&message).0)
+        }
+    };
+    Ok(HttpResponse::Ok().json(SignMessageOutput {
+        account: Account::from(sp_core::crypto::AccountId32::from(public)),
+        message: req.message.clone(),
+        signature: format!("0x{}", hex::encode(signature)),
+        scheme: req.scheme,
+    }))
+}
+
+/// Verify the signature of a message by an account
+#[utoipa::path(
+    post,
+    path = "/account/verify",
+    tag = "account",
+    request_body = VerifyMessageInput,
+    responses(
+        (status = 200, body = VerifyMessageOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["read"]), ("bearer" = ["read"])),
+)]
+pub async fn verify(req: Json<VerifyMessageInput>) -> error::Result<HttpResponse> {
+    let account = AccountId32::try_from(&req.account).map_err(map_account_err)?;
+    let valid = verify_message(&account, &req.message, &req.signature, req.scheme, req.raw)?;
+    Ok(HttpResponse::Ok().json(VerifyMessageOutput {
+        account: req.account.clone(),
+        valid,
+    }))
+}
+
+/// Wrapping of raw payloads signed by Polkadot wallets, so that a signed
+/// message can never be a valid extrinsic
+fn wrap_bytes(message: &[u8]) -> Vec<u8> {
+    [b"<Bytes>", message, b"</Bytes>"].concat()
+}
+
+/// Check a hex encoded signature of `message` by `account`. Wallets sign the
+/// `<Bytes>` wrapped message, an unwrapped signature is only accepted when
+/// `raw` is set.
+pub fn verify_message(
+    account: &AccountId32,
+    message: &str,
+    signature: &str,
+    scheme: KeyScheme,
+    raw: bool,
+) -> error::Result<bool> {
+    let signature = hex::decode(signature.trim_start_matches("0x"))
+        .ok()
+        .and_then(|signature| <[u8; 64]>::try_from(signature).ok());
+    let signature = match signature {
+        Some(signature) => signature,
+        None => {
+            let req_error = RequestError {
+                message: json!("Invalid signature"),
+                description: "Signatures are 64 hex encoded bytes".into(),
+            };
+            let req_error = serde_json::to_string_pretty(&req_error).unwrap();
+            return Err(error::ErrorBadRequest(req_error));
+        }
+    };
+    let message = if raw {
+        message.as_bytes().to_vec()
+    } else {
+        wrap_bytes(message.as_bytes())
+    };
+    Ok(match scheme {
+        KeyScheme::Sr25519 => sp_core::sr25519::Pair::verify(
+            &sp_core::sr25519::Signature::from_raw(signature),
+            &message,
+            &sp_core::sr25519::Public::from_raw(account.0),
+        ),
+        KeyScheme::Ed25519 => sp_core::ed25519::Pair::verify(
+            &sp_core::ed25519::Signature::from_raw(signature),
+            &message,
+            &sp_core::ed25519::Public::from_raw(account.0),
+        ),
+    })
+}
+
+/// Account of the key pair a secret URI derives with `scheme`, ed25519 only
+/// supports hard junctions
+fn derive_account(seed: &Seed, scheme: KeyScheme) -> error::Result<sp_core::crypto::AccountId32> {
+    let public = match scheme {
+        KeyScheme::Sr25519 => {
+            sp_core::sr25519::Pair::from_string(seed.as_str(), None).map(|pair| pair.public().0)
+        }
+        KeyScheme::Ed25519 => {
+            sp_core::ed25519::Pair::from_string(seed.as_str(), None).map(|pair| pair.public().0)
+        }
+    };
+    public
+        .map(sp_core::crypto::AccountId32::from)
+        .map_err(map_secret_err)
+}
+
+/// Account encoded with `prefix`, or with [`SS58_PREFIX`]
+fn ss58_address(
+    account: &sp_core::crypto::AccountId32,
+    prefix: Option<u16>,
+) -> error::Result<(String, u16)> {
+    let prefix = prefix.unwrap_or(SS58_PREFIX);
+    // SS58 encodes the prefix on 14 bits
+    if prefix > 16383 {
+        let req_error = RequestError {
+            message: json!("Invalid SS58 prefix"),
+            description: format!("SS58 prefix {} is above 16383", prefix),
+        };
+        let req_error = serde_json::to_string_pretty(&req_error).unwrap();
+        return Err(error::ErrorBadRequest(req_error));
+    }
+    let address = account.to_ss58check_with_version(Ss58AddressFormat::custom(prefix));
+    Ok((address, prefix))
+}
+
 /// Fund a given account with amount
+#[utoipa::path(
+    post,
+    path = "/account/fund",
+    tag = "account",
+    request_body = FundAccountInput,
+    responses(
+        (status = 200, body = FundAccountOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["account:write"]), ("bearer" = ["account:write"])),
+)]
 pub async fn fund(
     data: web::Data<AppState>,
-    req: web::Json<FundAccountInput>,
+    req: Json<FundAccountInput>,
 ) -> error::Result<HttpResponse> {
     let pair = get_pair_from_seed(&req.seed)?;
     //let signer = sp_core::sr25519::Pair::try_from(pair).unwrap();
     let signer = PairSigner::new(pair);
     let account = subxt::utils::AccountId32::try_from(&req.to).map_err(map_account_err)?;
-    let account = subxt::utils::MultiAddress::Id(account);
-    let amount_input = req.amount;
+    let amount = u128::from(req.amount);
     let api = &data.api;
 
-    let call = sugarfunge::tx()
-        .balances()
-        .transfer(account, amount_input.into());
+    let existential_deposit = existential_deposit(api).map_err(map_subxt_err)?;
+    let storage = api.storage().await.map_err(map_subxt_err)?;
+    let sender_free = free_balance(&storage, signer.account_id())
+        .await
+        .map_err(map_subxt_err)?;
+    let recipient_free = free_balance(&storage, &account)
+        .await
+        .map_err(map_subxt_err)?;
+    let warnings = transfer_warnings(sender_free, recipient_free, amount, existential_deposit);
+    if !warnings.is_empty() && !req.force {
+        return Ok(refuse_transfer(&warnings, "account::fund"));
+    }
 
-    let result = api
-        .tx()
-        .sign_and_submit_then_watch(&call, &signer, Default::default())
+    let result = submit_transfer(api, &signer, account, amount, req.keep_alive)
+        .await
+        .map_err(map_sf_err)?;
+    match result {
+        Some(event) => Ok(HttpResponse::Ok().json(FundAccountOutput {
+            from: event.from.into(),
+            to: event.to.into(),
+            amount: event.amount.into(),
+            warnings,
+        })),
+        None => Ok(HttpResponse::BadRequest().json(RequestError {
+            message: json!("Failed to find sugarfunge::balances::events::Transfer"),
+            description: "Error in account::fund".to_string(),
+        })),
+    }
+}
+
+/// Transfer the whole transferable balance of the signer
+#[utoipa::path(
+    post,
+    path = "/account/transfer_all",
+    tag = "account",
+    request_body = TransferAllInput,
+    responses(
+        (status = 200, body = FundAccountOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["account:write"]), ("bearer" = ["account:write"])),
+)]
+pub async fn transfer_all(
+    data: web::Data<AppState>,
+    req: Json<TransferAllInput>,
+) -> error::Result<HttpResponse> {
+    let pair = get_pair_from_seed(&req.seed)?;
+    let signer = PairSigner::new(pair);
+    let account = AccountId32::try_from(&req.to).map_err(map_account_err)?;
+    let api = &data.api;
+
+    let existential_deposit = existential_deposit(api).map_err(map_subxt_err)?;
+    let storage = api.storage().await.map_err(map_subxt_err)?;
+    let (sender_free, frozen) = storage
+        .fetch(&sugarfunge::storage().system().account(signer.account_id()))
         .await
         .map_err(map_subxt_err)?
-        .wait_for_finalized_success()
+        .map(|info| {
+            let frozen = std::cmp::max(info.data.misc_frozen, info.data.fee_frozen);
+            (info.data.free, frozen)
+        })
+        .unwrap_or_default();
+    let recipient_free = free_balance(&storage, &account)
+        .await
+        .map_err(map_subxt_err)?;
+    // The frozen balance stays, and counts towards the existential deposit
+    let amount = if req.keep_alive {
+        sender_free.saturating_sub(std::cmp::max(frozen, existential_deposit))
+    } else {
+        sender_free.saturating_sub(frozen)
+    };
+    let warnings = transfer_warnings(sender_free, recipient_free, amount, existential_deposit);
+    if !warnings.is_empty() && !req.force {
+        return Ok(refuse_transfer(&warnings, "account::transfer_all"));
+    }
+
+    let call = sugarfunge::tx()
+        .balances()
+        .transfer_all(subxt::utils::MultiAddress::Id(account), req.keep_alive);
+    let result = api
+        .sign_and_submit(&call, &signer)
         .await
         .map_err(map_sf_err)?;
     let result = result
@@ -68,30 +379,232 @@ pub async fn fund(
             from: event.from.into(),
             to: event.to.into(),
             amount: event.amount.into(),
+            warnings,
         })),
         None => Ok(HttpResponse::BadRequest().json(RequestError {
             message: json!("Failed to find sugarfunge::balances::events::Transfer"),
-            description: "Error in account::fund".to_string(),
+            description: "Error in account::transfer_all".to_string(),
         })),
     }
 }
 
+/// Transfer from the signer to many recipients. The runtime has no utility
+/// pallet, so the transfers are submitted one after the other and the ones
+/// before a failed transfer stay applied. The error lists them next to the
+/// index of the failed one.
+#[utoipa::path(
+    post,
+    path = "/account/batch_transfer",
+    tag = "account",
+    request_body = BatchTransferInput,
+    responses(
+        (status = 200, body = BatchTransferOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["account:write"]), ("bearer" = ["account:write"])),
+)]
+pub async fn batch_transfer(
+    data: web::Data<AppState>,
+    req: Json<BatchTransferInput>,
+) -> error::Result<HttpResponse> {
+    let pair = get_pair_from_seed(&req.seed)?;
+    let signer = PairSigner::new(pair);
+    let from = signer.account_id().clone();
+    let api = &data.api;
+
+    let mut recipients = Vec::new();
+    for transfer in req.transfers.iter() {
+        let account = AccountId32::try_from(&transfer.to).map_err(map_account_err)?;
+        recipients.push((account, u128::from(transfer.amount)));
+    }
+
+    // Warnings are checked against the balances the earlier transfers leave
+    let existential_deposit = existential_deposit(api).map_err(map_subxt_err)?;
+    let storage = api.storage().await.map_err(map_subxt_err)?;
+    let mut balances: HashMap<AccountId32, u128> = HashMap::new();
+    balances.insert(
+        from.clone(),
+        free_balance(&storage, &from).await.map_err(map_subxt_err)?,
+    );
+    let mut warnings = Vec::new();
+    for (account, amount) in recipients.iter() {
+        let recipient_free = match balances.get(account) {
+            Some(free) => *free,
+            None => free_balance(&storage, account)
+                .await
+                .map_err(map_subxt_err)?,
+        };
+        let sender_free = balances[&from];
+        let transfer_warnings =
+            transfer_warnings(sender_free, recipient_free, *amount, existential_deposit);
+        balances.insert(account.clone(), recipient_free.saturating_add(*amount));
+        balances.insert(from.clone(), sender_free.saturating_sub(*amount));
+        warnings.push(transfer_warnings);
+    }
+    if !req.force {
+        if let Some((index, refused)) = warnings
+            .iter()
+            .enumerate()
+            .find(|(_, warnings)| !warnings.is_empty())
+        {
+            return Ok(HttpResponse::BadRequest().json(RequestError {
+                message: json!(refused),
+                description: format!(
+                    "Error in account::batch_transfer: transfer {} would reap the sender or \
+                     leave the recipient below the existential deposit, set force to submit it anyway",
+                    index
+                ),
+            }));
+        }
+    }
+
+    let mut transfers = Vec::new();
+    for (index, ((account, amount), warnings)) in recipients.into_iter().zip(warnings).enumerate() {
+        let event = match submit_transfer(api, &signer, account, amount, req.keep_alive).await {
+            Ok(Some(event)) => event,
+            Ok(None) => {
+                return Ok(refuse_batch_transfer(
+                    index,
+                    "Failed to find sugarfunge::balances::events::Transfer".into(),
+                    &transfers,
+                ))
+            }
+            Err(e) => return Ok(refuse_batch_transfer(index, e.to_string(), &transfers)),
+        };
+        transfers.push(FundAccountOutput {
+            from: event.from.into(),
+            to: event.to.into(),
+            amount: event.amount.into(),
+            warnings,
+        });
+    }
+
+    Ok(HttpResponse::Ok().json(BatchTransferOutput {
+        from: from.into(),
+        transfers,
+    }))
+}
+
+/// `ExistentialDeposit` of the runtime
+fn existential_deposit(api: &ClientAPI) -> Result<u128, subxt::Error> {
+    api.constant(&sugarfunge::constants().balances().existential_deposit())
+}
+
+/// Free balance of `account`, zero when it does not exist
+async fn free_balance(storage: &Storage<'_>, account: &AccountId32) -> Result<u128, subxt::Error> {
+    let info = storage
+        .fetch(&sugarfunge::storage().system().account(account))
+        .await?;
+    Ok(info.map(|info| info.data.free).unwrap_or_default())
+}
+
+/// What sending `amount` between accounts holding `sender_free` and
+/// `recipient_free` would cause. A sender short of `amount` is left for the
+/// chain to refuse.
+fn transfer_warnings(
+    sender_free: u128,
+    recipient_free: u128,
+    amount: u128,
+    existential_deposit: u128,
+) -> Vec<TransferWarning> {
+    let mut warnings = Vec::new();
+    if sender_free >= amount && sender_free - amount < existential_deposit {
+        warnings.push(TransferWarning::ReapsSender);
+    }
+    if recipient_free.saturating_add(amount) < existential_deposit {
+        warnings.push(TransferWarning::BelowExistentialDeposit);
+    }
+    warnings
+}
+
+/// Transfer `index` of a batch failed with `error`, the message lists the
+/// transfers submitted before it
+fn refuse_batch_transfer(
+    index: usize,
+    error: String,
+    transfers: &[FundAccountOutput],
+) -> HttpResponse {
+    HttpResponse::BadRequest().json(RequestError {
+        message: json!({
+            "error": error,
+            "failed_index": index,
+            "transfers": transfers,
+        }),
+        description: format!(
+            "Error in account::batch_transfer: transfer {} failed, the {} before it were submitted",
+            index, index
+        ),
+    })
+}
+
+fn refuse_transfer(warnings: &[TransferWarning], handler: &str) -> HttpResponse {
+    HttpResponse::BadRequest().json(RequestError {
+        message: json!(warnings),
+        description: format!(
+            "Error in {}: the transfer would reap the sender or leave the recipient below \
+             the existential deposit, set force to submit it anyway",
+            handler
+        ),
+    })
+}
+
+/// Submit `transfer`, or `transfer_keep_alive` when `keep_alive` is set
+async fn submit_transfer(
+    api: &ClientAPI,
+    signer: &Signer,
+    to: AccountId32,
+    amount: u128,
+    keep_alive: bool,
+) -> Result<Option<sugarfunge::balances::events::Transfer>, subxt::Error> {
+    let dest = subxt::utils::MultiAddress::Id(to);
+    let events = if keep_alive {
+        let call = sugarfunge::tx()
+            .balances()
+            .transfer_keep_alive(dest, amount);
+        api.sign_and_submit(&call, signer).await?
+    } else {
+        let call = sugarfunge::tx().balances().transfer(dest, amount);
+        api.sign_and_submit(&call, signer).await?
+    };
+    events.find_first::<sugarfunge::balances::events::Transfer>()
+}
+
 /// Get balance for given account
+#[utoipa::path(
+    post,
+    path = "/account/balance",
+    tag = "account",
+    request_body = AccountBalanceInput,
+    responses(
+        (status = 200, body = AccountBalanceOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["read"]), ("bearer" = ["read"])),
+)]
 pub async fn balance(
     data: web::Data<AppState>,
-    req: web::Json<AccountBalanceInput>,
+    req: Json<AccountBalanceInput>,
 ) -> error::Result<HttpResponse> {
     let account = subxt::utils::AccountId32::try_from(&req.account).map_err(map_account_err)?;
     let api = &data.api;
 
     let call = sugarfunge::storage().system().account(&account);
 
-    //let result = api.storage().fetch(&call, None).await;
-    let block = api.blocks().at_latest().await.unwrap();
-    let data = block.storage().fetch(&call).await.unwrap();
+    let existential_deposit = existential_deposit(api).map_err(map_subxt_err)?;
+    let storage = api.storage().await.map_err(map_subxt_err)?;
+    let data = storage.fetch(&call).await.map_err(map_subxt_err)?;
     match data {
-        Some(data) => Ok(HttpResponse::Ok().json(AccountBalanceOutput {
-            balance: data.data.free.into(),
+        Some(info) => Ok(HttpResponse::Ok().json(AccountBalanceOutput {
+            balance: info.data.free.into(),
+            free: info.data.free.into(),
+            reserved: info.data.reserved.into(),
+            misc_frozen: info.data.misc_frozen.into(),
+            fee_frozen: info.data.fee_frozen.into(),
+            nonce: info.nonce,
+            providers: info.providers,
+            consumers: info.consumers,
+            sufficients: info.sufficients,
+            existential_deposit: existential_deposit.into(),
         })),
         None => Ok(HttpResponse::BadRequest().json(RequestError {
             message: json!("Failed to find sugarfunge::balances::events::balance"),
@@ -101,9 +614,20 @@ pub async fn balance(
 }
 
 /// Check if account exists and is active
+#[utoipa::path(
+    post,
+    path = "/account/exists",
+    tag = "account",
+    request_body = AccountExistsInput,
+    responses(
+        (status = 200, body = AccountExistsOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["read"]), ("bearer" = ["read"])),
+)]
 pub async fn exists(
     data: web::Data<AppState>,
-    req: web::Json<AccountExistsInput>,
+    req: Json<AccountExistsInput>,
 ) -> error::Result<HttpResponse> {
     let account = subxt::utils::AccountId32::try_from(&req.account).map_err(map_account_err)?;
     let account_out = account.clone();
@@ -111,7 +635,7 @@ pub async fn exists(
 
     let call = sugarfunge::storage().system().account(&account);
 
-    let storage = api.storage().at_latest().await.map_err(map_subxt_err)?;
+    let storage = api.storage().await.map_err(map_subxt_err)?;
     let data = storage.fetch(&call).await.map_err(map_subxt_err)?;
     match data {
         Some(data) => Ok(HttpResponse::Ok().json(AccountExistsOutput {
@@ -125,16 +649,217 @@ pub async fn exists(
     }
 }
 
+/// Balances, assets, pool, manifests, claims and open challenges of an
+/// account, all read at the latest block
+#[utoipa::path(
+    post,
+    path = "/account/portfolio",
+    tag = "account",
+    request_body = AccountPortfolioInput,
+    responses(
+        (status = 200, body = AccountPortfolioOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["read"]), ("bearer" = ["read"])),
+)]
+pub async fn portfolio(
+    data: web::Data<AppState>,
+    req: Json<AccountPortfolioInput>,
+) -> error::Result<HttpResponse> {
+    let account = AccountId32::try_from(&req.account).map_err(map_account_err)?;
+
+    let storage = data.api.storage().await.map_err(map_subxt_err)?;
+    let block_number = storage
+        .fetch(&sugarfunge::storage().system().number())
+        .await
+        .map_err(map_subxt_err)?
+        .unwrap_or_default();
+
+    let info = storage
+        .fetch(&sugarfunge::storage().system().account(&account))
+        .await
+        .map_err(map_subxt_err)?;
+    let (free, reserved, frozen) = info
+        .map(|info| {
+            let frozen = std::cmp::max(info.data.misc_frozen, info.data.fee_frozen);
+            (info.data.free, info.data.reserved, frozen)
+        })
+        .unwrap_or_default();
+    let balance = NativeBalance {
+        free: free.into(),
+        reserved: reserved.into(),
+        frozen: frozen.into(),
+        transferable: free.saturating_sub(frozen).into(),
+    };
+
+    let mut query_key = sugarfunge::storage()
+        .asset()
+        .balances_root()
+        .to_root_bytes();
+    query_key.extend(blake2_128_concat(&account));
+    let keys = storage
+        .fetch_all_keys(&query_key)
+        .await
+        .map_err(map_subxt_err)?;
+    let mut assets = Vec::new();
+    for key in keys.iter() {
+        let class_idx = 96;
+        let class_id = u64::decode(&mut &key.0[class_idx..(class_idx + 8)])
+            .map_err(|e| map_subxt_err(e.into()))?;
+
+        let asset_idx = 120;
+        let asset_id = u64::decode(&mut &key.0[asset_idx..(asset_idx + 8)])
+            .map_err(|e| map_subxt_err(e.into()))?;
+
+        let amount = match storage.fetch_raw(&key.0).await.map_err(map_subxt_err)? {
+            Some(storage_data) => {
+                u128::decode(&mut &storage_data[..]).map_err(|e| map_subxt_err(e.into()))?
+            }
+            None => continue,
+        };
+        let class = storage
+            .fetch(&sugarfunge::storage().asset().classes(class_id))
+            .await
+            .map_err(map_subxt_err)?;
+        let asset = storage
+            .fetch(&sugarfunge::storage().asset().assets(class_id, asset_id))
+            .await
+            .map_err(map_subxt_err)?;
+        assets.push(PortfolioAsset {
+            class_id: class_id.into(),
+            asset_id: asset_id.into(),
+            amount: amount.into(),
+            class: class.map(|class| ClassInfo {
+                class_id: class_id.into(),
+                owner: class.owner.into(),
+                metadata: serde_json::from_slice(class.metadata.0.as_slice()).unwrap_or_default(),
+            }),
+            asset: asset.map(|asset| AssetInfo {
+                class_id: class_id.into(),
+                asset_id: asset_id.into(),
+                metadata: serde_json::from_slice(asset.metadata.0.as_slice()).unwrap_or_default(),
+            }),
+        });
+    }
+
+    let user = storage
+        .fetch(&sugarfunge::storage().pool().users(&account))
+        .await
+        .map_err(map_subxt_err)?;
+    let mut pool_request = None;
+    if let Some(request_pool_id) = user.as_ref().and_then(|user| user.request_pool_id) {
+        let request = storage
+            .fetch(
+                &sugarfunge::storage()
+                    .pool()
+                    .pool_requests(request_pool_id, &account),
+            )
+            .await
+            .map_err(map_subxt_err)?;
+        pool_request = request.map(|request| PoolRequestData {
+            pool_id: request_pool_id.into(),
+            account: account.clone().into(),
+            voted: request.voted.0.into_iter().map(Account::from).collect(),
+            positive_votes: request.positive_votes,
+            peer_id: String::from_utf8(request.peer_id.0)
+                .unwrap_or_default()
+                .into(),
+        });
+    }
+    let pool = user.map(|user| PoolUserData {
+        account: account.clone().into(),
+        pool_id: transform_option_pool_value(user.pool_id),
+        request_pool_id: transform_option_pool_value(user.request_pool_id),
+        peer_id: String::from_utf8(user.peer_id.0).unwrap_or_default().into(),
+    });
+
+    let mut uploaded_manifests = Vec::new();
+    let mut stored_manifests = Vec::new();
+    for (pool_id, cid, value) in fetch_manifests_at(&storage, None)
+        .await
+        .map_err(map_subxt_err)?
+    {
+        let uploaded = value.users_data.iter().any(|data| data.uploader == account);
+        let stored = value
+            .users_data
+            .iter()
+            .any(|data| data.storers.contains(&account));
+        if !uploaded && !stored {
+            continue;
+        }
+        let (manifest_metadata, metadata_undecodable) =
+            decode_manifest_metadata(value.manifest_metadata.as_slice());
+        let manifest = PortfolioManifest {
+            pool_id: pool_id.into(),
+            cid: Cid::from(cid),
+            uploaders: transform_vec_uploader_data_runtime_to_vec_uploader_data(value.users_data),
+            manifest_metadata,
+            metadata_undecodable,
+            size: value.size,
+        };
+        match (uploaded, stored) {
+            (true, true) => {
+                uploaded_manifests.push(manifest.clone());
+                stored_manifests.push(manifest);
+            }
+            (true, false) => uploaded_manifests.push(manifest),
+            _ => stored_manifests.push(manifest),
+        }
+    }
+
+    let claim = storage
+        .fetch(&sugarfunge::storage().fula().claims(&account))
+        .await
+        .map_err(map_subxt_err)?
+        .map(|claim| ClaimData {
+            account: account.clone().into(),
+            minted_labor_tokens: claim.minted_labor_tokens.into(),
+            expected_labor_tokens: claim.expected_labor_tokens.into(),
+            minted_challenge_tokens: claim.challenge_tokens.into(),
+        });
+
+    let open_challenges = open_challenges_at(&storage, &account)
+        .await
+        .map_err(map_subxt_err)?
+        .into_iter()
+        .map(|(challenger, cid)| OpenChallenge {
+            challenger: challenger.into(),
+            cid: Cid::from(cid),
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(AccountPortfolioOutput {
+        account: account.into(),
+        block_number,
+        block_hash: format!("{:?}", storage.block_hash()),
+        balance,
+        assets,
+        pool,
+        pool_request,
+        uploaded_manifests,
+        stored_manifests,
+        claim,
+        open_challenges,
+    }))
+}
+
+/// Reimburse the fees paid by seed from the configured sponsor account
 pub async fn refund_fees(data: web::Data<AppState>, seed: &Seed) -> error::Result<HttpResponse> {
+    let refund = data.config.refund.clone();
+    if !refund.enabled {
+        return Ok(HttpResponse::Ok().finish());
+    }
     let result_fund = fund(
         data,
-        web::Json(FundAccountInput {
-            seed: Seed::from(String::from(REFUND_SEED)),
+        Json(FundAccountInput {
+            seed: Seed::from(refund.sponsor_seed),
             to: Account::from(format!(
                 "{}",
                 get_pair_from_seed(seed)?.public().into_account()
             )),
-            amount: Balance::from(REFUND_FEE_VALUE),
+            amount: Balance::from(refund.fee_value),
+            keep_alive: false,
+            force: false,
         }),
     )
     .await;
@@ -0,0 +1,563 @@
+//! Activity of accounts, indexed from finalized events.
+//!
+//! Nothing on chain lists what an account did, so the gateway indexes the
+//! balances, asset, bag, bundle, market, pool and fula events of the
+//! finalized blocks, backfilled at startup, and files each one under the
+//! accounts it involves.
+
+use crate::chain::{Block, RawEvent};
+use crate::follower::BlockIndex;
+use crate::state::ClientAPI;
+use async_trait::async_trait;
+use codec::Decode;
+use hex::ToHex;
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use subxt::events::StaticEvent;
+use subxt::utils::AccountId32;
+use sugarfunge_api_types::activity::*;
+use sugarfunge_api_types::primitives::*;
+use sugarfunge_api_types::sugarfunge;
+
+/// Entries kept per account, the oldest are dropped first
+const MAX_ENTRIES: usize = 1_000;
+
+/// Accounts kept, the ones inactive for the longest are dropped first
+const MAX_ACCOUNTS: usize = 100_000;
+
+/// Payload of an event and the accounts it involves
+type Activity = (ActivityPayload, Vec<AccountId32>);
+
+#[derive(Default)]
+pub struct ActivityIndex {
+    /// Timestamp of the first block indexed
+    since: Mutex<Option<u64>>,
+    accounts: Mutex<Accounts>,
+}
+
+#[derive(Default)]
+struct Accounts {
+    /// Oldest first
+    entries: HashMap<AccountId32, VecDeque<ActivityEntry>>,
+    /// Id of the latest entry of every account, least recently active first
+    recency: BTreeSet<(u64, [u8; 32])>,
+}
+
+impl Accounts {
+    fn push(&mut self, account: AccountId32, entry: ActivityEntry) {
+        let bytes = account.0;
+        if !self.entries.contains_key(&account) && self.entries.len() == MAX_ACCOUNTS {
+            if let Some(inactive) = self.recency.iter().next().cloned() {
+                self.recency.remove(&inactive);
+                self.entries.remove(&AccountId32(inactive.1));
+            }
+        }
+        let account_entries = self.entries.entry(account).or_default();
+        if let Some(latest) = account_entries.back() {
+            self.recency.remove(&(latest.id, bytes));
+        }
+        if account_entries.len() == MAX_ENTRIES {
+            account_entries.pop_front();
+        }
+        self.recency.insert((entry.id, bytes));
+        account_entries.push_back(entry);
+    }
+}
+
+impl ActivityIndex {
+    pub fn new() -> ActivityIndex {
+        ActivityIndex::default()
+    }
+
+    /// Unix time in seconds the index starts at, the current time until a
+    /// block is indexed
+    pub fn since(&self) -> u64 {
+        self.since.lock().unwrap().unwrap_or_else(now)
+    }
+
+    /// Up to `limit` entries of `account` older than `before`, latest first,
+    /// and the cursor of the next page
+    pub fn page(
+        &self,
+        account: &AccountId32,
+        before: Option<u64>,
+        limit: usize,
+    ) -> (Vec<ActivityEntry>, Option<u64>) {
+        let accounts = self.accounts.lock().unwrap();
+        let mut older = match accounts.entries.get(account) {
+            Some(entries) => entries
+                .iter()
+                .rev()
+                .filter(|entry| before.map_or(true, |before| entry.id < before)),
+            None => return (Vec::new(), None),
+        };
+        let page: Vec<ActivityEntry> = older.by_ref().take(limit).cloned().collect();
+        let next = match older.next() {
+            Some(_) => page.last().map(|entry| entry.id),
+            None => None,
+        };
+        (page, next)
+    }
+
+    /// Index the events of one block
+    pub fn record(&self, block: &Block) {
+        let timestamp = block.timestamp / 1000;
+        self.since.lock().unwrap().get_or_insert(timestamp);
+        let mut accounts_index = self.accounts.lock().unwrap();
+        for (index, event) in block.events.iter().enumerate() {
+            let (payload, mut accounts) = match activity(event) {
+                Ok(Some(activity)) => activity,
+                Ok(None) => continue,
+                Err(e) => {
+                    log::warn!(
+                        "Activity index could not decode {}.{}: {}",
+                        event.pallet,
+                        event.variant,
+                        e
+                    );
+                    continue;
+                }
+            };
+            let entry = ActivityEntry {
+                id: (u64::from(block.number) << 32) | index as u64,
+                block_number: block.number,
+                timestamp,
+                extrinsic_index: event.extrinsic_index,
+                event_type: format!("{}.{}", event.pallet, event.variant),
+                payload,
+            };
+            accounts.sort_by(|a, b| a.0.cmp(&b.0));
+            accounts.dedup();
+            for account in accounts {
+                accounts_index.push(account, entry.clone());
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl BlockIndex for ActivityIndex {
+    const NAME: &'static str = "Activity index";
+
+    async fn index(&self, _api: &ClientAPI, block: &Block) -> Result<(), subxt::Error> {
+        self.record(block);
+        Ok(())
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+fn decode<E: StaticEvent + Decode>(event: &RawEvent) -> Result<E, codec::Error> {
+    E::decode(&mut &event.fields[..])
+}
+
+fn cid(cid: Vec<u8>) -> Cid {
+    Cid::from(String::from_utf8(cid).unwrap_or_default())
+}
+
+fn cids(cids: Vec<Vec<u8>>) -> Vec<Cid> {
+    cids.into_iter().map(cid).collect()
+}
+
+/// Activity of an event, none for the events that are not listed
+fn activity(event: &RawEvent) -> Result<Option<Activity>, codec::Error> {
+    use sugarfunge::{asset, bag, balances, bundle, fula, market, pool};
+
+    let activity = match (event.pallet.as_str(), event.variant.as_str()) {
+        ("Balances", "Transfer") => {
+            let e: balances::events::Transfer = decode(event)?;
+            (
+                ActivityPayload::Transfer {
+                    from: e.from.clone().into(),
+                    to: e.to.clone().into(),
+                    amount: e.amount.into(),
+                },
+                vec![e.from, e.to],
+            )
+        }
+        ("Asset", "ClassCreated") => {
+            let e: asset::events::ClassCreated = decode(event)?;
+            (
+                ActivityPayload::ClassCreated {
+                    who: e.who.clone().into(),
+                    class_id: e.class_id.into(),
+                },
+                vec![e.who],
+            )
+        }
+        ("Asset", "AssetCreated") => {
+            let e: asset::events::AssetCreated = decode(event)?;
+            (
+                ActivityPayload::AssetCreated {
+                    who: e.who.clone().into(),
+                    class_id: e.class_id.into(),
+                    asset_id: e.asset_id.into(),
+                },
+                vec![e.who],
+            )
+        }
+        ("Asset", "Mint") => {
+            let e: asset::events::Mint = decode(event)?;
+            asset_minted(e.who, e.to, e.class_id, vec![e.asset_id], vec![e.amount])
+        }
+        ("Asset", "BatchMint") => {
+            let e: asset::events::BatchMint = decode(event)?;
+            asset_minted(e.who, e.to, e.class_id, e.asset_ids, e.amounts)
+        }
+        ("Asset", "Burn") => {
+            let e: asset::events::Burn = decode(event)?;
+            asset_burned(e.who, e.from, e.class_id, vec![e.asset_id], vec![e.amount])
+        }
+        ("Asset", "BatchBurn") => {
+            let e: asset::events::BatchBurn = decode(event)?;
+            asset_burned(e.who, e.from, e.class_id, e.asset_ids, e.amounts)
+        }
+        ("Asset", "Transferred") => {
+            let e: asset::events::Transferred = decode(event)?;
+            asset_transferred(
+                e.who,
+                e.from,
+                e.to,
+                e.class_id,
+                vec![e.asset_id],
+                vec![e.amount],
+            )
+        }
+        ("Asset", "BatchTransferred") => {
+            let e: asset::events::BatchTransferred = decode(event)?;
+            asset_transferred(e.who, e.from, e.to, e.class_id, e.asset_ids, e.amounts)
+        }
+        ("Bag", "Created") => {
+            let e: bag::events::Created = decode(event)?;
+            let mut accounts = vec![e.who.clone(), e.bag.clone()];
+            accounts.extend(e.owners.iter().cloned());
+            (
+                ActivityPayload::BagCreated {
+                    who: e.who.into(),
+                    bag: e.bag.into(),
+                    class_id: e.class_id.into(),
+                    asset_id: e.asset_id.into(),
+                    owners: e.owners.into_iter().map(Account::from).collect(),
+                },
+                accounts,
+            )
+        }
+        ("Bag", "Deposit") => {
+            let e: bag::events::Deposit = decode(event)?;
+            (
+                ActivityPayload::BagDeposit {
+                    who: e.who.clone().into(),
+                    bag: e.bag.clone().into(),
+                },
+                vec![e.who, e.bag],
+            )
+        }
+        ("Bag", "Sweep") => {
+            let e: bag::events::Sweep = decode(event)?;
+            (
+                ActivityPayload::BagSwept {
+                    who: e.who.clone().into(),
+                    bag: e.bag.clone().into(),
+                    to: e.to.clone().into(),
+                },
+                vec![e.who, e.bag, e.to],
+            )
+        }
+        ("Bundle", "Mint") => {
+            let e: bundle::events::Mint = decode(event)?;
+            (
+                ActivityPayload::BundleMinted {
+                    who: e.who.clone().into(),
+                    from: e.from.clone().into(),
+                    to: e.to.clone().into(),
+                    bundle_id: e.bundle_id.encode_hex(),
+                    amount: e.amount.into(),
+                },
+                vec![e.who, e.from, e.to],
+            )
+        }
+        ("Bundle", "Burn") => {
+            let e: bundle::events::Burn = decode(event)?;
+            (
+                ActivityPayload::BundleBurned {
+                    who: e.who.clone().into(),
+                    from: e.from.clone().into(),
+                    to: e.to.clone().into(),
+                    bundle_id: e.bundle_id.encode_hex(),
+                    amount: e.amount.into(),
+                },
+                vec![e.who, e.from, e.to],
+            )
+        }
+        ("Market", "Deposit") => {
+            let e: market::events::Deposit = decode(event)?;
+            (
+                ActivityPayload::MarketDeposit {
+                    who: e.who.clone().into(),
+                    market_id: e.market_id.into(),
+                    market_rate_id: e.market_rate_id.into(),
+                    amount: e.amount.into(),
+                    success: e.success,
+                },
+                vec![e.who],
+            )
+        }
+        ("Market", "Exchanged") => {
+            let e: market::events::Exchanged = decode(event)?;
+            (
+                ActivityPayload::MarketExchange {
+                    buyer: e.buyer.clone().into(),
+                    market_id: e.market_id.into(),
+                    market_rate_id: e.market_rate_id.into(),
+                    amount: e.amount.into(),
+                    success: e.success,
+                },
+                vec![e.buyer],
+            )
+        }
+        ("Pool", "PoolCreated") => {
+            let e: pool::events::PoolCreated = decode(event)?;
+            (
+                ActivityPayload::PoolCreated {
+                    owner: e.owner.clone().map(Account::from),
+                    pool_id: e.pool_id.into(),
+                },
+                e.owner.into_iter().collect(),
+            )
+        }
+        ("Pool", "JoinRequested") => {
+            let e: pool::events::JoinRequested = decode(event)?;
+            (
+                ActivityPayload::JoinRequested {
+                    account: e.account.clone().into(),
+                    pool_id: e.pool_id.into(),
+                },
+                vec![e.account],
+            )
+        }
+        ("Pool", "RequestWithdrawn") => {
+            let e: pool::events::RequestWithdrawn = decode(event)?;
+            (
+                ActivityPayload::JoinRequestWithdrawn {
+                    account: e.account.clone().into(),
+                    pool_id: e.pool_id.into(),
+                },
+                vec![e.account],
+            )
+        }
+        ("Pool", "VotingResult") => {
+            let e: pool::events::VotingResult = decode(event)?;
+            (
+                ActivityPayload::JoinVoteResult {
+                    account: e.account.clone().into(),
+                    pool_id: e.pool_id.into(),
+                    result: String::from_utf8_lossy(&e.result).into_owned(),
+                },
+                vec![e.account],
+            )
+        }
+        ("Pool", "ParticipantLeft") => {
+            let e: pool::events::ParticipantLeft = decode(event)?;
+            (
+                ActivityPayload::PoolLeft {
+                    account: e.account.clone().into(),
+                    pool_id: e.pool_id.into(),
+                },
+                vec![e.account],
+            )
+        }
+        ("Fula", "ManifestOutput") => {
+            let e: fula::events::ManifestOutput = decode(event)?;
+            manifests_uploaded(e.uploader, vec![e.pool_id], vec![e.manifest])
+        }
+        ("Fula", "BatchManifestOutput") => {
+            let e: fula::events::BatchManifestOutput = decode(event)?;
+            manifests_uploaded(e.uploader, e.pool_ids, e.manifests)
+        }
+        ("Fula", "ManifestRemoved") => {
+            let e: fula::events::ManifestRemoved = decode(event)?;
+            manifests_removed(e.uploader, vec![e.pool_id], vec![e.cid])
+        }
+        ("Fula", "BatchManifestRemoved") => {
+            let e: fula::events::BatchManifestRemoved = decode(event)?;
+            manifests_removed(e.uploader, e.pool_ids, e.cids)
+        }
+        ("Fula", "StorageManifestOutput") => {
+            let e: fula::events::StorageManifestOutput = decode(event)?;
+            (
+                ActivityPayload::ManifestsStored {
+                    storer: e.storer.clone().into(),
+                    pool_id: e.pool_id.into(),
+                    cids: vec![cid(e.cid)],
+                },
+                vec![e.storer],
+            )
+        }
+        ("Fula", "BatchStorageManifestOutput") => {
+            let e: fula::events::BatchStorageManifestOutput = decode(event)?;
+            (
+                ActivityPayload::ManifestsStored {
+                    storer: e.storer.clone().into(),
+                    pool_id: e.pool_id.into(),
+                    cids: cids(e.cids),
+                },
+                vec![e.storer],
+            )
+        }
+        ("Fula", "RemoveStorerOutput") => {
+            let e: fula::events::RemoveStorerOutput = decode(event)?;
+            let storer = match e.storer {
+                Some(storer) => storer,
+                None => return Ok(None),
+            };
+            (
+                ActivityPayload::ManifestsReleased {
+                    storer: storer.clone().into(),
+                    pool_id: e.pool_id.into(),
+                    cids: vec![cid(e.cid)],
+                },
+                vec![storer],
+            )
+        }
+        ("Fula", "BatchRemoveStorerOutput") => {
+            let e: fula::events::BatchRemoveStorerOutput = decode(event)?;
+            (
+                ActivityPayload::ManifestsReleased {
+                    storer: e.storer.clone().into(),
+                    pool_id: e.pool_id.into(),
+                    cids: cids(e.cids),
+                },
+                vec![e.storer],
+            )
+        }
+        ("Fula", "Challenge") => {
+            let e: fula::events::Challenge = decode(event)?;
+            (
+                ActivityPayload::ChallengeOpened {
+                    challenger: e.challenger.clone().into(),
+                    challenged: e.challenged.clone().into(),
+                    cid: cid(e.cid),
+                },
+                vec![e.challenger, e.challenged],
+            )
+        }
+        ("Fula", "VerifiedChallenges") => {
+            let e: fula::events::VerifiedChallenges = decode(event)?;
+            (
+                ActivityPayload::ChallengeVerified {
+                    challenged: e.challenged.clone().into(),
+                    successful: cids(e.successful),
+                    failed: cids(e.failed),
+                },
+                vec![e.challenged],
+            )
+        }
+        ("Fula", "MintedLaborTokens") => {
+            let e: fula::events::MintedLaborTokens = decode(event)?;
+            (
+                ActivityPayload::LaborTokensMinted {
+                    account: e.account.clone().into(),
+                    class_id: e.class_id.into(),
+                    asset_id: e.asset_id.into(),
+                    amount: e.amount.into(),
+                    calculated_amount: e.calculated_amount.into(),
+                },
+                vec![e.account],
+            )
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(activity))
+}
+
+fn asset_minted(
+    who: AccountId32,
+    to: AccountId32,
+    class_id: u64,
+    asset_ids: Vec<u64>,
+    amounts: Vec<u128>,
+) -> Activity {
+    (
+        ActivityPayload::AssetMinted {
+            who: who.clone().into(),
+            to: to.clone().into(),
+            class_id: class_id.into(),
+            asset_ids: asset_ids.into_iter().map(AssetId::from).collect(),
+            amounts: amounts.into_iter().map(Balance::from).collect(),
+        },
+        vec![who, to],
+    )
+}
+
+fn asset_burned(
+    who: AccountId32,
+    from: AccountId32,
+    class_id: u64,
+    asset_ids: Vec<u64>,
+    amounts: Vec<u128>,
+) -> Activity {
+    (
+        ActivityPayload::AssetBurned {
+            who: who.clone().into(),
+            from: from.clone().into(),
+            class_id: class_id.into(),
+            asset_ids: asset_ids.into_iter().map(AssetId::from).collect(),
+            amounts: amounts.into_iter().map(Balance::from).collect(),
+        },
+        vec![who, from],
+    )
+}
+
+fn asset_transferred(
+    who: AccountId32,
+    from: AccountId32,
+    to: AccountId32,
+    class_id: u64,
+    asset_ids: Vec<u64>,
+    amounts: Vec<u128>,
+) -> Activity {
+    (
+        ActivityPayload::AssetTransferred {
+            who: who.clone().into(),
+            from: from.clone().into(),
+            to: to.clone().into(),
+            class_id: class_id.into(),
+            asset_ids: asset_ids.into_iter().map(AssetId::from).collect(),
+            amounts: amounts.into_iter().map(Balance::from).collect(),
+        },
+        vec![who, from, to],
+    )
+}
+
+fn manifests_uploaded(
+    uploader: AccountId32,
+    pool_ids: Vec<u32>,
+    manifests: Vec<Vec<u8>>,
+) -> Activity {
+    let (manifest_metadata, _) = crate::fula::get_vec_manifests_from_node(manifests);
+    (
+        ActivityPayload::ManifestsUploaded {
+            uploader: uploader.clone().into(),
+            pool_ids: pool_ids.into_iter().map(PoolId::from).collect(),
+            manifest_metadata,
+        },
+        vec![uploader],
+    )
+}
+
+fn manifests_removed(uploader: AccountId32, pool_ids: Vec<u32>, removed: Vec<Vec<u8>>) -> Activity {
+    (
+        ActivityPayload::ManifestsRemoved {
+            uploader: uploader.clone().into(),
+            pool_ids: pool_ids.into_iter().map(PoolId::from).collect(),
+            cids: cids(removed),
+        },
+        vec![uploader],
+    )
+}
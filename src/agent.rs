@@ -0,0 +1,276 @@
+//! Storage provider agent: keeps a Fula node busy without manual calls.
+//!
+//! After every finalized block with fula or pool events the agent claims the
+//! available manifests of its pool up to its capacity, reports the size of
+//! stored content that has none yet and answers the challenges sent to it.
+
+use crate::chain::Signer;
+use crate::challenge::open_challenges;
+use crate::config::{ConfigError, TokenConfig};
+use crate::state::{AppState, ClientAPI};
+use crate::util::{blake2_128_concat, get_pair_from_seed};
+use codec::Decode;
+use futures::StreamExt;
+use sp_core::Pair;
+use std::sync::Arc;
+use std::time::Duration;
+use subxt::tx::PairSigner;
+use subxt::utils::AccountId32;
+use sugarfunge_api_types::sugarfunge;
+use sugarfunge_api_types::sugarfunge::runtime_types::functionland_fula::Manifest;
+use sugarfunge_api_types::sugarfunge::runtime_types::sp_core::bounded::bounded_vec::BoundedVec;
+
+pub mod ipfs;
+
+use ipfs::Ipfs;
+
+/// Delay before subscribing again when the event stream ends
+const RESUBSCRIBE_DELAY: Duration = Duration::from_secs(5);
+
+/// What one pass of the agent did
+#[derive(Debug, Default, PartialEq)]
+pub struct SyncReport {
+    /// Manifests claimed for storage
+    pub claimed: Vec<String>,
+    /// Stored cids whose size was reported
+    pub sized: Vec<String>,
+    /// Challenged cids proven to be held
+    pub successful: Vec<String>,
+    /// Challenged cids that could not be proven
+    pub failed: Vec<String>,
+}
+
+impl SyncReport {
+    pub fn is_empty(&self) -> bool {
+        *self == SyncReport::default()
+    }
+}
+
+pub struct Agent {
+    api: ClientAPI,
+    ipfs: Arc<dyn Ipfs>,
+    signer: Signer,
+    account: AccountId32,
+    pool_id: u32,
+    capacity: usize,
+    tokens: TokenConfig,
+}
+
+impl Agent {
+    pub fn new(
+        api: ClientAPI,
+        ipfs: Arc<dyn Ipfs>,
+        pair: sp_core::sr25519::Pair,
+        pool_id: u32,
+        capacity: usize,
+        tokens: TokenConfig,
+    ) -> Agent {
+        let account = AccountId32::from(pair.public().0);
+        Agent {
+            api,
+            ipfs,
+            signer: PairSigner::new(pair),
+            account,
+            pool_id,
+            capacity,
+            tokens,
+        }
+    }
+
+    /// The agent configured for the gateway, if it is enabled
+    pub fn from_config(state: &AppState) -> Result<Option<Agent>, ConfigError> {
+        let config = &state.config.agent;
+        if !config.enabled {
+            return Ok(None);
+        }
+        let seed = config
+            .seed()
+            .ok_or_else(|| ConfigError::invalid("agent.key", "no seed for the agent key"))?;
+        let pair = get_pair_from_seed(&seed)
+            .map_err(|_| ConfigError::invalid("agent.keys", "invalid seed for the agent key"))?;
+        let pool_id = config
+            .pool_id
+            .ok_or_else(|| ConfigError::invalid("agent.pool_id", "required by the agent"))?;
+        Ok(Some(Agent::new(
+            state.api.clone(),
+            state.ipfs.clone(),
+            pair,
+            pool_id,
+            config.capacity,
+            state.config.tokens.clone(),
+        )))
+    }
+
+    /// Sync once, then again after every block with fula or pool events
+    pub async fn run(self) {
+        log::info!(
+            "Storage agent {} serving pool {}",
+            self.account,
+            self.pool_id
+        );
+        loop {
+            self.sync_logged().await;
+            let mut blocks = match self.api.watch_events().await {
+                Ok(blocks) => blocks,
+                Err(e) => {
+                    log::warn!("Storage agent could not watch events: {}", e);
+                    actix_web::rt::time::sleep(RESUBSCRIBE_DELAY).await;
+                    continue;
+                }
+            };
+            while let Some(events) = blocks.next().await {
+                match events {
+                    Ok(events) => {
+                        if events
+                            .iter()
+                            .any(|event| event.pallet == "Fula" || event.pallet == "Pool")
+                        {
+                            self.sync_logged().await;
+                        }
+                    }
+                    Err(e) => log::warn!("Storage agent event error: {}", e),
+                }
+            }
+            log::warn!("Storage agent event stream ended, subscribing again");
+            actix_web::rt::time::sleep(RESUBSCRIBE_DELAY).await;
+        }
+    }
+
+    async fn sync_logged(&self) {
+        match self.sync().await {
+            Ok(report) if !report.is_empty() => log::info!("Storage agent: {:?}", report),
+            Ok(_) => {}
+            Err(e) => log::warn!("Storage agent sync failed: {}", e),
+        }
+    }
+
+    /// Claim manifests, report sizes and answer challenges
+    pub async fn sync(&self) -> Result<SyncReport, subxt::Error> {
+        let mut report = SyncReport::default();
+        let manifests = self.manifests().await?;
+
+        let stored: Vec<&(String, Manifest<AccountId32, Vec<u8>>)> = manifests
+            .iter()
+            .filter(|(_, manifest)| self.is_storer(manifest))
+            .collect();
+        let free = self.capacity.saturating_sub(stored.len());
+        let mut claim = Vec::new();
+        for (cid, _) in manifests
+            .iter()
+            .filter(|(_, manifest)| !self.is_storer(manifest) && is_available(manifest))
+        {
+            if claim.len() >= free {
+                break;
+            }
+            match self.ipfs.pin(cid).await {
+                Ok(()) => claim.push(cid.clone()),
+                Err(e) => log::warn!("Storage agent could not pin {}: {}", cid, e),
+            }
+        }
+        if !claim.is_empty() {
+            let call = sugarfunge::tx()
+                .fula()
+                .batch_storage_manifest(bounded(&claim), self.pool_id);
+            self.api.sign_and_submit(&call, &self.signer).await?;
+            report.claimed = claim.clone();
+        }
+
+        let missing_size = stored
+            .iter()
+            .filter(|(_, manifest)| manifest.size.is_none())
+            .map(|(cid, _)| cid.clone())
+            .chain(claim);
+        let mut sizes = Vec::new();
+        for cid in missing_size {
+            match self.ipfs.size(&cid).await {
+                Ok(size) => sizes.push((cid, size)),
+                Err(e) => log::warn!("Storage agent could not size {}: {}", cid, e),
+            }
+        }
+        if !sizes.is_empty() {
+            let (cids, sizes): (Vec<String>, Vec<u64>) = sizes.into_iter().unzip();
+            let call =
+                sugarfunge::tx()
+                    .fula()
+                    .update_file_sizes(bounded(&cids), self.pool_id, sizes);
+            self.api.sign_and_submit(&call, &self.signer).await?;
+            report.sized = cids;
+        }
+
+        let challenged = open_challenges(&self.api, &self.account).await?;
+        if !challenged.is_empty() {
+            let mut held = Vec::new();
+            for (_, cid) in challenged {
+                match self.ipfs.has(&cid).await {
+                    Ok(true) => held.push(cid),
+                    Ok(false) => {}
+                    Err(e) => log::warn!("Storage agent could not check {}: {}", cid, e),
+                }
+            }
+            let call = sugarfunge::tx().fula().verify_challenge(
+                self.pool_id,
+                bounded(&held),
+                self.tokens.challenge_token_class_id,
+                self.tokens.challenge_token_asset_id,
+            );
+            let events = self.api.sign_and_submit(&call, &self.signer).await?;
+            if let Some(event) =
+                events.find_first::<sugarfunge::fula::events::VerifiedChallenges>()?
+            {
+                report.successful = to_strings(event.successful);
+                report.failed = to_strings(event.failed);
+            }
+        }
+        Ok(report)
+    }
+
+    fn is_storer(&self, manifest: &Manifest<AccountId32, Vec<u8>>) -> bool {
+        manifest
+            .users_data
+            .iter()
+            .any(|data| data.storers.contains(&self.account))
+    }
+
+    /// Manifests of the agent pool with their cid
+    async fn manifests(
+        &self,
+    ) -> Result<Vec<(String, Manifest<AccountId32, Vec<u8>>)>, subxt::Error> {
+        let mut prefix = sugarfunge::storage()
+            .fula()
+            .manifests_root()
+            .to_root_bytes();
+        prefix.extend(blake2_128_concat(&self.pool_id));
+
+        let storage = self.api.storage().await?;
+        let keys = storage.fetch_all_keys(&prefix).await?;
+        let mut manifests = Vec::new();
+        for key in keys.iter() {
+            let cid_idx = 68;
+            let cid = String::decode(&mut &key.0[cid_idx..])?;
+            if let Some(value) = storage.fetch_raw(&key.0).await? {
+                let manifest = Manifest::<AccountId32, Vec<u8>>::decode(&mut &value[..])?;
+                manifests.push((cid, manifest));
+            }
+        }
+        Ok(manifests)
+    }
+}
+
+fn is_available(manifest: &Manifest<AccountId32, Vec<u8>>) -> bool {
+    manifest
+        .users_data
+        .iter()
+        .any(|data| (data.storers.len() as u16) < data.replication_factor)
+}
+
+fn bounded(cids: &[String]) -> Vec<BoundedVec<u8>> {
+    cids.iter()
+        .map(|cid| BoundedVec(cid.as_bytes().to_vec()))
+        .collect()
+}
+
+fn to_strings(cids: Vec<Vec<u8>>) -> Vec<String> {
+    cids.into_iter()
+        .map(|cid| String::from_utf8(cid).unwrap_or_default())
+        .collect()
+}
@@ -0,0 +1,109 @@
+use async_trait::async_trait;
+use derive_more::Display;
+use serde::Deserialize;
+use std::time::Duration;
+use url::Url;
+
+#[derive(Debug, Display)]
+#[display(fmt = "ipfs: {}", _0)]
+pub struct IpfsError(pub String);
+
+impl std::error::Error for IpfsError {}
+
+/// Content operations the agent needs from the local IPFS node
+#[async_trait]
+pub trait Ipfs: Send + Sync {
+    /// Fetch the content of `cid` and pin it so it is kept around
+    async fn pin(&self, cid: &str) -> Result<(), IpfsError>;
+
+    /// Whether the content of `cid` is pinned locally, without fetching it
+    async fn has(&self, cid: &str) -> Result<bool, IpfsError>;
+
+    /// Total size in bytes of the content of `cid`
+    async fn size(&self, cid: &str) -> Result<u64, IpfsError>;
+}
+
+/// Kubo HTTP RPC client
+pub struct HttpIpfs {
+    api: Url,
+    client: reqwest::Client,
+}
+
+#[derive(Deserialize)]
+struct FilesStat {
+    #[serde(rename = "CumulativeSize")]
+    cumulative_size: u64,
+}
+
+impl HttpIpfs {
+    pub fn new(api: Url) -> HttpIpfs {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(300))
+            .build()
+            .unwrap_or_default();
+        HttpIpfs { api, client }
+    }
+
+    async fn rpc(
+        &self,
+        command: &str,
+        query: &[(&str, &str)],
+    ) -> Result<reqwest::Response, IpfsError> {
+        let url = self
+            .api
+            .join(&format!("api/v0/{}", command))
+            .map_err(|e| IpfsError(e.to_string()))?;
+        self.client
+            .post(url)
+            .query(query)
+            .send()
+            .await
+            .map_err(|e| IpfsError(format!("{}: {}", command, e)))
+    }
+}
+
+async fn error_body(command: &str, response: reqwest::Response) -> IpfsError {
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    IpfsError(format!("{} returned {}: {}", command, status, body))
+}
+
+#[async_trait]
+impl Ipfs for HttpIpfs {
+    async fn pin(&self, cid: &str) -> Result<(), IpfsError> {
+        let response = self.rpc("pin/add", &[("arg", cid)]).await?;
+        if !response.status().is_success() {
+            return Err(error_body("pin/add", response).await);
+        }
+        Ok(())
+    }
+
+    async fn has(&self, cid: &str) -> Result<bool, IpfsError> {
+        let response = self
+            .rpc("pin/ls", &[("arg", cid), ("type", "recursive")])
+            .await?;
+        if response.status().is_success() {
+            return Ok(true);
+        }
+        // Kubo answers 500 with "is not pinned" for content it does not hold
+        let error = error_body("pin/ls", response).await;
+        if error.0.contains("not pinned") {
+            Ok(false)
+        } else {
+            Err(error)
+        }
+    }
+
+    async fn size(&self, cid: &str) -> Result<u64, IpfsError> {
+        let path = format!("/ipfs/{}", cid);
+        let response = self.rpc("files/stat", &[("arg", &path)]).await?;
+        if !response.status().is_success() {
+            return Err(error_body("files/stat", response).await);
+        }
+        let stat: FilesStat = response
+            .json()
+            .await
+            .map_err(|e| IpfsError(format!("files/stat: {}", e)))?;
+        Ok(stat.cumulative_size)
+    }
+}
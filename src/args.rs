@@ -1,14 +1,26 @@
 use clap::Parser;
-// use structopt::StructOpt;
+use std::path::PathBuf;
 use url::Url;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 pub struct Args {
-    #[arg(short = 's', long, value_parser, default_value = "ws://127.0.0.1:9944")]
-    pub node_server: Url,
-    #[arg(short, long, value_parser, default_value = "http://127.0.0.1:4000")]
-    pub listen: Url,
-    #[arg(short, long = "db-uri", value_parser)]
-    pub db: Option<String>,
+    /// TOML configuration file, overridden by environment variables and flags
+    #[arg(short, long, value_parser)]
+    pub config: Option<PathBuf>,
+    /// Node websocket endpoint [default: ws://127.0.0.1:9944]
+    #[arg(short = 's', long, value_parser)]
+    pub node_server: Option<Url>,
+    /// Address the API listens on [default: http://127.0.0.1:4000]
+    #[arg(short, long, value_parser)]
+    pub listen: Option<Url>,
+    /// Run the storage provider agent next to the gateway
+    #[arg(long)]
+    pub agent: bool,
+    /// Name of the `agent.keys` entry the agent signs with
+    #[arg(long, value_parser)]
+    pub key: Option<String>,
+    /// Pool whose manifests the agent stores
+    #[arg(long, value_parser)]
+    pub pool: Option<u32>,
 }
@@ -12,9 +12,20 @@ use sugarfunge_api_types::sugarfunge;
 use sugarfunge_api_types::sugarfunge::runtime_types::sp_core::bounded::bounded_vec::BoundedVec;
 
 /// Create an asset class for an account
+#[utoipa::path(
+    post,
+    path = "/asset/create_class",
+    tag = "asset",
+    request_body = CreateClassInput,
+    responses(
+        (status = 200, body = CreateClassOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["asset:write"]), ("bearer" = ["asset:write"])),
+)]
 pub async fn create_class(
     data: web::Data<AppState>,
-    req: web::Json<CreateClassInput>,
+    req: Json<CreateClassInput>,
 ) -> error::Result<HttpResponse> {
     let pair = get_pair_from_seed(&req.seed)?;
     let signer = PairSigner::new(pair);
@@ -29,11 +40,7 @@ pub async fn create_class(
         .create_class(to.into(), req.class_id.into(), metadata);
 
     let result = api
-        .tx()
-        .sign_and_submit_then_watch(&call, &signer, Default::default())
-        .await
-        .map_err(map_subxt_err)?
-        .wait_for_finalized_success()
+        .sign_and_submit(&call, &signer)
         .await
         .map_err(map_sf_err)?;
     let result = result
@@ -52,9 +59,20 @@ pub async fn create_class(
 }
 
 /// Get class info
+#[utoipa::path(
+    post,
+    path = "/asset/class_info",
+    tag = "asset",
+    request_body = ClassInfoInput,
+    responses(
+        (status = 200, body = ClassInfoOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["read"]), ("bearer" = ["read"])),
+)]
 pub async fn class_info(
     data: web::Data<AppState>,
-    req: web::Json<ClassInfoInput>,
+    req: Json<ClassInfoInput>,
 ) -> error::Result<HttpResponse> {
     let api = &data.api;
 
@@ -62,7 +80,7 @@ pub async fn class_info(
         .asset()
         .classes(u64::from(req.class_id));
 
-    let storage = api.storage().at_latest().await.map_err(map_subxt_err)?;
+    let storage = api.storage().await.map_err(map_subxt_err)?;
 
     let info = storage.fetch(&call).await.map_err(map_subxt_err)?;
 
@@ -79,9 +97,20 @@ pub async fn class_info(
 }
 
 /// Create an asset for class
+#[utoipa::path(
+    post,
+    path = "/asset/create",
+    tag = "asset",
+    request_body = CreateInput,
+    responses(
+        (status = 200, body = CreateOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["asset:write"]), ("bearer" = ["asset:write"])),
+)]
 pub async fn create(
     data: web::Data<AppState>,
-    req: web::Json<CreateInput>,
+    req: Json<CreateInput>,
 ) -> error::Result<HttpResponse> {
     let pair = get_pair_from_seed(&req.seed)?;
     let signer = PairSigner::new(pair);
@@ -95,11 +124,7 @@ pub async fn create(
             .create_asset(req.class_id.into(), req.asset_id.into(), metadata);
 
     let result = api
-        .tx()
-        .sign_and_submit_then_watch(&call, &signer, Default::default())
-        .await
-        .map_err(map_subxt_err)?
-        .wait_for_finalized_success()
+        .sign_and_submit(&call, &signer)
         .await
         .map_err(map_sf_err)?;
     let result = result
@@ -119,9 +144,20 @@ pub async fn create(
 }
 
 /// Get asset info
+#[utoipa::path(
+    post,
+    path = "/asset/info",
+    tag = "asset",
+    request_body = AssetInfoInput,
+    responses(
+        (status = 200, body = AssetInfoOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["read"]), ("bearer" = ["read"])),
+)]
 pub async fn info(
     data: web::Data<AppState>,
-    req: web::Json<AssetInfoInput>,
+    req: Json<AssetInfoInput>,
 ) -> error::Result<HttpResponse> {
     let api = &data.api;
 
@@ -129,7 +165,7 @@ pub async fn info(
         .asset()
         .assets(u64::from(req.class_id), u64::from(req.asset_id));
 
-    let storage = api.storage().at_latest().await.map_err(map_subxt_err)?;
+    let storage = api.storage().await.map_err(map_subxt_err)?;
 
     let info = storage.fetch(&call).await.map_err(map_subxt_err)?;
 
@@ -146,9 +182,20 @@ pub async fn info(
 }
 
 /// Update asset class metadata
+#[utoipa::path(
+    post,
+    path = "/asset/update_metadata",
+    tag = "asset",
+    request_body = UpdateMetadataInput,
+    responses(
+        (status = 200, body = UpdateMetadataOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["asset:write"]), ("bearer" = ["asset:write"])),
+)]
 pub async fn update_metadata(
     data: web::Data<AppState>,
-    req: web::Json<UpdateMetadataInput>,
+    req: Json<UpdateMetadataInput>,
 ) -> error::Result<HttpResponse> {
     let pair = get_pair_from_seed(&req.seed)?;
     let signer = PairSigner::new(pair);
@@ -163,11 +210,7 @@ pub async fn update_metadata(
     );
 
     let result = api
-        .tx()
-        .sign_and_submit_then_watch(&call, &signer, Default::default())
-        .await
-        .map_err(map_subxt_err)?
-        .wait_for_finalized_success()
+        .sign_and_submit(&call, &signer)
         .await
         .map_err(map_sf_err)?;
     let result = result
@@ -188,10 +231,18 @@ pub async fn update_metadata(
 }
 
 /// Mint amount of asset to account
-pub async fn mint(
-    data: web::Data<AppState>,
-    req: web::Json<MintInput>,
-) -> error::Result<HttpResponse> {
+#[utoipa::path(
+    post,
+    path = "/asset/mint",
+    tag = "asset",
+    request_body = MintInput,
+    responses(
+        (status = 200, body = MintOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["asset:write"]), ("bearer" = ["asset:write"])),
+)]
+pub async fn mint(data: web::Data<AppState>, req: Json<MintInput>) -> error::Result<HttpResponse> {
     let pair = get_pair_from_seed(&req.seed)?;
     let signer = PairSigner::new(pair);
     let to = subxt::utils::AccountId32::try_from(&req.to).map_err(map_account_err)?;
@@ -205,11 +256,7 @@ pub async fn mint(
     );
 
     let result = api
-        .tx()
-        .sign_and_submit_then_watch(&call, &signer, Default::default())
-        .await
-        .map_err(map_subxt_err)?
-        .wait_for_finalized_success()
+        .sign_and_submit(&call, &signer)
         .await
         .map_err(map_sf_err)?;
     let result = result
@@ -231,10 +278,18 @@ pub async fn mint(
 }
 
 /// Burn amount of asset from account
-pub async fn burn(
-    data: web::Data<AppState>,
-    req: web::Json<BurnInput>,
-) -> error::Result<HttpResponse> {
+#[utoipa::path(
+    post,
+    path = "/asset/burn",
+    tag = "asset",
+    request_body = BurnInput,
+    responses(
+        (status = 200, body = BurnOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["asset:write"]), ("bearer" = ["asset:write"])),
+)]
+pub async fn burn(data: web::Data<AppState>, req: Json<BurnInput>) -> error::Result<HttpResponse> {
     let pair = get_pair_from_seed(&req.seed)?;
     let signer = PairSigner::new(pair);
     let from = subxt::utils::AccountId32::try_from(&req.from).map_err(map_account_err)?;
@@ -248,11 +303,7 @@ pub async fn burn(
     );
 
     let result = api
-        .tx()
-        .sign_and_submit_then_watch(&call, &signer, Default::default())
-        .await
-        .map_err(map_subxt_err)?
-        .wait_for_finalized_success()
+        .sign_and_submit(&call, &signer)
         .await
         .map_err(map_sf_err)?;
     let result = result
@@ -274,9 +325,20 @@ pub async fn burn(
 }
 
 /// Get balance for given asset
+#[utoipa::path(
+    post,
+    path = "/asset/balance",
+    tag = "asset",
+    request_body = AssetBalanceInput,
+    responses(
+        (status = 200, body = AssetBalanceOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["read"]), ("bearer" = ["read"])),
+)]
 pub async fn balance(
     data: web::Data<AppState>,
-    req: web::Json<AssetBalanceInput>,
+    req: Json<AssetBalanceInput>,
 ) -> error::Result<HttpResponse> {
     let account =
         sp_core::sr25519::Public::from_str(req.account.as_str()).map_err(map_account_err)?;
@@ -289,7 +351,7 @@ pub async fn balance(
         u64::from(req.asset_id),
     );
 
-    let storage = api.storage().at_latest().await.map_err(map_subxt_err)?;
+    let storage = api.storage().await.map_err(map_subxt_err)?;
 
     let amount = storage.fetch(&call).await.map_err(map_subxt_err)?;
 
@@ -305,9 +367,20 @@ pub async fn balance(
 }
 
 /// Get balances for owner and maybe class
+#[utoipa::path(
+    post,
+    path = "/asset/balances",
+    tag = "asset",
+    request_body = AssetBalancesInput,
+    responses(
+        (status = 200, body = AssetBalancesOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["read"]), ("bearer" = ["read"])),
+)]
 pub async fn balances(
     data: web::Data<AppState>,
-    req: web::Json<AssetBalancesInput>,
+    req: Json<AssetBalancesInput>,
 ) -> error::Result<HttpResponse> {
     let account =
         sp_core::sr25519::Public::from_str(&req.account.as_str()).map_err(map_account_err)?;
@@ -333,7 +406,7 @@ pub async fn balances(
     //     println!("query_key asset_id len: {}", query_key.len());
     // }
 
-    let storage = api.storage().at_latest().await.map_err(map_subxt_err)?;
+    let storage = api.storage().await.map_err(map_subxt_err)?;
 
     let keys = storage
         .fetch_keys(&query_key, 1000, None)
@@ -361,7 +434,7 @@ pub async fn balances(
         let asset_id = u64::decode(&mut &asset_key[..]);
         // println!("asset_id: {:?}", asset_id);
 
-        let storage = api.storage().at_latest().await.map_err(map_subxt_err)?;
+        let storage = api.storage().await.map_err(map_subxt_err)?;
 
         if let Some(storage_data) = storage.fetch_raw(&key.0).await.map_err(map_subxt_err)? {
             let value = u128::decode(&mut &storage_data[..]);
@@ -384,9 +457,20 @@ pub async fn balances(
 }
 
 /// Transfer asset from to accounts
+#[utoipa::path(
+    post,
+    path = "/asset/transfer_from",
+    tag = "asset",
+    request_body = TransferFromInput,
+    responses(
+        (status = 200, body = TransferFromOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["asset:write"]), ("bearer" = ["asset:write"])),
+)]
 pub async fn transfer_from(
     data: web::Data<AppState>,
-    req: web::Json<TransferFromInput>,
+    req: Json<TransferFromInput>,
 ) -> error::Result<HttpResponse> {
     let pair = get_pair_from_seed(&req.seed)?;
     let signer = PairSigner::new(pair);
@@ -403,11 +487,7 @@ pub async fn transfer_from(
     );
 
     let result = api
-        .tx()
-        .sign_and_submit_then_watch(&call, &signer, Default::default())
-        .await
-        .map_err(map_subxt_err)?
-        .wait_for_finalized_success()
+        .sign_and_submit(&call, &signer)
         .await
         .map_err(map_sf_err)?;
     let result = result
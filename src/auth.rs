@@ -0,0 +1,354 @@
+use crate::config::AuthConfig;
+use crate::rate_limit::{RateLimit, RouteClass};
+use crate::state::AppState;
+use crate::util::RequestError;
+use actix_web::body::EitherBody;
+use actix_web::dev::{
+    forward_ready, HttpServiceFactory, Service, ServiceRequest, ServiceResponse, Transform,
+};
+use actix_web::{http, web, Error, HttpMessage, HttpResponse, Route};
+use futures::future::{ready, LocalBoxFuture, Ready};
+use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
+pub use sugarfunge_api_types::endpoints::API_KEY_HEADER;
+
+/// Permission attached to a route, granted by an API key or a JWT `scope` claim
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(try_from = "String", into = "String")]
+pub enum Scope {
+    /// Reachable without credentials, for the routes signing in with an account
+    Public,
+    /// Granted to accounts signed in with a signature, only the session
+    /// routes accept it
+    Session,
+    Read,
+    AccountWrite,
+    AssetWrite,
+    FulaWrite,
+    PoolWrite,
+    ContractAdmin,
+    ValidatorAdmin,
+    /// Grants every other scope
+    Admin,
+}
+
+impl Scope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Scope::Public => "public",
+            Scope::Session => "session",
+            Scope::Read => "read",
+            Scope::AccountWrite => "account:write",
+            Scope::AssetWrite => "asset:write",
+            Scope::FulaWrite => "fula:write",
+            Scope::PoolWrite => "pool:write",
+            Scope::ContractAdmin => "contract:admin",
+            Scope::ValidatorAdmin => "validator:admin",
+            Scope::Admin => "admin",
+        }
+    }
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Scope {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Scope, String> {
+        match value {
+            "public" => Ok(Scope::Public),
+            "session" => Ok(Scope::Session),
+            "read" => Ok(Scope::Read),
+            "account:write" => Ok(Scope::AccountWrite),
+            "asset:write" => Ok(Scope::AssetWrite),
+            "fula:write" => Ok(Scope::FulaWrite),
+            "pool:write" => Ok(Scope::PoolWrite),
+            "contract:admin" => Ok(Scope::ContractAdmin),
+            "validator:admin" => Ok(Scope::ValidatorAdmin),
+            "admin" => Ok(Scope::Admin),
+            _ => Err(format!("unknown scope {}", value)),
+        }
+    }
+}
+
+impl TryFrom<String> for Scope {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Scope, String> {
+        Scope::from_str(&value)
+    }
+}
+
+impl From<Scope> for String {
+    fn from(scope: Scope) -> String {
+        scope.as_str().to_string()
+    }
+}
+
+/// Authenticated caller, available to handlers through the request extensions
+#[derive(Clone, Debug)]
+pub struct Identity {
+    /// API key name or JWT subject
+    pub subject: String,
+    pub scopes: HashSet<Scope>,
+}
+
+impl Identity {
+    pub fn allows(&self, scope: Scope) -> bool {
+        scope == Scope::Public
+            || self.scopes.contains(&scope)
+            || self.scopes.contains(&Scope::Admin)
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct Claims {
+    sub: String,
+    /// Space separated list of scopes, as in OAuth 2.0
+    #[serde(default)]
+    scope: String,
+}
+
+/// Claims of the bearer tokens issued when an account signs in
+#[derive(Serialize, Debug)]
+struct SessionClaims<'a> {
+    sub: &'a str,
+    scope: String,
+    iat: u64,
+    exp: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    iss: Option<&'a str>,
+}
+
+/// Bearer token granting the `session` scope to `subject` until `expires_at`,
+/// a unix timestamp. Signed with the secret bearer tokens are verified with.
+pub fn issue_session_token(
+    auth: &AuthConfig,
+    subject: &str,
+    issued_at: u64,
+    expires_at: u64,
+) -> Result<String, String> {
+    let secret = match &auth.jwt_secret {
+        Some(secret) => secret,
+        None => return Err("Sessions need auth.jwt_secret to sign bearer tokens".into()),
+    };
+    let claims = SessionClaims {
+        sub: subject,
+        scope: Scope::Session.to_string(),
+        iat: issued_at,
+        exp: expires_at,
+        iss: auth.jwt_issuer.as_deref(),
+    };
+    encode(
+        &Header::new(Algorithm::HS256),
+        &claims,
+        &EncodingKey::from_secret(secret.as_bytes()),
+    )
+    .map_err(|e| format!("Could not sign the session token: {}", e))
+}
+
+/// Register a route that is only reachable with the given scope, rate
+/// limited with the budget of its route class
+pub fn scoped(path: &str, scope: Scope, route: Route) -> impl HttpServiceFactory {
+    web::resource(path)
+        .wrap(RateLimit(RouteClass::from_scope(scope)))
+        .wrap(RequireScope(scope))
+        .route(route)
+}
+
+/// Middleware rejecting requests whose credentials do not grant the scope,
+/// before the wrapped handler gets to sign anything
+pub struct RequireScope(pub Scope);
+
+impl<S, B> Transform<S, ServiceRequest> for RequireScope
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = RequireScopeMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequireScopeMiddleware {
+            service,
+            scope: self.0,
+        }))
+    }
+}
+
+pub struct RequireScopeMiddleware<S> {
+    service: S,
+    scope: Scope,
+}
+
+impl<S, B> Service<ServiceRequest> for RequireScopeMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let auth = match req.app_data::<web::Data<AppState>>() {
+            Some(data) => data.config.auth.clone(),
+            None => {
+                return reject(
+                    req,
+                    HttpResponse::InternalServerError().json(RequestError {
+                        message: json!("Missing application state"),
+                        description: "Error in auth::RequireScope".into(),
+                    }),
+                )
+            }
+        };
+
+        if auth.enabled && self.scope != Scope::Public {
+            match authenticate(&req, &auth) {
+                Ok(Some(identity)) if identity.allows(self.scope) => {
+                    req.extensions_mut().insert(identity);
+                }
+                Ok(Some(identity)) => {
+                    return reject(
+                        req,
+                        HttpResponse::Forbidden().json(RequestError {
+                            message: json!(format!("Missing scope {}", self.scope)),
+                            description: format!(
+                                "{} is not allowed to call this route",
+                                identity.subject
+                            ),
+                        }),
+                    )
+                }
+                Ok(None) => {
+                    return reject(
+                        req,
+                        HttpResponse::Unauthorized().json(RequestError {
+                            message: json!("Missing credentials"),
+                            description: format!(
+                                "Provide an {} header or a bearer token with scope {}",
+                                API_KEY_HEADER, self.scope
+                            ),
+                        }),
+                    )
+                }
+                Err(description) => {
+                    return reject(
+                        req,
+                        HttpResponse::Unauthorized().json(RequestError {
+                            message: json!("Invalid credentials"),
+                            description,
+                        }),
+                    )
+                }
+            }
+        }
+
+        let fut = self.service.call(req);
+        Box::pin(async move { fut.await.map(ServiceResponse::map_into_left_body) })
+    }
+}
+
+fn reject<B: 'static>(
+    req: ServiceRequest,
+    response: HttpResponse,
+) -> LocalBoxFuture<'static, Result<ServiceResponse<EitherBody<B>>, Error>> {
+    let (req, _) = req.into_parts();
+    let response = ServiceResponse::new(req, response).map_into_right_body();
+    Box::pin(ready(Ok(response)))
+}
+
+/// Resolve the caller from an API key or a bearer token. Websocket clients
+/// cannot set headers, so GET requests may pass them as query parameters.
+fn authenticate(req: &ServiceRequest, auth: &AuthConfig) -> Result<Option<Identity>, String> {
+    let query = web::Query::<CredentialsQuery>::from_query(req.query_string())
+        .map(|query| query.into_inner())
+        .unwrap_or_default();
+    let from_query = req.method() == http::Method::GET;
+
+    let api_key = req
+        .headers()
+        .get(API_KEY_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(String::from)
+        .or(query.api_key.filter(|_| from_query));
+    if let Some(api_key) = api_key {
+        return match auth
+            .api_keys
+            .iter()
+            .find(|entry| constant_time_eq(entry.key.as_bytes(), api_key.as_bytes()))
+        {
+            Some(entry) => Ok(Some(Identity {
+                subject: entry.name.clone(),
+                scopes: entry.scopes.iter().cloned().collect(),
+            })),
+            None => Err("Unknown API key".into()),
+        };
+    }
+
+    let token = req
+        .headers()
+        .get(http::header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(String::from)
+        .or(query.access_token.filter(|_| from_query));
+    if let Some(token) = token {
+        let secret = match &auth.jwt_secret {
+            Some(secret) => secret,
+            None => return Err("Bearer tokens are not accepted by this server".into()),
+        };
+        let mut validation = Validation::new(Algorithm::HS256);
+        if let Some(issuer) = &auth.jwt_issuer {
+            validation.set_issuer(&[issuer]);
+        }
+        let claims = decode::<Claims>(
+            token.trim(),
+            &DecodingKey::from_secret(secret.as_bytes()),
+            &validation,
+        )
+        .map_err(|e| format!("Invalid bearer token: {}", e))?
+        .claims;
+        return Ok(Some(Identity {
+            subject: claims.sub,
+            scopes: claims
+                .scope
+                .split_whitespace()
+                .filter_map(|scope| Scope::from_str(scope).ok())
+                .collect(),
+        }));
+    }
+
+    Ok(None)
+}
+
+#[derive(Deserialize, Debug, Default)]
+struct CredentialsQuery {
+    api_key: Option<String>,
+    access_token: Option<String>,
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter()
+        .zip(b.iter())
+        .fold(0u8, |acc, (x, y)| acc | (x ^ y))
+        == 0
+}
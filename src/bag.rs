@@ -11,9 +11,20 @@ use sugarfunge_api_types::primitives::*;
 use sugarfunge_api_types::sugarfunge;
 use sugarfunge_api_types::sugarfunge::runtime_types::sp_core::bounded::bounded_vec::BoundedVec;
 
+#[utoipa::path(
+    post,
+    path = "/bag/register",
+    tag = "bag",
+    request_body = RegisterInput,
+    responses(
+        (status = 200, body = RegisterOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["asset:write"]), ("bearer" = ["asset:write"])),
+)]
 pub async fn register(
     data: web::Data<AppState>,
-    req: web::Json<RegisterInput>,
+    req: Json<RegisterInput>,
 ) -> error::Result<HttpResponse> {
     let pair = get_pair_from_seed(&req.seed)?;
     let signer = PairSigner::new(pair);
@@ -26,11 +37,7 @@ pub async fn register(
         .register(req.class_id.into(), metadata);
 
     let result = api
-        .tx()
-        .sign_and_submit_then_watch(&call, &signer, Default::default())
-        .await
-        .map_err(map_subxt_err)?
-        .wait_for_finalized_success()
+        .sign_and_submit(&call, &signer)
         .await
         .map_err(map_sf_err)?;
     let result = result
@@ -62,9 +69,20 @@ pub fn transform_owners_output(in_owners: Vec<AccountId32>) -> Vec<String> {
         .collect()
 }
 
+#[utoipa::path(
+    post,
+    path = "/bag/create",
+    tag = "bag",
+    request_body = bag::CreateInput,
+    responses(
+        (status = 200, body = bag::CreateOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["asset:write"]), ("bearer" = ["asset:write"])),
+)]
 pub async fn create(
     data: web::Data<AppState>,
-    req: web::Json<CreateInput>,
+    req: Json<CreateInput>,
 ) -> error::Result<HttpResponse> {
     let pair = get_pair_from_seed(&req.seed)?;
     let signer = PairSigner::new(pair);
@@ -78,11 +96,7 @@ pub async fn create(
     );
 
     let result = api
-        .tx()
-        .sign_and_submit_then_watch(&call, &signer, Default::default())
-        .await
-        .map_err(map_subxt_err)?
-        .wait_for_finalized_success()
+        .sign_and_submit(&call, &signer)
         .await
         .map_err(map_sf_err)?;
     let result = result
@@ -102,9 +116,20 @@ pub async fn create(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/bag/sweep",
+    tag = "bag",
+    request_body = SweepInput,
+    responses(
+        (status = 200, body = SweepOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["asset:write"]), ("bearer" = ["asset:write"])),
+)]
 pub async fn sweep(
     data: web::Data<AppState>,
-    req: web::Json<SweepInput>,
+    req: Json<SweepInput>,
 ) -> error::Result<HttpResponse> {
     let pair = get_pair_from_seed(&req.seed)?;
     let signer = PairSigner::new(pair);
@@ -115,11 +140,7 @@ pub async fn sweep(
     let call = sugarfunge::tx().bag().sweep(to.into(), bag.into());
 
     let result = api
-        .tx()
-        .sign_and_submit_then_watch(&call, &signer, Default::default())
-        .await
-        .map_err(map_subxt_err)?
-        .wait_for_finalized_success()
+        .sign_and_submit(&call, &signer)
         .await
         .map_err(map_sf_err)?;
     let result = result
@@ -138,9 +159,20 @@ pub async fn sweep(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/bag/deposit",
+    tag = "bag",
+    request_body = DepositInput,
+    responses(
+        (status = 200, body = DepositOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["asset:write"]), ("bearer" = ["asset:write"])),
+)]
 pub async fn deposit(
     data: web::Data<AppState>,
-    req: web::Json<DepositInput>,
+    req: Json<DepositInput>,
 ) -> error::Result<HttpResponse> {
     let pair = get_pair_from_seed(&req.seed)?;
     let signer = PairSigner::new(pair);
@@ -155,11 +187,7 @@ pub async fn deposit(
     );
 
     let result = api
-        .tx()
-        .sign_and_submit_then_watch(&call, &signer, Default::default())
-        .await
-        .map_err(map_subxt_err)?
-        .wait_for_finalized_success()
+        .sign_and_submit(&call, &signer)
         .await
         .map_err(map_sf_err)?;
     let result = result
@@ -19,9 +19,20 @@ fn hash(s: &[u8]) -> sp_core::H256 {
     sp_io::hashing::blake2_256(s).into()
 }
 
+#[utoipa::path(
+    post,
+    path = "/bundle/register",
+    tag = "bundle",
+    request_body = RegisterBundleInput,
+    responses(
+        (status = 200, body = RegisterBundleOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["asset:write"]), ("bearer" = ["asset:write"])),
+)]
 pub async fn register_bundle(
     data: web::Data<AppState>,
-    req: web::Json<RegisterBundleInput>,
+    req: Json<RegisterBundleInput>,
 ) -> error::Result<HttpResponse> {
     let pair = get_pair_from_seed(&req.seed)?;
     let signer = PairSigner::new(pair);
@@ -56,11 +67,7 @@ pub async fn register_bundle(
     );
 
     let result = api
-        .tx()
-        .sign_and_submit_then_watch(&call, &signer, Default::default())
-        .await
-        .map_err(map_subxt_err)?
-        .wait_for_finalized_success()
+        .sign_and_submit(&call, &signer)
         .await
         .map_err(map_sf_err)?;
     let result = result
@@ -80,9 +87,20 @@ pub async fn register_bundle(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/bundle/mint",
+    tag = "bundle",
+    request_body = MintBundleInput,
+    responses(
+        (status = 200, body = MintBundleOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["asset:write"]), ("bearer" = ["asset:write"])),
+)]
 pub async fn mint_bundle(
     data: web::Data<AppState>,
-    req: web::Json<MintBundleInput>,
+    req: Json<MintBundleInput>,
 ) -> error::Result<HttpResponse> {
     let pair = get_pair_from_seed(&req.seed)?;
     let signer = PairSigner::new(pair);
@@ -99,11 +117,7 @@ pub async fn mint_bundle(
     );
 
     let result = api
-        .tx()
-        .sign_and_submit_then_watch(&call, &signer, Default::default())
-        .await
-        .map_err(map_subxt_err)?
-        .wait_for_finalized_success()
+        .sign_and_submit(&call, &signer)
         .await
         .map_err(map_sf_err)?;
     let result = result
@@ -124,9 +138,20 @@ pub async fn mint_bundle(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/bundle/burn",
+    tag = "bundle",
+    request_body = BurnBundleInput,
+    responses(
+        (status = 200, body = BurnBundleOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["asset:write"]), ("bearer" = ["asset:write"])),
+)]
 pub async fn burn_bundle(
     data: web::Data<AppState>,
-    req: web::Json<BurnBundleInput>,
+    req: Json<BurnBundleInput>,
 ) -> error::Result<HttpResponse> {
     let pair = get_pair_from_seed(&req.seed)?;
     let signer = PairSigner::new(pair);
@@ -143,11 +168,7 @@ pub async fn burn_bundle(
     );
 
     let result = api
-        .tx()
-        .sign_and_submit_then_watch(&call, &signer, Default::default())
-        .await
-        .map_err(map_subxt_err)?
-        .wait_for_finalized_success()
+        .sign_and_submit(&call, &signer)
         .await
         .map_err(map_sf_err)?;
     let result = result
@@ -168,6 +189,16 @@ pub async fn burn_bundle(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/bundle",
+    tag = "bundle",
+    responses(
+        (status = 200, body = GetBundles),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["read"]), ("bearer" = ["read"])),
+)]
 pub async fn get_bundles_id(data: web::Data<AppState>) -> error::Result<HttpResponse> {
     let api = &data.api;
 
@@ -177,7 +208,7 @@ pub async fn get_bundles_id(data: web::Data<AppState>) -> error::Result<HttpResp
         .asset_bundles_root()
         .to_root_bytes();
 
-    let storage = api.storage().at_latest().await.map_err(map_subxt_err)?;
+    let storage = api.storage().await.map_err(map_subxt_err)?;
 
     let keys = storage
         .fetch_keys(&query_key, 1000, None)
@@ -226,7 +257,7 @@ pub async fn verify_bundle_exist(
         .asset_bundles_root()
         .to_root_bytes();
 
-    let storage = api.storage().at_latest().await.map_err(map_subxt_err)?;
+    let storage = api.storage().await.map_err(map_subxt_err)?;
 
     let keys = storage
         .fetch_keys(&query_key, 1000, None)
@@ -246,6 +277,16 @@ pub async fn verify_bundle_exist(
     return Ok(false);
 }
 
+#[utoipa::path(
+    post,
+    path = "/bundle/data",
+    tag = "bundle",
+    responses(
+        (status = 200, body = GetBundlesData),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["read"]), ("bearer" = ["read"])),
+)]
 pub async fn get_bundles_data(data: web::Data<AppState>) -> error::Result<HttpResponse> {
     let api = &data.api;
 
@@ -255,7 +296,7 @@ pub async fn get_bundles_data(data: web::Data<AppState>) -> error::Result<HttpRe
         .bundles_root()
         .to_root_bytes();
 
-    let storage = api.storage().at_latest().await.map_err(map_subxt_err)?;
+    let storage = api.storage().await.map_err(map_subxt_err)?;
 
     let keys = storage
         .fetch_keys(&query_key, 1000, None)
@@ -0,0 +1,384 @@
+use async_trait::async_trait;
+use codec::Decode;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use sp_core::hashing::twox_128;
+use subxt::constants::ConstantAddress;
+use subxt::events::{EventDetails, Phase, StaticEvent};
+use subxt::ext::frame_metadata::StorageEntryType;
+use subxt::metadata::DecodeWithMetadata;
+use subxt::rpc::types::Health;
+use subxt::storage::StorageAddress;
+use subxt::tx::{PairSigner, TxPayload};
+use subxt::utils::H256;
+use subxt::{Metadata, OnlineClient, PolkadotConfig};
+
+pub mod mock;
+
+pub type Signer = PairSigner<PolkadotConfig, sp_core::sr25519::Pair>;
+
+/// Everything the handlers need from the chain, so that they can run
+/// against a node or against the in-memory [`mock::MockChain`]
+#[async_trait]
+pub trait Chain: Send + Sync {
+    /// Runtime metadata, used to encode calls and storage keys
+    fn metadata(&self) -> Metadata;
+
+    /// Sign and submit an encoded call, wait until its block is finalized and
+    /// return the events it emitted, or its dispatch error
+    async fn submit(&self, call: Vec<u8>, signer: &Signer) -> Result<Vec<RawEvent>, subxt::Error>;
+
+    /// Every block finalized from now on, with its events
+    async fn watch_blocks(
+        &self,
+    ) -> Result<BoxStream<'static, Result<Block, subxt::Error>>, subxt::Error>;
+
+    /// Number of the latest finalized block
+    async fn finalized_block_number(&self) -> Result<u32, subxt::Error>;
+
+    /// Finalized block `number` with its events, none past the finalized head
+    async fn block(&self, number: u32) -> Result<Option<Block>, subxt::Error>;
+
+    /// Hash of the latest block, to read storage from a consistent state
+    async fn latest_block(&self) -> Result<H256, subxt::Error>;
+
+    async fn fetch_raw(&self, key: &[u8], at: H256) -> Result<Option<Vec<u8>>, subxt::Error>;
+
+    /// Up to `count` keys starting with `prefix`, in key order, after `start_key`
+    async fn fetch_keys(
+        &self,
+        prefix: &[u8],
+        count: u32,
+        start_key: Option<&[u8]>,
+        at: H256,
+    ) -> Result<Vec<Vec<u8>>, subxt::Error>;
+
+    async fn health(&self) -> Result<Health, subxt::Error>;
+}
+
+impl dyn Chain {
+    pub async fn sign_and_submit<Call: TxPayload>(
+        &self,
+        call: &Call,
+        signer: &Signer,
+    ) -> Result<Events, subxt::Error> {
+        let call = call.encode_call_data(&self.metadata())?;
+        self.submit(call, signer).await.map(Events)
+    }
+
+    /// Events of every block finalized from now on, one item per block
+    pub async fn watch_events(
+        &self,
+    ) -> Result<BoxStream<'static, Result<Vec<RawEvent>, subxt::Error>>, subxt::Error> {
+        let blocks = self.watch_blocks().await?;
+        Ok(blocks.map(|block| block.map(|block| block.events)).boxed())
+    }
+
+    /// Storage at the latest block
+    pub async fn storage(&self) -> Result<Storage<'_>, subxt::Error> {
+        let at = self.latest_block().await?;
+        Ok(Storage { chain: self, at })
+    }
+
+    /// Runtime constant, read from the metadata
+    pub fn constant<Address: ConstantAddress>(
+        &self,
+        address: &Address,
+    ) -> Result<<Address::Target as DecodeWithMetadata>::Target, subxt::Error> {
+        let metadata = self.metadata();
+        let constant = metadata
+            .pallet(address.pallet_name())?
+            .constant(address.constant_name())?;
+        let value = Address::Target::decode_with_metadata(
+            &mut &constant.value[..],
+            constant.ty.id(),
+            &metadata,
+        )?;
+        Ok(value)
+    }
+}
+
+/// A finalized block and the events it emitted
+#[derive(Clone, Debug)]
+pub struct Block {
+    pub number: u32,
+    pub hash: H256,
+    /// `Timestamp::Now` of the block, unix time in milliseconds
+    pub timestamp: u64,
+    pub events: Vec<RawEvent>,
+}
+
+/// SCALE encoded event, decoded on demand with the generated event types
+#[derive(Clone, Debug)]
+pub struct RawEvent {
+    pub pallet: String,
+    pub variant: String,
+    pub fields: Vec<u8>,
+    /// Index in its block of the extrinsic that emitted the event, none for
+    /// the events of block initialization and finalization
+    pub extrinsic_index: Option<u32>,
+}
+
+impl RawEvent {
+    pub fn new<E: StaticEvent + codec::Encode>(event: &E) -> RawEvent {
+        RawEvent {
+            pallet: E::PALLET.into(),
+            variant: E::EVENT.into(),
+            fields: event.encode(),
+            extrinsic_index: None,
+        }
+    }
+
+    pub fn is<E: StaticEvent>(&self) -> bool {
+        self.pallet == E::PALLET && self.variant == E::EVENT
+    }
+
+    pub fn as_event<E: StaticEvent + Decode>(&self) -> Result<Option<E>, codec::Error> {
+        if !self.is::<E>() {
+            return Ok(None);
+        }
+        E::decode(&mut &self.fields[..]).map(Some)
+    }
+}
+
+/// Events emitted by a submitted extrinsic
+#[derive(Clone, Debug)]
+pub struct Events(pub Vec<RawEvent>);
+
+impl Events {
+    pub fn find_first<E: StaticEvent + Decode>(&self) -> Result<Option<E>, subxt::Error> {
+        for event in self.0.iter() {
+            if let Some(event) = event.as_event::<E>()? {
+                return Ok(Some(event));
+            }
+        }
+        Ok(None)
+    }
+
+    pub fn find<E: StaticEvent + Decode>(&self) -> Result<Vec<E>, subxt::Error> {
+        let mut found = Vec::new();
+        for event in self.0.iter() {
+            if let Some(event) = event.as_event::<E>()? {
+                found.push(event);
+            }
+        }
+        Ok(found)
+    }
+}
+
+pub struct StorageKey(pub Vec<u8>);
+
+/// Keys fetched per request by [`Storage::fetch_all_keys`]
+const KEYS_PAGE_SIZE: u32 = 1000;
+
+/// Storage reads pinned to one block
+pub struct Storage<'a> {
+    chain: &'a dyn Chain,
+    at: H256,
+}
+
+impl Storage<'_> {
+    pub fn block_hash(&self) -> H256 {
+        self.at
+    }
+
+    pub async fn fetch<Address: StorageAddress>(
+        &self,
+        address: &Address,
+    ) -> Result<Option<<Address::Target as DecodeWithMetadata>::Target>, subxt::Error> {
+        let metadata = self.chain.metadata();
+        let key = subxt::storage::utils::storage_address_bytes(address, &metadata)?;
+        let value = match self.chain.fetch_raw(&key, self.at).await? {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+        let return_ty = match &metadata
+            .pallet(address.pallet_name())?
+            .storage(address.entry_name())?
+            .ty
+        {
+            StorageEntryType::Plain(ty) => ty.id(),
+            StorageEntryType::Map { value, .. } => value.id(),
+        };
+        let value = Address::Target::decode_with_metadata(&mut &value[..], return_ty, &metadata)?;
+        Ok(Some(value))
+    }
+
+    pub async fn fetch_raw(&self, key: &[u8]) -> Result<Option<Vec<u8>>, subxt::Error> {
+        self.chain.fetch_raw(key, self.at).await
+    }
+
+    pub async fn fetch_keys(
+        &self,
+        prefix: &[u8],
+        count: u32,
+        start_key: Option<&[u8]>,
+    ) -> Result<Vec<StorageKey>, subxt::Error> {
+        let keys = self
+            .chain
+            .fetch_keys(prefix, count, start_key, self.at)
+            .await?;
+        Ok(keys.into_iter().map(StorageKey).collect())
+    }
+
+    /// Every key starting with `prefix`, fetched a page at a time
+    pub async fn fetch_all_keys(&self, prefix: &[u8]) -> Result<Vec<StorageKey>, subxt::Error> {
+        let mut keys: Vec<StorageKey> = Vec::new();
+        loop {
+            let start_key = keys.last().map(|key| key.0.clone());
+            let page = self
+                .fetch_keys(prefix, KEYS_PAGE_SIZE, start_key.as_deref())
+                .await?;
+            let last_page = page.len() < KEYS_PAGE_SIZE as usize;
+            keys.extend(page);
+            if last_page {
+                return Ok(keys);
+            }
+        }
+    }
+}
+
+/// A node reached over its websocket RPC
+pub struct NodeChain {
+    api: OnlineClient<PolkadotConfig>,
+}
+
+impl NodeChain {
+    pub fn new(api: OnlineClient<PolkadotConfig>) -> NodeChain {
+        NodeChain { api }
+    }
+}
+
+/// Call data that was already encoded against the node metadata
+struct EncodedCall(Vec<u8>);
+
+impl TxPayload for EncodedCall {
+    fn encode_call_data_to(
+        &self,
+        _metadata: &Metadata,
+        out: &mut Vec<u8>,
+    ) -> Result<(), subxt::Error> {
+        out.extend_from_slice(&self.0);
+        Ok(())
+    }
+}
+
+fn raw_event(event: EventDetails) -> RawEvent {
+    RawEvent {
+        pallet: event.pallet_name().into(),
+        variant: event.variant_name().into(),
+        fields: event.field_bytes().to_vec(),
+        extrinsic_index: match event.phase() {
+            Phase::ApplyExtrinsic(index) => Some(index),
+            _ => None,
+        },
+    }
+}
+
+/// Storage key of `Timestamp::Now`
+fn timestamp_key() -> Vec<u8> {
+    [twox_128(b"Timestamp"), twox_128(b"Now")].concat()
+}
+
+/// Events and timestamp of a block read from the node
+async fn read_block(
+    api: &OnlineClient<PolkadotConfig>,
+    block: subxt::blocks::Block<PolkadotConfig, OnlineClient<PolkadotConfig>>,
+) -> Result<Block, subxt::Error> {
+    let events = block.events().await?;
+    let events = events
+        .iter()
+        .map(|event| event.map(raw_event))
+        .collect::<Result<Vec<_>, _>>()?;
+    let timestamp = match api
+        .storage()
+        .at(block.hash())
+        .fetch_raw(&timestamp_key())
+        .await?
+    {
+        Some(timestamp) => u64::decode(&mut &timestamp[..])?,
+        None => 0,
+    };
+    Ok(Block {
+        number: block.number(),
+        hash: block.hash(),
+        timestamp,
+        events,
+    })
+}
+
+#[async_trait]
+impl Chain for NodeChain {
+    fn metadata(&self) -> Metadata {
+        self.api.metadata()
+    }
+
+    async fn submit(&self, call: Vec<u8>, signer: &Signer) -> Result<Vec<RawEvent>, subxt::Error> {
+        let events = self
+            .api
+            .tx()
+            .sign_and_submit_then_watch(&EncodedCall(call), signer, Default::default())
+            .await?
+            .wait_for_finalized_success()
+            .await?;
+        events.iter().map(|event| event.map(raw_event)).collect()
+    }
+
+    async fn watch_blocks(
+        &self,
+    ) -> Result<BoxStream<'static, Result<Block, subxt::Error>>, subxt::Error> {
+        let api = self.api.clone();
+        let blocks = self.api.blocks().subscribe_finalized().await?;
+        Ok(blocks
+            .then(move |block| {
+                let api = api.clone();
+                async move { read_block(&api, block?).await }
+            })
+            .boxed())
+    }
+
+    async fn finalized_block_number(&self) -> Result<u32, subxt::Error> {
+        let hash = self.api.rpc().finalized_head().await?;
+        Ok(self.api.blocks().at(hash).await?.number())
+    }
+
+    async fn block(&self, number: u32) -> Result<Option<Block>, subxt::Error> {
+        if number > self.finalized_block_number().await? {
+            return Ok(None);
+        }
+        let hash = match self.api.rpc().block_hash(Some(number.into())).await? {
+            Some(hash) => hash,
+            None => return Ok(None),
+        };
+        let block = self.api.blocks().at(hash).await?;
+        read_block(&self.api, block).await.map(Some)
+    }
+
+    async fn latest_block(&self) -> Result<H256, subxt::Error> {
+        Ok(self.api.blocks().at_latest().await?.hash())
+    }
+
+    async fn fetch_raw(&self, key: &[u8], at: H256) -> Result<Option<Vec<u8>>, subxt::Error> {
+        self.api.storage().at(at).fetch_raw(key).await
+    }
+
+    async fn fetch_keys(
+        &self,
+        prefix: &[u8],
+        count: u32,
+        start_key: Option<&[u8]>,
+        at: H256,
+    ) -> Result<Vec<Vec<u8>>, subxt::Error> {
+        let keys = self
+            .api
+            .storage()
+            .at(at)
+            .fetch_keys(prefix, count, start_key)
+            .await?;
+        Ok(keys.into_iter().map(|key| key.0).collect())
+    }
+
+    async fn health(&self) -> Result<Health, subxt::Error> {
+        self.api.rpc().system_health().await
+    }
+}
@@ -0,0 +1,1911 @@
+//! In-memory chain simulating the pallets behind the API routes, so that the
+//! handlers can be exercised without a node.
+//!
+//! Storage is kept as raw key/value pairs laid out like the runtime lays them
+//! out, so the handlers read it with the same keys and offsets as on a node.
+//! Every submitted extrinsic is applied at once in a new block. Fees,
+//! weights and randomness are not simulated.
+
+use super::{Block, Chain, RawEvent, Signer};
+use async_trait::async_trait;
+use codec::{Decode, Encode};
+use futures::channel::mpsc;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use sp_core::hashing::{blake2_128, blake2_256, twox_128};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use subxt::ext::frame_metadata::RuntimeMetadataPrefixed;
+use subxt::ext::sp_core::sr25519::Public;
+use subxt::rpc::types::Health;
+use subxt::utils::{AccountId32, MultiAddress, H256};
+use subxt::{Metadata, PolkadotConfig};
+use sugarfunge_api_types::sugarfunge;
+use sugarfunge_api_types::sugarfunge::runtime_types::frame_system::AccountInfo;
+use sugarfunge_api_types::sugarfunge::runtime_types::fula_pool::{
+    pallet::Call as PoolCall, Pool, PoolRequest, User,
+};
+use sugarfunge_api_types::sugarfunge::runtime_types::functionland_fula::{
+    pallet::Call as FulaCall, Challenge, ChallengeState, ClaimData, Manifest, ManifestAvailable,
+    ManifestStorageData, ManifestWithPoolId, StorerData, UploaderData,
+};
+use sugarfunge_api_types::sugarfunge::runtime_types::pallet_balances::{
+    pallet::Call as BalancesCall, AccountData,
+};
+use sugarfunge_api_types::sugarfunge::runtime_types::pallet_sudo::pallet::Call as SudoCall;
+use sugarfunge_api_types::sugarfunge::runtime_types::sp_core::bounded::bounded_vec::BoundedVec;
+use sugarfunge_api_types::sugarfunge::runtime_types::sugarfunge_asset::{
+    pallet::Call as AssetCall, Asset, Class,
+};
+use sugarfunge_api_types::sugarfunge::runtime_types::sugarfunge_bag::{
+    pallet::Call as BagCall, BagClass,
+};
+use sugarfunge_api_types::sugarfunge::runtime_types::sugarfunge_bundle::pallet::Call as BundleCall;
+use sugarfunge_api_types::sugarfunge::runtime_types::sugarfunge_market::{
+    pallet::Call as MarketCall, AmountOp, AssetRate, Market, RateAccount, RateAction,
+};
+use sugarfunge_api_types::sugarfunge::runtime_types::sugarfunge_runtime::RuntimeCall;
+use sugarfunge_api_types::sugarfunge::runtime_types::sugarfunge_validator_set::pallet::Call as ValidatorSetCall;
+
+const METADATA: &[u8] =
+    include_bytes!("../../crates/sugarfunge-api-types/sugarfunge_metadata.scale");
+
+/// `ExistentialDeposit` of the runtime in the metadata file
+const EXISTENTIAL_DEPOSIT: u128 = 500;
+
+/// `Timestamp::Now` advances by this much per block, from zero at genesis
+const BLOCK_TIME_MS: u64 = 6_000;
+
+/// Bundle schema: class ids, asset ids per class and amounts per asset
+type Schema = (Vec<u64>, Vec<Vec<u64>>, Vec<Vec<u128>>);
+
+/// `sugarfunge_bundle::Bundle` fields, in storage order
+type Bundle = (AccountId32, u64, u64, Vec<u8>, Schema, AccountId32);
+
+/// `sugarfunge_bag::Bag` fields, in storage order
+type Bag = (AccountId32, u64, u64, u128);
+
+type Result<T> = std::result::Result<T, subxt::Error>;
+
+pub struct MockChain {
+    metadata: Metadata,
+    state: Mutex<State>,
+    /// Every block after genesis, in order
+    blocks: Mutex<Vec<Block>>,
+    watchers: Mutex<Vec<mpsc::UnboundedSender<Result<Block>>>>,
+    /// Finalized blocks are not sent to the watchers, as when a subscription
+    /// misses blocks while it is renewed
+    muted: Mutex<bool>,
+}
+
+impl Default for MockChain {
+    fn default() -> Self {
+        MockChain::new()
+    }
+}
+
+impl MockChain {
+    /// Empty chain where Alice is the sudo key and the only validator
+    pub fn new() -> MockChain {
+        let metadata = RuntimeMetadataPrefixed::decode(&mut &METADATA[..])
+            .expect("metadata file should decode");
+        let metadata = Metadata::try_from(metadata).expect("metadata file should be supported");
+
+        let alice = alice();
+        let mut state = State {
+            storage: BTreeMap::new(),
+            block: 0,
+            sudo: alice.clone(),
+        };
+        state.put(validators_key(), &vec![alice]);
+
+        MockChain {
+            metadata,
+            state: Mutex::new(state),
+            blocks: Mutex::new(Vec::new()),
+            watchers: Mutex::new(Vec::new()),
+            muted: Mutex::new(false),
+        }
+    }
+
+    /// Give `account` a free native balance
+    pub fn endow(self, account: &AccountId32, amount: u128) -> MockChain {
+        self.state.lock().unwrap().set_free(account, amount);
+        self
+    }
+
+    /// Nest an existing pool under `parent`, which the runtime only does at
+    /// genesis
+    pub fn set_pool_parent(&self, pool_id: u32, parent: Option<u32>) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        let mut pool = state.get_pool(pool_id)?;
+        pool.parent = parent;
+        state.put(pool_key(pool_id), &pool);
+        Ok(())
+    }
+
+    /// Stop or resume sending finalized blocks to the watchers
+    pub fn mute_watchers(&self, muted: bool) {
+        *self.muted.lock().unwrap() = muted;
+    }
+}
+
+#[async_trait]
+impl Chain for MockChain {
+    fn metadata(&self) -> Metadata {
+        self.metadata.clone()
+    }
+
+    async fn submit(&self, call: Vec<u8>, signer: &Signer) -> Result<Vec<RawEvent>> {
+        let who = <Signer as subxt::tx::Signer<PolkadotConfig>>::account_id(signer).clone();
+        let call = RuntimeCall::decode(&mut &call[..])?;
+
+        let block = {
+            let mut state = self.state.lock().unwrap();
+            state.bump_nonce(&who);
+            // Dispatch on a copy so that a failing call leaves no trace
+            let mut next = state.clone();
+            let mut events = next.dispatch(&who, call)?;
+            // The call is the only extrinsic after the timestamp inherent
+            for event in events.iter_mut() {
+                event.extrinsic_index = Some(1);
+            }
+            next.block += 1;
+            let timestamp = next.block * BLOCK_TIME_MS;
+            next.put(block_number_key(), &(next.block as u32));
+            next.put(timestamp_key(), &timestamp);
+            *state = next;
+            Block {
+                number: state.block as u32,
+                hash: H256::from_low_u64_be(state.block),
+                timestamp,
+                events,
+            }
+        };
+
+        let events = block.events.clone();
+        self.blocks.lock().unwrap().push(block.clone());
+        if !*self.muted.lock().unwrap() {
+            self.watchers
+                .lock()
+                .unwrap()
+                .retain(|watcher| watcher.unbounded_send(Ok(block.clone())).is_ok());
+        }
+        Ok(events)
+    }
+
+    async fn watch_blocks(&self) -> Result<BoxStream<'static, Result<Block>>> {
+        let (tx, rx) = mpsc::unbounded();
+        self.watchers.lock().unwrap().push(tx);
+        Ok(rx.boxed())
+    }
+
+    async fn finalized_block_number(&self) -> Result<u32> {
+        Ok(self.state.lock().unwrap().block as u32)
+    }
+
+    async fn block(&self, number: u32) -> Result<Option<Block>> {
+        let blocks = self.blocks.lock().unwrap();
+        Ok(number
+            .checked_sub(1)
+            .and_then(|index| blocks.get(index as usize))
+            .cloned())
+    }
+
+    async fn latest_block(&self) -> Result<H256> {
+        Ok(H256::from_low_u64_be(self.state.lock().unwrap().block))
+    }
+
+    /// Only the latest state is kept, `at` is ignored
+    async fn fetch_raw(&self, key: &[u8], _at: H256) -> Result<Option<Vec<u8>>> {
+        Ok(self.state.lock().unwrap().storage.get(key).cloned())
+    }
+
+    async fn fetch_keys(
+        &self,
+        prefix: &[u8],
+        count: u32,
+        start_key: Option<&[u8]>,
+        _at: H256,
+    ) -> Result<Vec<Vec<u8>>> {
+        let state = self.state.lock().unwrap();
+        Ok(state
+            .keys(prefix)
+            .into_iter()
+            .filter(|key| start_key.map_or(true, |start| key.as_slice() > start))
+            .take(count as usize)
+            .collect())
+    }
+
+    async fn health(&self) -> Result<Health> {
+        Ok(Health {
+            peers: 0,
+            is_syncing: false,
+            should_have_peers: false,
+        })
+    }
+}
+
+fn alice() -> AccountId32 {
+    AccountId32::from(Public::from(sp_keyring::AccountKeyring::Alice.public()))
+}
+
+fn module_error(pallet: &str, error: &str) -> subxt::Error {
+    subxt::Error::Other(format!("Module error: {}::{}", pallet, error))
+}
+
+fn unsupported(call: &str) -> subxt::Error {
+    subxt::Error::Other(format!("{} is not simulated by the mock chain", call))
+}
+
+fn ensure(condition: bool, pallet: &str, error: &str) -> Result<()> {
+    if condition {
+        Ok(())
+    } else {
+        Err(module_error(pallet, error))
+    }
+}
+
+/// Amount moved by a market rate for `amount` exchanges
+fn rate_amount(price: i128, amount: u128) -> Result<u128> {
+    u128::try_from(price)
+        .ok()
+        .and_then(|price| price.checked_mul(amount))
+        .ok_or_else(|| module_error("Market", "InvalidRateAmount"))
+}
+
+/// Account holding the assets of a bag, bundle or market
+fn sub_account(tag: &[u8], id: impl Encode) -> AccountId32 {
+    AccountId32(blake2_256(&(tag, id).encode()))
+}
+
+fn storage_key(pallet: &str, item: &str, parts: &[Vec<u8>]) -> Vec<u8> {
+    let mut key = twox_128(pallet.as_bytes()).to_vec();
+    key.extend(twox_128(item.as_bytes()));
+    for part in parts {
+        key.extend(part);
+    }
+    key
+}
+
+/// `Blake2_128Concat` hashed key part
+fn concat(value: &impl Encode) -> Vec<u8> {
+    let value = value.encode();
+    let mut part = blake2_128(&value).to_vec();
+    part.extend(value);
+    part
+}
+
+/// `Blake2_128` hashed key part
+fn opaque(value: &impl Encode) -> Vec<u8> {
+    blake2_128(&value.encode()).to_vec()
+}
+
+/// Reads back the `Blake2_128Concat` parts of a storage map key
+struct KeyParts<'a>(&'a [u8]);
+
+impl<'a> KeyParts<'a> {
+    fn new(key: &'a [u8]) -> Self {
+        KeyParts(&key[32..])
+    }
+
+    fn next<T: Decode>(&mut self) -> T {
+        self.0 = &self.0[16..];
+        T::decode(&mut self.0).expect("mock storage keys are well formed")
+    }
+}
+
+fn block_number_key() -> Vec<u8> {
+    storage_key("System", "Number", &[])
+}
+
+fn timestamp_key() -> Vec<u8> {
+    storage_key("Timestamp", "Now", &[])
+}
+
+fn account_key(who: &AccountId32) -> Vec<u8> {
+    storage_key("System", "Account", &[concat(who)])
+}
+
+fn validators_key() -> Vec<u8> {
+    storage_key("ValidatorSet", "Validators", &[])
+}
+
+fn class_key(class_id: u64) -> Vec<u8> {
+    storage_key("Asset", "Classes", &[concat(&class_id)])
+}
+
+fn asset_key(class_id: u64, asset_id: u64) -> Vec<u8> {
+    storage_key("Asset", "Assets", &[concat(&class_id), opaque(&asset_id)])
+}
+
+fn asset_balance_key(who: &AccountId32, class_id: u64, asset_id: u64) -> Vec<u8> {
+    storage_key(
+        "Asset",
+        "Balances",
+        &[concat(who), concat(&class_id), concat(&asset_id)],
+    )
+}
+
+fn bag_class_key(class_id: u64) -> Vec<u8> {
+    storage_key("Bag", "BagClasses", &[opaque(&class_id)])
+}
+
+fn bag_key(bag: &AccountId32) -> Vec<u8> {
+    storage_key("Bag", "Bags", &[opaque(bag)])
+}
+
+fn next_bag_id_key(class_id: u64) -> Vec<u8> {
+    storage_key("Bag", "NextBagId", &[opaque(&class_id)])
+}
+
+fn bundle_key(bundle_id: &H256) -> Vec<u8> {
+    storage_key("Bundle", "Bundles", &[concat(bundle_id)])
+}
+
+fn asset_bundle_key(class_id: u64, asset_id: u64) -> Vec<u8> {
+    storage_key(
+        "Bundle",
+        "AssetBundles",
+        &[concat(&class_id), concat(&asset_id)],
+    )
+}
+
+fn market_key(market_id: u64) -> Vec<u8> {
+    storage_key("Market", "Markets", &[opaque(&market_id)])
+}
+
+fn market_rates_key(market_id: u64, market_rate_id: u64) -> Vec<u8> {
+    storage_key(
+        "Market",
+        "MarketRates",
+        &[concat(&market_id), concat(&market_rate_id)],
+    )
+}
+
+fn last_pool_id_key() -> Vec<u8> {
+    storage_key("Pool", "LastPoolId", &[])
+}
+
+fn pool_key(pool_id: u32) -> Vec<u8> {
+    storage_key("Pool", "Pools", &[concat(&pool_id)])
+}
+
+fn pool_request_key(pool_id: u32, who: &AccountId32) -> Vec<u8> {
+    storage_key("Pool", "PoolRequests", &[concat(&pool_id), concat(who)])
+}
+
+fn user_key(who: &AccountId32) -> Vec<u8> {
+    storage_key("Pool", "Users", &[concat(who)])
+}
+
+fn manifest_key(pool_id: u32, cid: &Vec<u8>) -> Vec<u8> {
+    storage_key("Fula", "Manifests", &[concat(&pool_id), concat(cid)])
+}
+
+fn storer_data_key(pool_id: u32, who: &AccountId32, cid: &Vec<u8>) -> Vec<u8> {
+    storage_key(
+        "Fula",
+        "ManifestsStorerData",
+        &[concat(&pool_id), concat(who), concat(cid)],
+    )
+}
+
+fn challenge_key(who: &AccountId32, cid: &Vec<u8>) -> Vec<u8> {
+    storage_key("Fula", "ChallengeRequests", &[concat(who), concat(cid)])
+}
+
+fn claim_key(who: &AccountId32) -> Vec<u8> {
+    storage_key("Fula", "Claims", &[concat(who)])
+}
+
+#[derive(Clone)]
+struct State {
+    storage: BTreeMap<Vec<u8>, Vec<u8>>,
+    block: u64,
+    sudo: AccountId32,
+}
+
+impl State {
+    fn get<T: Decode>(&self, key: &[u8]) -> Option<T> {
+        self.storage
+            .get(key)
+            .map(|value| T::decode(&mut &value[..]).expect("mock storage values are well formed"))
+    }
+
+    fn put<T: Encode>(&mut self, key: Vec<u8>, value: &T) {
+        self.storage.insert(key, value.encode());
+    }
+
+    fn remove(&mut self, key: &[u8]) {
+        self.storage.remove(key);
+    }
+
+    fn contains(&self, key: &[u8]) -> bool {
+        self.storage.contains_key(key)
+    }
+
+    fn keys(&self, prefix: &[u8]) -> Vec<Vec<u8>> {
+        self.storage
+            .range(prefix.to_vec()..)
+            .map(|(key, _)| key)
+            .take_while(|key| key.starts_with(prefix))
+            .cloned()
+            .collect()
+    }
+
+    fn dispatch(&mut self, who: &AccountId32, call: RuntimeCall) -> Result<Vec<RawEvent>> {
+        match call {
+            RuntimeCall::Balances(call) => self.balances(who, call),
+            RuntimeCall::Sudo(call) => self.sudo(who, call),
+            RuntimeCall::Asset(call) => self.asset(who, call),
+            RuntimeCall::Bag(call) => self.bag(who, call),
+            RuntimeCall::Bundle(call) => self.bundle(who, call),
+            RuntimeCall::Market(call) => self.market(who, call),
+            RuntimeCall::Fula(call) => self.fula(who, call),
+            RuntimeCall::Pool(call) => self.pool(who, call),
+            _ => Err(unsupported("call")),
+        }
+    }
+
+    // System and Balances
+
+    fn bump_nonce(&mut self, who: &AccountId32) {
+        let mut info = self.account(who);
+        info.nonce += 1;
+        self.put(account_key(who), &info);
+    }
+
+    fn account(&self, who: &AccountId32) -> AccountInfo<u32, AccountData<u128>> {
+        self.get(&account_key(who)).unwrap_or(AccountInfo {
+            nonce: 0,
+            consumers: 0,
+            providers: 0,
+            sufficients: 0,
+            data: AccountData {
+                free: 0,
+                reserved: 0,
+                misc_frozen: 0,
+                fee_frozen: 0,
+            },
+        })
+    }
+
+    fn free(&self, who: &AccountId32) -> u128 {
+        self.account(who).data.free
+    }
+
+    fn set_free(&mut self, who: &AccountId32, free: u128) {
+        let mut info = self.account(who);
+        info.data.free = free;
+        info.providers = u32::from(free > 0);
+        self.put(account_key(who), &info);
+    }
+
+    fn transfer(&mut self, from: &AccountId32, to: &AccountId32, amount: u128) -> Result<()> {
+        let free = self.free(from);
+        ensure(free >= amount, "Balances", "InsufficientBalance")?;
+        self.set_free(from, free - amount);
+        self.set_free(to, self.free(to) + amount);
+        Ok(())
+    }
+
+    fn balances(&mut self, who: &AccountId32, call: BalancesCall) -> Result<Vec<RawEvent>> {
+        let free = self.free(who);
+        let (dest, amount, keep_alive) = match call {
+            BalancesCall::transfer { dest, value } => (dest, value, false),
+            BalancesCall::transfer_keep_alive { dest, value } => (dest, value, true),
+            BalancesCall::transfer_all { dest, keep_alive } => {
+                let amount = if keep_alive {
+                    free.saturating_sub(EXISTENTIAL_DEPOSIT)
+                } else {
+                    free
+                };
+                (dest, amount, keep_alive)
+            }
+            _ => return Err(unsupported("Balances call")),
+        };
+        let to = match dest {
+            MultiAddress::Id(to) => to,
+            _ => return Err(unsupported("Balances destination")),
+        };
+        ensure(free >= amount, "Balances", "InsufficientBalance")?;
+        ensure(
+            self.free(&to) + amount >= EXISTENTIAL_DEPOSIT,
+            "Balances",
+            "ExistentialDeposit",
+        )?;
+        let left = free - amount;
+        ensure(
+            !keep_alive || left >= EXISTENTIAL_DEPOSIT,
+            "Balances",
+            "KeepAlive",
+        )?;
+        self.transfer(who, &to, amount)?;
+        // The sender is reaped with the dust it has left
+        if left < EXISTENTIAL_DEPOSIT {
+            self.set_free(who, 0);
+        }
+        Ok(vec![RawEvent::new(
+            &sugarfunge::balances::events::Transfer {
+                from: who.clone(),
+                to,
+                amount,
+            },
+        )])
+    }
+
+    fn sudo(&mut self, who: &AccountId32, call: SudoCall) -> Result<Vec<RawEvent>> {
+        ensure(*who == self.sudo, "Sudo", "RequireSudo")?;
+        let call = match call {
+            SudoCall::sudo { call } => *call,
+            _ => return Err(unsupported("Sudo call")),
+        };
+        let mut validators: Vec<AccountId32> = self.get(&validators_key()).unwrap_or_default();
+        let event = match call {
+            RuntimeCall::ValidatorSet(ValidatorSetCall::add_validator { validator_id }) => {
+                ensure(
+                    !validators.contains(&validator_id),
+                    "ValidatorSet",
+                    "Duplicate",
+                )?;
+                validators.push(validator_id.clone());
+                RawEvent::new(
+                    &sugarfunge::validator_set::events::ValidatorAdditionInitiated(validator_id),
+                )
+            }
+            RuntimeCall::ValidatorSet(ValidatorSetCall::remove_validator { validator_id }) => {
+                ensure(
+                    validators.contains(&validator_id),
+                    "ValidatorSet",
+                    "ValidatorNotApproved",
+                )?;
+                validators.retain(|validator| *validator != validator_id);
+                RawEvent::new(
+                    &sugarfunge::validator_set::events::ValidatorRemovalInitiated(validator_id),
+                )
+            }
+            _ => return Err(unsupported("Sudo dispatched call")),
+        };
+        self.put(validators_key(), &validators);
+        Ok(vec![event])
+    }
+
+    // Asset
+
+    fn asset_balance(&self, who: &AccountId32, class_id: u64, asset_id: u64) -> u128 {
+        self.get(&asset_balance_key(who, class_id, asset_id))
+            .unwrap_or_default()
+    }
+
+    fn set_asset_balance(&mut self, who: &AccountId32, class_id: u64, asset_id: u64, value: u128) {
+        let key = asset_balance_key(who, class_id, asset_id);
+        if value == 0 {
+            self.remove(&key);
+        } else {
+            self.put(key, &value);
+        }
+    }
+
+    /// `(class_id, asset_id, balance)` of every asset held by `who`
+    fn asset_balances(&self, who: &AccountId32) -> Vec<(u64, u64, u128)> {
+        self.keys(&storage_key("Asset", "Balances", &[concat(who)]))
+            .into_iter()
+            .map(|key| {
+                let mut parts = KeyParts::new(&key);
+                let _: AccountId32 = parts.next();
+                let class_id = parts.next();
+                let asset_id = parts.next();
+                (class_id, asset_id, self.get(&key).unwrap_or_default())
+            })
+            .collect()
+    }
+
+    fn mint_asset(&mut self, to: &AccountId32, class_id: u64, asset_id: u64, amount: u128) {
+        let balance = self.asset_balance(to, class_id, asset_id);
+        self.set_asset_balance(to, class_id, asset_id, balance + amount);
+    }
+
+    fn burn_asset(
+        &mut self,
+        from: &AccountId32,
+        class_id: u64,
+        asset_id: u64,
+        amount: u128,
+    ) -> Result<()> {
+        let balance = self.asset_balance(from, class_id, asset_id);
+        ensure(balance >= amount, "Asset", "InsufficientBalance")?;
+        self.set_asset_balance(from, class_id, asset_id, balance - amount);
+        Ok(())
+    }
+
+    fn transfer_asset(
+        &mut self,
+        from: &AccountId32,
+        to: &AccountId32,
+        class_id: u64,
+        asset_id: u64,
+        amount: u128,
+    ) -> Result<()> {
+        self.burn_asset(from, class_id, asset_id, amount)?;
+        self.mint_asset(to, class_id, asset_id, amount);
+        Ok(())
+    }
+
+    fn create_class(
+        &mut self,
+        owner: &AccountId32,
+        class_id: u64,
+        metadata: Vec<u8>,
+    ) -> Result<()> {
+        ensure(!self.contains(&class_key(class_id)), "Asset", "InUse")?;
+        self.put(
+            class_key(class_id),
+            &Class {
+                owner: owner.clone(),
+                metadata: BoundedVec(metadata),
+            },
+        );
+        Ok(())
+    }
+
+    fn ensure_class_owner(&self, who: &AccountId32, class_id: u64) -> Result<()> {
+        let class: Class<AccountId32, BoundedVec<u8>> = self
+            .get(&class_key(class_id))
+            .ok_or_else(|| module_error("Asset", "ClassNotFound"))?;
+        ensure(class.owner == *who, "Asset", "NoPermission")
+    }
+
+    fn put_asset(
+        &mut self,
+        creator: &AccountId32,
+        class_id: u64,
+        asset_id: u64,
+        metadata: Vec<u8>,
+    ) {
+        self.put(
+            asset_key(class_id, asset_id),
+            &Asset {
+                class_id,
+                creator: creator.clone(),
+                metadata: BoundedVec(metadata),
+            },
+        );
+    }
+
+    fn asset(&mut self, who: &AccountId32, call: AssetCall) -> Result<Vec<RawEvent>> {
+        use sugarfunge::asset::events;
+        let event = match call {
+            AssetCall::create_class {
+                owner,
+                class_id,
+                metadata,
+            } => {
+                self.create_class(&owner, class_id, metadata.0)?;
+                RawEvent::new(&events::ClassCreated {
+                    class_id,
+                    who: owner,
+                })
+            }
+            AssetCall::create_asset {
+                class_id,
+                asset_id,
+                metadata,
+            } => {
+                self.ensure_class_owner(who, class_id)?;
+                ensure(
+                    !self.contains(&asset_key(class_id, asset_id)),
+                    "Asset",
+                    "InUse",
+                )?;
+                self.put_asset(who, class_id, asset_id, metadata.0);
+                RawEvent::new(&events::AssetCreated {
+                    class_id,
+                    asset_id,
+                    who: who.clone(),
+                })
+            }
+            AssetCall::update_class_metadata { class_id, metadata } => {
+                self.ensure_class_owner(who, class_id)?;
+                self.put(
+                    class_key(class_id),
+                    &Class {
+                        owner: who.clone(),
+                        metadata,
+                    },
+                );
+                return Ok(vec![]);
+            }
+            AssetCall::update_asset_metadata {
+                class_id,
+                asset_id,
+                metadata,
+            } => {
+                self.ensure_class_owner(who, class_id)?;
+                ensure(
+                    self.contains(&asset_key(class_id, asset_id)),
+                    "Asset",
+                    "AssetNotFound",
+                )?;
+                self.put_asset(who, class_id, asset_id, metadata.0.clone());
+                RawEvent::new(&events::AssetMetadataUpdated {
+                    class_id,
+                    asset_id,
+                    who: who.clone(),
+                    metadata: metadata.0,
+                })
+            }
+            AssetCall::mint {
+                to,
+                class_id,
+                asset_id,
+                amount,
+            } => {
+                self.ensure_class_owner(who, class_id)?;
+                if !self.contains(&asset_key(class_id, asset_id)) {
+                    self.put_asset(who, class_id, asset_id, vec![]);
+                }
+                self.mint_asset(&to, class_id, asset_id, amount);
+                RawEvent::new(&events::Mint {
+                    who: who.clone(),
+                    to,
+                    class_id,
+                    asset_id,
+                    amount,
+                })
+            }
+            AssetCall::batch_mint {
+                to,
+                class_id,
+                asset_ids,
+                amounts,
+            } => {
+                self.ensure_class_owner(who, class_id)?;
+                ensure(
+                    asset_ids.len() == amounts.len(),
+                    "Asset",
+                    "InvalidArrayLength",
+                )?;
+                for (asset_id, amount) in asset_ids.iter().zip(amounts.iter()) {
+                    self.mint_asset(&to, class_id, *asset_id, *amount);
+                }
+                RawEvent::new(&events::BatchMint {
+                    who: who.clone(),
+                    to,
+                    class_id,
+                    asset_ids,
+                    amounts,
+                })
+            }
+            AssetCall::burn {
+                from,
+                class_id,
+                asset_id,
+                amount,
+            } => {
+                self.ensure_class_owner(who, class_id)?;
+                self.burn_asset(&from, class_id, asset_id, amount)?;
+                RawEvent::new(&events::Burn {
+                    who: who.clone(),
+                    from,
+                    class_id,
+                    asset_id,
+                    amount,
+                })
+            }
+            AssetCall::batch_burn {
+                from,
+                class_id,
+                asset_ids,
+                amounts,
+            } => {
+                self.ensure_class_owner(who, class_id)?;
+                ensure(
+                    asset_ids.len() == amounts.len(),
+                    "Asset",
+                    "InvalidArrayLength",
+                )?;
+                for (asset_id, amount) in asset_ids.iter().zip(amounts.iter()) {
+                    self.burn_asset(&from, class_id, *asset_id, *amount)?;
+                }
+                RawEvent::new(&events::BatchBurn {
+                    who: who.clone(),
+                    from,
+                    class_id,
+                    asset_ids,
+                    amounts,
+                })
+            }
+            AssetCall::transfer_from {
+                from,
+                to,
+                class_id,
+                asset_id,
+                amount,
+            } => {
+                ensure(from == *who, "Asset", "NoPermission")?;
+                self.transfer_asset(&from, &to, class_id, asset_id, amount)?;
+                RawEvent::new(&events::Transferred {
+                    who: who.clone(),
+                    from,
+                    to,
+                    class_id,
+                    asset_id,
+                    amount,
+                })
+            }
+            AssetCall::batch_transfer_from {
+                from,
+                to,
+                class_id,
+                asset_ids,
+                amounts,
+            } => {
+                ensure(from == *who, "Asset", "NoPermission")?;
+                ensure(
+                    asset_ids.len() == amounts.len(),
+                    "Asset",
+                    "InvalidArrayLength",
+                )?;
+                for (asset_id, amount) in asset_ids.iter().zip(amounts.iter()) {
+                    self.transfer_asset(&from, &to, class_id, *asset_id, *amount)?;
+                }
+                RawEvent::new(&events::BatchTransferred {
+                    who: who.clone(),
+                    from,
+                    to,
+                    class_id,
+                    asset_ids,
+                    amounts,
+                })
+            }
+        };
+        Ok(vec![event])
+    }
+
+    // Bag
+
+    fn bag(&mut self, who: &AccountId32, call: BagCall) -> Result<Vec<RawEvent>> {
+        use sugarfunge::bag::events;
+        let event = match call {
+            BagCall::register { class_id, metadata } => {
+                ensure(
+                    !self.contains(&bag_class_key(class_id)),
+                    "Bag",
+                    "BagClassExists",
+                )?;
+                self.create_class(&sub_account(b"bag", class_id), class_id, metadata.0)?;
+                self.put(
+                    bag_class_key(class_id),
+                    &BagClass {
+                        operator: who.clone(),
+                        class_id,
+                    },
+                );
+                RawEvent::new(&events::Register {
+                    who: who.clone(),
+                    class_id,
+                })
+            }
+            BagCall::create {
+                class_id,
+                owners,
+                shares,
+            } => {
+                ensure(
+                    self.contains(&bag_class_key(class_id)),
+                    "Bag",
+                    "InvalidBagClass",
+                )?;
+                ensure(owners.len() == shares.len(), "Bag", "InvalidArrayLength")?;
+                let asset_id: u64 = self.get(&next_bag_id_key(class_id)).unwrap_or_default();
+                self.put(next_bag_id_key(class_id), &(asset_id + 1));
+
+                let bag = sub_account(b"bag", (class_id, asset_id));
+                let total_shares = shares.iter().sum::<u128>();
+                let value: Bag = (who.clone(), class_id, asset_id, total_shares);
+                self.put(bag_key(&bag), &value);
+                for (owner, share) in owners.iter().zip(shares.iter()) {
+                    self.mint_asset(owner, class_id, asset_id, *share);
+                }
+                RawEvent::new(&events::Created {
+                    bag,
+                    who: who.clone(),
+                    class_id,
+                    asset_id,
+                    owners,
+                })
+            }
+            BagCall::deposit {
+                bag,
+                class_ids,
+                asset_ids,
+                amounts,
+            } => {
+                ensure(self.contains(&bag_key(&bag)), "Bag", "InvalidBag")?;
+                ensure(
+                    class_ids.len() == asset_ids.len() && class_ids.len() == amounts.len(),
+                    "Bag",
+                    "InvalidArrayLength",
+                )?;
+                for ((class_id, asset_ids), amounts) in
+                    class_ids.iter().zip(asset_ids.iter()).zip(amounts.iter())
+                {
+                    ensure(
+                        asset_ids.len() == amounts.len(),
+                        "Bag",
+                        "InvalidArrayLength",
+                    )?;
+                    for (asset_id, amount) in asset_ids.iter().zip(amounts.iter()) {
+                        self.transfer_asset(who, &bag, *class_id, *asset_id, *amount)?;
+                    }
+                }
+                RawEvent::new(&events::Deposit {
+                    bag,
+                    who: who.clone(),
+                })
+            }
+            BagCall::sweep { to, bag } => {
+                let (_, class_id, asset_id, total_shares): Bag = self
+                    .get(&bag_key(&bag))
+                    .ok_or_else(|| module_error("Bag", "InvalidBag"))?;
+                ensure(
+                    self.asset_balance(who, class_id, asset_id) == total_shares,
+                    "Bag",
+                    "InsufficientShares",
+                )?;
+                self.burn_asset(who, class_id, asset_id, total_shares)?;
+                for (class_id, asset_id, amount) in self.asset_balances(&bag) {
+                    self.transfer_asset(&bag, &to, class_id, asset_id, amount)?;
+                }
+                RawEvent::new(&events::Sweep {
+                    bag,
+                    who: who.clone(),
+                    to,
+                })
+            }
+        };
+        Ok(vec![event])
+    }
+
+    // Bundle
+
+    fn bundle(&mut self, who: &AccountId32, call: BundleCall) -> Result<Vec<RawEvent>> {
+        use sugarfunge::bundle::events;
+        let event = match call {
+            BundleCall::register_bundle {
+                class_id,
+                asset_id,
+                bundle_id,
+                schema,
+                metadata,
+            } => {
+                ensure(
+                    H256(blake2_256(&schema.encode())) == bundle_id,
+                    "Bundle",
+                    "InvalidBundleIdForBundle",
+                )?;
+                ensure(
+                    !self.contains(&bundle_key(&bundle_id)),
+                    "Bundle",
+                    "BundleExists",
+                )?;
+                let schema = Schema::decode(&mut &schema.encode()[..])?;
+                let bundle: Bundle = (
+                    who.clone(),
+                    class_id,
+                    asset_id,
+                    metadata.0,
+                    schema,
+                    sub_account(b"bundle", bundle_id),
+                );
+                self.put(bundle_key(&bundle_id), &bundle);
+                self.put(asset_bundle_key(class_id, asset_id), &bundle_id);
+                RawEvent::new(&events::Register {
+                    bundle_id,
+                    who: who.clone(),
+                    class_id,
+                    asset_id,
+                })
+            }
+            BundleCall::mint_bundle {
+                from,
+                to,
+                bundle_id,
+                amount,
+            } => {
+                let (_, class_id, asset_id, _, schema, vault): Bundle = self
+                    .get(&bundle_key(&bundle_id))
+                    .ok_or_else(|| module_error("Bundle", "BundleNotFound"))?;
+                self.move_schema(&schema, &from, &vault, amount)?;
+                self.mint_asset(&to, class_id, asset_id, amount);
+                RawEvent::new(&events::Mint {
+                    bundle_id,
+                    who: who.clone(),
+                    from,
+                    to,
+                    amount,
+                })
+            }
+            BundleCall::burn_bundle {
+                from,
+                to,
+                bundle_id,
+                amount,
+            } => {
+                let (_, class_id, asset_id, _, schema, vault): Bundle = self
+                    .get(&bundle_key(&bundle_id))
+                    .ok_or_else(|| module_error("Bundle", "BundleNotFound"))?;
+                self.burn_asset(&from, class_id, asset_id, amount)?;
+                self.move_schema(&schema, &vault, &to, amount)?;
+                RawEvent::new(&events::Burn {
+                    bundle_id,
+                    who: who.clone(),
+                    from,
+                    to,
+                    amount,
+                })
+            }
+        };
+        Ok(vec![event])
+    }
+
+    /// Transfer `count` times the assets of a bundle schema
+    fn move_schema(
+        &mut self,
+        schema: &Schema,
+        from: &AccountId32,
+        to: &AccountId32,
+        count: u128,
+    ) -> Result<()> {
+        let (class_ids, asset_ids, amounts) = schema;
+        for ((class_id, asset_ids), amounts) in
+            class_ids.iter().zip(asset_ids.iter()).zip(amounts.iter())
+        {
+            for (asset_id, amount) in asset_ids.iter().zip(amounts.iter()) {
+                self.transfer_asset(from, to, *class_id, *asset_id, amount * count)?;
+            }
+        }
+        Ok(())
+    }
+
+    // Market
+
+    fn owned_market(&self, who: &AccountId32, market_id: u64) -> Result<Market<AccountId32>> {
+        let market: Market<AccountId32> = self
+            .get(&market_key(market_id))
+            .ok_or_else(|| module_error("Market", "InvalidMarket"))?;
+        ensure(market.owner == *who, "Market", "InvalidMarketOwner")?;
+        Ok(market)
+    }
+
+    fn market_rates(
+        &self,
+        market_id: u64,
+        market_rate_id: u64,
+    ) -> Result<Vec<AssetRate<AccountId32, u64, u64>>> {
+        self.get(&market_rates_key(market_id, market_rate_id))
+            .ok_or_else(|| module_error("Market", "InvalidMarketRate"))
+    }
+
+    fn market(&mut self, who: &AccountId32, call: MarketCall) -> Result<Vec<RawEvent>> {
+        use sugarfunge::market::events;
+        let event = match call {
+            MarketCall::create_market { market_id } => {
+                ensure(
+                    !self.contains(&market_key(market_id)),
+                    "Market",
+                    "MarketExists",
+                )?;
+                self.put(
+                    market_key(market_id),
+                    &Market {
+                        owner: who.clone(),
+                        vault: sub_account(b"market", market_id),
+                    },
+                );
+                RawEvent::new(&events::Created {
+                    market_id,
+                    who: who.clone(),
+                })
+            }
+            MarketCall::create_market_rate {
+                market_id,
+                market_rate_id,
+                rates,
+            } => {
+                self.owned_market(who, market_id)?;
+                ensure(
+                    !self.contains(&market_rates_key(market_id, market_rate_id)),
+                    "Market",
+                    "MarketRateExists",
+                )?;
+                self.put(market_rates_key(market_id, market_rate_id), &rates);
+                RawEvent::new(&events::RateCreated {
+                    market_id,
+                    market_rate_id,
+                    who: who.clone(),
+                })
+            }
+            MarketCall::deposit {
+                market_id,
+                market_rate_id,
+                amount,
+            } => {
+                let market = self.owned_market(who, market_id)?;
+                let rates = self.market_rates(market_id, market_rate_id)?;
+                let mut balances = Vec::new();
+                for rate in rates.iter() {
+                    let mut balance = 0;
+                    if let (RateAccount::Market, RateAction::Transfer(price)) =
+                        (&rate.from, &rate.action)
+                    {
+                        let total = rate_amount(*price, amount)?;
+                        self.transfer_asset(
+                            who,
+                            &market.vault,
+                            rate.class_id,
+                            rate.asset_id,
+                            total,
+                        )
+                        .map_err(|_| module_error("Market", "InsufficientLiquidity"))?;
+                        balance = total as i128;
+                    }
+                    balances.push((rate, balance));
+                }
+                RawEvent {
+                    pallet: "Market".into(),
+                    variant: "Deposit".into(),
+                    fields: (who, market_id, market_rate_id, amount, balances, true).encode(),
+                    extrinsic_index: None,
+                }
+            }
+            MarketCall::exchange_assets {
+                market_id,
+                market_rate_id,
+                amount,
+            } => {
+                let market: Market<AccountId32> = self
+                    .get(&market_key(market_id))
+                    .ok_or_else(|| module_error("Market", "InvalidMarket"))?;
+                let rates = self.market_rates(market_id, market_rate_id)?;
+                let account = |rate_account: &RateAccount<AccountId32>| match rate_account {
+                    RateAccount::Market => market.vault.clone(),
+                    RateAccount::Account(account) => account.clone(),
+                    RateAccount::Buyer => who.clone(),
+                };
+                let mut balances = Vec::new();
+                for rate in rates.iter() {
+                    let (from, to) = (account(&rate.from), account(&rate.to));
+                    let (class_id, asset_id) = (rate.class_id, rate.asset_id);
+                    let balance = match &rate.action {
+                        RateAction::Transfer(price) => {
+                            let total = rate_amount(*price, amount)?;
+                            self.transfer_asset(&from, &to, class_id, asset_id, total)
+                                .map_err(|_| module_error("Market", "InsufficientAmount"))?;
+                            total as i128
+                        }
+                        RateAction::Mint(price) => {
+                            let total = rate_amount(*price, amount)?;
+                            self.mint_asset(&to, class_id, asset_id, total);
+                            total as i128
+                        }
+                        RateAction::Burn(price) => {
+                            let total = rate_amount(*price, amount)?;
+                            self.burn_asset(&from, class_id, asset_id, total)
+                                .map_err(|_| module_error("Market", "InsufficientAmount"))?;
+                            total as i128
+                        }
+                        RateAction::Has(op, expected) => {
+                            let balance = self.asset_balance(&from, class_id, asset_id) as i128;
+                            let has = match op {
+                                AmountOp::Equal => balance == *expected,
+                                AmountOp::LessThan => balance < *expected,
+                                AmountOp::LessEqualThan => balance <= *expected,
+                                AmountOp::GreaterThan => balance > *expected,
+                                AmountOp::GreaterEqualThan => balance >= *expected,
+                            };
+                            ensure(has, "Market", "InsufficientAmount")?;
+                            balance
+                        }
+                        _ => 0,
+                    };
+                    balances.push((rate, balance));
+                }
+                RawEvent {
+                    pallet: "Market".into(),
+                    variant: "Exchanged".into(),
+                    fields: (who, market_id, market_rate_id, amount, balances, true).encode(),
+                    extrinsic_index: None,
+                }
+            }
+        };
+        Ok(vec![event])
+    }
+
+    // Pool
+
+    fn user(&self, who: &AccountId32) -> Option<User<BoundedVec<u8>>> {
+        self.get(&user_key(who))
+    }
+
+    fn ensure_not_busy(&self, who: &AccountId32) -> Result<()> {
+        let busy = self.user(who).map_or(false, |user| {
+            user.pool_id.is_some() || user.request_pool_id.is_some()
+        });
+        ensure(!busy, "Pool", "UserBusy")
+    }
+
+    fn get_pool(&self, pool_id: u32) -> Result<Pool> {
+        self.get(&pool_key(pool_id))
+            .ok_or_else(|| module_error("Pool", "PoolDoesNotExist"))
+    }
+
+    fn pool(&mut self, who: &AccountId32, call: PoolCall) -> Result<Vec<RawEvent>> {
+        use sugarfunge::pool::events;
+        let event = match call {
+            PoolCall::create {
+                name,
+                region,
+                peer_id,
+            } => {
+                self.ensure_not_busy(who)?;
+                let pool_id = self.get::<u32>(&last_pool_id_key()).unwrap_or_default() + 1;
+                self.put(last_pool_id_key(), &pool_id);
+                self.put(
+                    pool_key(pool_id),
+                    &Pool {
+                        name: BoundedVec(name),
+                        owner: Some(who.clone()),
+                        parent: None,
+                        participants: BoundedVec(vec![who.clone()]),
+                        request_number: 0,
+                        region: BoundedVec(region),
+                    },
+                );
+                self.put(
+                    user_key(who),
+                    &User {
+                        pool_id: Some(pool_id),
+                        request_pool_id: None,
+                        peer_id,
+                    },
+                );
+                RawEvent::new(&events::PoolCreated {
+                    owner: Some(who.clone()),
+                    pool_id,
+                })
+            }
+            PoolCall::join { pool_id, peer_id } => {
+                let mut pool = self.get_pool(pool_id)?;
+                self.ensure_not_busy(who)?;
+                pool.request_number += 1;
+                self.put(pool_key(pool_id), &pool);
+                self.put(
+                    pool_request_key(pool_id, who),
+                    &PoolRequest {
+                        voted: BoundedVec(vec![]),
+                        positive_votes: 0,
+                        peer_id: BoundedVec(peer_id.0.clone()),
+                    },
+                );
+                self.put(
+                    user_key(who),
+                    &User {
+                        pool_id: None,
+                        request_pool_id: Some(pool_id),
+                        peer_id,
+                    },
+                );
+                RawEvent::new(&events::JoinRequested {
+                    account: who.clone(),
+                    pool_id,
+                })
+            }
+            PoolCall::cancel_join { pool_id } => {
+                let mut pool = self.get_pool(pool_id)?;
+                ensure(
+                    self.contains(&pool_request_key(pool_id, who)),
+                    "Pool",
+                    "RequestDoesNotExist",
+                )?;
+                pool.request_number -= 1;
+                self.put(pool_key(pool_id), &pool);
+                self.remove(&pool_request_key(pool_id, who));
+                self.remove(&user_key(who));
+                RawEvent::new(&events::RequestWithdrawn {
+                    account: who.clone(),
+                    pool_id,
+                })
+            }
+            PoolCall::leave_pool { pool_id } => {
+                let mut pool = self.get_pool(pool_id)?;
+                let user = self
+                    .user(who)
+                    .ok_or_else(|| module_error("Pool", "UserDoesNotExist"))?;
+                ensure(user.pool_id == Some(pool_id), "Pool", "AccessDenied")?;
+                pool.participants.0.retain(|participant| participant != who);
+                self.put(pool_key(pool_id), &pool);
+                self.remove(&user_key(who));
+                RawEvent::new(&events::ParticipantLeft {
+                    account: who.clone(),
+                    pool_id,
+                })
+            }
+            PoolCall::vote {
+                pool_id,
+                account,
+                positive,
+                ..
+            } => {
+                let mut pool = self.get_pool(pool_id)?;
+                ensure(pool.participants.0.contains(who), "Pool", "AccessDenied")?;
+                let mut request: PoolRequest = self
+                    .get(&pool_request_key(pool_id, &account))
+                    .ok_or_else(|| module_error("Pool", "RequestDoesNotExist"))?;
+                ensure(!request.voted.0.contains(who), "Pool", "AlreadyVoted")?;
+                request.voted.0.push(who.clone());
+                if positive {
+                    request.positive_votes += 1;
+                }
+
+                let participants = pool.participants.0.len();
+                let positive_votes = request.positive_votes as usize;
+                let negative_votes = request.voted.0.len() - positive_votes;
+                let result = if positive_votes * 2 > participants {
+                    "Accepted"
+                } else if negative_votes * 2 >= participants {
+                    "Denied"
+                } else {
+                    self.put(pool_request_key(pool_id, &account), &request);
+                    return Ok(vec![]);
+                };
+
+                self.remove(&pool_request_key(pool_id, &account));
+                pool.request_number -= 1;
+                if result == "Accepted" {
+                    pool.participants.0.push(account.clone());
+                    self.put(
+                        user_key(&account),
+                        &User {
+                            pool_id: Some(pool_id),
+                            request_pool_id: None,
+                            peer_id: request.peer_id,
+                        },
+                    );
+                } else {
+                    self.remove(&user_key(&account));
+                }
+                self.put(pool_key(pool_id), &pool);
+                RawEvent::new(&events::VotingResult {
+                    account,
+                    pool_id,
+                    result: result.as_bytes().to_vec(),
+                })
+            }
+        };
+        Ok(vec![event])
+    }
+
+    // Fula
+
+    fn ensure_in_pool(&self, who: &AccountId32, pool_id: u32) -> Result<()> {
+        let in_pool = self
+            .user(who)
+            .map_or(false, |user| user.pool_id == Some(pool_id));
+        ensure(in_pool, "Fula", "AccountNotInPool")
+    }
+
+    fn get_manifest(
+        &self,
+        pool_id: u32,
+        cid: &Vec<u8>,
+    ) -> Result<Manifest<AccountId32, BoundedVec<u8>>> {
+        self.get(&manifest_key(pool_id, cid))
+            .ok_or_else(|| module_error("Fula", "ManifestNotFound"))
+    }
+
+    /// `(pool_id, cid, manifest)` of every manifest
+    fn manifests(&self) -> Vec<(u32, Vec<u8>, Manifest<AccountId32, BoundedVec<u8>>)> {
+        self.keys(&storage_key("Fula", "Manifests", &[]))
+            .into_iter()
+            .map(|key| {
+                let mut parts = KeyParts::new(&key);
+                let pool_id = parts.next();
+                let cid = parts.next();
+                (pool_id, cid, self.get(&key).unwrap())
+            })
+            .collect()
+    }
+
+    /// `(pool_id, storer, cid, data)` of every stored manifest
+    fn storer_data(&self) -> Vec<(u32, AccountId32, Vec<u8>, ManifestStorageData)> {
+        self.keys(&storage_key("Fula", "ManifestsStorerData", &[]))
+            .into_iter()
+            .map(|key| {
+                let mut parts = KeyParts::new(&key);
+                let pool_id = parts.next();
+                let storer = parts.next();
+                let cid = parts.next();
+                (pool_id, storer, cid, self.get(&key).unwrap())
+            })
+            .collect()
+    }
+
+    fn upload_manifest(
+        &mut self,
+        who: &AccountId32,
+        manifest: Vec<u8>,
+        cid: Vec<u8>,
+        pool_id: u32,
+        replication_factor: u16,
+    ) -> Result<()> {
+        self.ensure_in_pool(who, pool_id)?;
+        ensure(replication_factor > 0, "Fula", "ReplicationFactorInvalid")?;
+        let uploader = UploaderData {
+            uploader: who.clone(),
+            storers: vec![],
+            replication_factor,
+        };
+        let value = match self.get_manifest(pool_id, &cid) {
+            Ok(mut value) => {
+                let uploaded = value.users_data.iter().any(|data| data.uploader == *who);
+                ensure(!uploaded, "Fula", "ManifestAlreadyExist")?;
+                value.users_data.push(uploader);
+                value
+            }
+            Err(_) => Manifest {
+                users_data: vec![uploader],
+                manifest_metadata: BoundedVec(manifest),
+                size: None,
+            },
+        };
+        self.put(manifest_key(pool_id, &cid), &value);
+        Ok(())
+    }
+
+    fn store_manifest(&mut self, who: &AccountId32, cid: &Vec<u8>, pool_id: u32) -> Result<()> {
+        self.ensure_in_pool(who, pool_id)?;
+        let mut manifest = self.get_manifest(pool_id, cid)?;
+        let stored = manifest
+            .users_data
+            .iter()
+            .any(|data| data.storers.contains(who));
+        ensure(!stored, "Fula", "AccountAlreadyStorer")?;
+        let uploader = manifest
+            .users_data
+            .iter_mut()
+            .find(|data| (data.storers.len() as u16) < data.replication_factor)
+            .ok_or_else(|| module_error("Fula", "ReplicationFactorLimitReached"))?;
+        uploader.storers.push(who.clone());
+        self.put(manifest_key(pool_id, cid), &manifest);
+        self.put(
+            storer_data_key(pool_id, who, cid),
+            &ManifestStorageData {
+                active_cycles: 0,
+                missed_cycles: 0,
+                active_days: 0,
+                challenge_state: ChallengeState::Open,
+            },
+        );
+        Ok(())
+    }
+
+    fn remove_storer(&mut self, who: &AccountId32, cid: &Vec<u8>, pool_id: u32) -> Result<()> {
+        let mut manifest = self.get_manifest(pool_id, cid)?;
+        let uploader = manifest
+            .users_data
+            .iter_mut()
+            .find(|data| data.storers.contains(who))
+            .ok_or_else(|| module_error("Fula", "AccountNotStorer"))?;
+        uploader.storers.retain(|storer| storer != who);
+        self.put(manifest_key(pool_id, cid), &manifest);
+        self.remove(&storer_data_key(pool_id, who, cid));
+        Ok(())
+    }
+
+    fn remove_manifest(&mut self, who: &AccountId32, cid: &Vec<u8>, pool_id: u32) -> Result<()> {
+        let mut manifest = self.get_manifest(pool_id, cid)?;
+        let uploaded = manifest.users_data.iter().any(|data| data.uploader == *who);
+        ensure(uploaded, "Fula", "AccountNotUploader")?;
+        manifest.users_data.retain(|data| data.uploader != *who);
+        if !manifest.users_data.is_empty() {
+            self.put(manifest_key(pool_id, cid), &manifest);
+            return Ok(());
+        }
+        self.remove(&manifest_key(pool_id, cid));
+        for (storer_pool_id, storer, storer_cid, _) in self.storer_data() {
+            if storer_pool_id == pool_id && storer_cid == *cid {
+                self.remove(&storer_data_key(pool_id, &storer, cid));
+            }
+        }
+        Ok(())
+    }
+
+    fn update_file_size(
+        &mut self,
+        who: &AccountId32,
+        cid: &Vec<u8>,
+        pool_id: u32,
+        size: u64,
+    ) -> Result<()> {
+        let mut manifest = self.get_manifest(pool_id, cid)?;
+        let stored = manifest
+            .users_data
+            .iter()
+            .any(|data| data.storers.contains(who));
+        ensure(stored, "Fula", "AccountNotStorer")?;
+        manifest.size = Some(size);
+        self.put(manifest_key(pool_id, cid), &manifest);
+        Ok(())
+    }
+
+    fn claim(&self, who: &AccountId32) -> ClaimData {
+        self.get(&claim_key(who)).unwrap_or(ClaimData {
+            minted_labor_tokens: 0,
+            expected_labor_tokens: 0,
+            challenge_tokens: 0,
+        })
+    }
+
+    fn fula(&mut self, who: &AccountId32, call: FulaCall) -> Result<Vec<RawEvent>> {
+        use sugarfunge::fula::events;
+        let event = match call {
+            FulaCall::upload_manifest {
+                manifest,
+                cid,
+                pool_id,
+                replication_factor,
+            } => {
+                self.upload_manifest(who, manifest.0.clone(), cid.0, pool_id, replication_factor)?;
+                RawEvent::new(&events::ManifestOutput {
+                    uploader: who.clone(),
+                    storer: vec![],
+                    pool_id,
+                    manifest: manifest.0,
+                })
+            }
+            FulaCall::batch_upload_manifest {
+                manifest,
+                cids,
+                pool_id,
+                replication_factor,
+            } => {
+                ensure(
+                    manifest.len() == cids.len()
+                        && cids.len() == pool_id.len()
+                        && cids.len() == replication_factor.len(),
+                    "Fula",
+                    "InvalidArrayLength",
+                )?;
+                let manifests: Vec<Vec<u8>> = manifest.into_iter().map(|value| value.0).collect();
+                for (((manifest, cid), pool_id), replication_factor) in manifests
+                    .iter()
+                    .zip(cids.into_iter())
+                    .zip(pool_id.iter())
+                    .zip(replication_factor.iter())
+                {
+                    self.upload_manifest(
+                        who,
+                        manifest.clone(),
+                        cid.0,
+                        *pool_id,
+                        *replication_factor,
+                    )?;
+                }
+                RawEvent::new(&events::BatchManifestOutput {
+                    uploader: who.clone(),
+                    pool_ids: pool_id,
+                    manifests,
+                })
+            }
+            FulaCall::storage_manifest { cid, pool_id } => {
+                self.store_manifest(who, &cid.0, pool_id)?;
+                RawEvent::new(&events::StorageManifestOutput {
+                    storer: who.clone(),
+                    pool_id,
+                    cid: cid.0,
+                })
+            }
+            FulaCall::batch_storage_manifest { cids, pool_id } => {
+                let cids: Vec<Vec<u8>> = cids.into_iter().map(|cid| cid.0).collect();
+                for cid in cids.iter() {
+                    self.store_manifest(who, cid, pool_id)?;
+                }
+                RawEvent::new(&events::BatchStorageManifestOutput {
+                    storer: who.clone(),
+                    pool_id,
+                    cids,
+                })
+            }
+            FulaCall::remove_stored_manifest { cid, pool_id } => {
+                self.remove_storer(who, &cid.0, pool_id)?;
+                RawEvent::new(&events::RemoveStorerOutput {
+                    storer: Some(who.clone()),
+                    pool_id,
+                    cid: cid.0,
+                })
+            }
+            FulaCall::batch_remove_stored_manifest { cids, pool_id } => {
+                let cids: Vec<Vec<u8>> = cids.into_iter().map(|cid| cid.0).collect();
+                for cid in cids.iter() {
+                    self.remove_storer(who, cid, pool_id)?;
+                }
+                RawEvent::new(&events::BatchRemoveStorerOutput {
+                    storer: who.clone(),
+                    pool_id,
+                    cids,
+                })
+            }
+            FulaCall::remove_manifest { cid, pool_id } => {
+                self.remove_manifest(who, &cid.0, pool_id)?;
+                RawEvent::new(&events::ManifestRemoved {
+                    uploader: who.clone(),
+                    pool_id,
+                    cid: cid.0,
+                })
+            }
+            FulaCall::batch_remove_manifest { cids, pool_ids } => {
+                ensure(cids.len() == pool_ids.len(), "Fula", "InvalidArrayLength")?;
+                let cids: Vec<Vec<u8>> = cids.into_iter().map(|cid| cid.0).collect();
+                for (cid, pool_id) in cids.iter().zip(pool_ids.iter()) {
+                    self.remove_manifest(who, cid, *pool_id)?;
+                }
+                RawEvent::new(&events::BatchManifestRemoved {
+                    uploader: who.clone(),
+                    pool_ids,
+                    cids,
+                })
+            }
+            FulaCall::update_manifest {
+                cid,
+                pool_id,
+                active_cycles,
+                missed_cycles,
+                active_days,
+            } => {
+                let key = storer_data_key(pool_id, who, &cid.0);
+                let mut data: ManifestStorageData = self
+                    .get(&key)
+                    .ok_or_else(|| module_error("Fula", "ManifestStorerDataNotFound"))?;
+                data.active_cycles = active_cycles;
+                data.missed_cycles = missed_cycles;
+                data.active_days = active_days;
+                self.put(key, &data);
+                RawEvent::new(&events::ManifestStorageUpdated {
+                    storer: who.clone(),
+                    pool_id,
+                    cid: cid.0,
+                    active_cycles,
+                    missed_cycles,
+                    active_days,
+                })
+            }
+            FulaCall::verify_manifests => {
+                let mut valid_cids = Vec::new();
+                let mut invalid_cids = Vec::new();
+                for (pool_id, storer, cid, _) in self.storer_data() {
+                    if storer != *who {
+                        continue;
+                    }
+                    let stored = self.get_manifest(pool_id, &cid).map_or(false, |manifest| {
+                        manifest
+                            .users_data
+                            .iter()
+                            .any(|data| data.storers.contains(who))
+                    });
+                    if stored {
+                        valid_cids.push(cid);
+                    } else {
+                        invalid_cids.push(cid);
+                    }
+                }
+                RawEvent::new(&events::VerifiedStorerManifests {
+                    storer: who.clone(),
+                    valid_cids,
+                    invalid_cids,
+                })
+            }
+            FulaCall::get_manifests {
+                pool_id,
+                uploader,
+                storer,
+            } => {
+                let manifests = self
+                    .manifests()
+                    .into_iter()
+                    .filter(|(manifest_pool_id, _, manifest)| {
+                        pool_id.map_or(true, |pool_id| pool_id == *manifest_pool_id)
+                            && uploader.as_ref().map_or(true, |uploader| {
+                                manifest
+                                    .users_data
+                                    .iter()
+                                    .any(|data| data.uploader == *uploader)
+                            })
+                            && storer.as_ref().map_or(true, |storer| {
+                                manifest
+                                    .users_data
+                                    .iter()
+                                    .any(|data| data.storers.contains(storer))
+                            })
+                    })
+                    .map(|(pool_id, _, manifest)| ManifestWithPoolId {
+                        pool_id,
+                        users_data: manifest.users_data,
+                        manifest_metadata: manifest.manifest_metadata,
+                        size: manifest.size,
+                    })
+                    .collect();
+                RawEvent::new(&events::GetManifests { manifests })
+            }
+            FulaCall::get_available_manifests { pool_id } => {
+                let manifests = self
+                    .manifests()
+                    .into_iter()
+                    .filter(|(manifest_pool_id, _, _)| {
+                        pool_id.map_or(true, |pool_id| pool_id == *manifest_pool_id)
+                    })
+                    .filter_map(|(pool_id, _, manifest)| {
+                        let available = manifest
+                            .users_data
+                            .iter()
+                            .map(|data| data.replication_factor - data.storers.len() as u16)
+                            .sum::<u16>();
+                        (available > 0).then_some(ManifestAvailable {
+                            pool_id,
+                            replication_factor: available,
+                            manifest_metadata: manifest.manifest_metadata,
+                        })
+                    })
+                    .collect();
+                RawEvent::new(&events::GetAvailableManifests { manifests })
+            }
+            FulaCall::get_manifests_storer_data { pool_id, storer } => {
+                let manifests = self
+                    .storer_data()
+                    .into_iter()
+                    .filter(|(data_pool_id, account, _, _)| {
+                        pool_id.map_or(true, |pool_id| pool_id == *data_pool_id)
+                            && storer.as_ref().map_or(true, |storer| storer == account)
+                    })
+                    .map(|(pool_id, account, cid, manifest_data)| StorerData {
+                        pool_id,
+                        cid: BoundedVec(cid),
+                        account,
+                        manifest_data,
+                    })
+                    .collect();
+                RawEvent::new(&events::GetManifestsStorerData { manifests })
+            }
+            FulaCall::generate_challenge => {
+                let candidates: Vec<_> = self
+                    .storer_data()
+                    .into_iter()
+                    .map(|(_, storer, cid, _)| (storer, cid))
+                    .filter(|(storer, cid)| !self.contains(&challenge_key(storer, cid)))
+                    .collect();
+                ensure(!candidates.is_empty(), "Fula", "NoAccountsToChallenge")?;
+                let (challenged, cid) = candidates[self.block as usize % candidates.len()].clone();
+                self.put(
+                    challenge_key(&challenged, &cid),
+                    &Challenge {
+                        challenger: who.clone(),
+                        challenge_state: ChallengeState::Open,
+                    },
+                );
+                RawEvent::new(&events::Challenge {
+                    challenger: who.clone(),
+                    challenged,
+                    cid,
+                    state: ChallengeState::Open,
+                })
+            }
+            FulaCall::verify_challenge {
+                pool_id,
+                cids,
+                class_id,
+                asset_id,
+            } => {
+                let cids: Vec<Vec<u8>> = cids.into_iter().map(|cid| cid.0).collect();
+                let prefix = storage_key("Fula", "ChallengeRequests", &[concat(who)]);
+                let mut successful = Vec::new();
+                let mut failed = Vec::new();
+                for key in self.keys(&prefix) {
+                    let mut parts = KeyParts::new(&key);
+                    let _: AccountId32 = parts.next();
+                    let cid: Vec<u8> = parts.next();
+                    self.remove(&key);
+
+                    let passed = cids.contains(&cid);
+                    let data_key = storer_data_key(pool_id, who, &cid);
+                    if let Some(mut data) = self.get::<ManifestStorageData>(&data_key) {
+                        data.challenge_state = if passed {
+                            ChallengeState::Successful
+                        } else {
+                            ChallengeState::Failed
+                        };
+                        self.put(data_key, &data);
+                    }
+                    if passed {
+                        successful.push(cid);
+                    } else {
+                        failed.push(cid);
+                    }
+                }
+                let minted = successful.len() as u128;
+                let mut claim = self.claim(who);
+                claim.challenge_tokens += minted;
+                self.put(claim_key(who), &claim);
+                self.mint_asset(who, class_id, asset_id, minted);
+                let verified = RawEvent::new(&events::VerifiedChallenges {
+                    challenged: who.clone(),
+                    successful,
+                    failed,
+                });
+                if minted == 0 {
+                    return Ok(vec![verified]);
+                }
+                return Ok(vec![
+                    RawEvent::new(&sugarfunge::asset::events::Mint {
+                        who: who.clone(),
+                        to: who.clone(),
+                        class_id,
+                        asset_id,
+                        amount: minted,
+                    }),
+                    verified,
+                ]);
+            }
+            FulaCall::mint_labor_tokens {
+                class_id,
+                asset_id,
+                amount,
+            } => {
+                let calculated_amount = self
+                    .storer_data()
+                    .iter()
+                    .filter(|(_, storer, _, _)| storer == who)
+                    .count() as u128;
+                let mut claim = self.claim(who);
+                claim.minted_labor_tokens += amount;
+                claim.expected_labor_tokens = calculated_amount;
+                self.put(claim_key(who), &claim);
+                self.mint_asset(who, class_id, asset_id, amount);
+                RawEvent::new(&events::MintedLaborTokens {
+                    account: who.clone(),
+                    class_id,
+                    asset_id,
+                    amount,
+                    calculated_amount,
+                })
+            }
+            FulaCall::update_file_size { cid, pool_id, size } => {
+                self.update_file_size(who, &cid.0, pool_id, size)?;
+                RawEvent::new(&events::UpdateFileSizeOutput {
+                    account: who.clone(),
+                    pool_id,
+                    cid: cid.0,
+                    size,
+                })
+            }
+            FulaCall::update_file_sizes {
+                cids,
+                pool_id,
+                sizes,
+            } => {
+                ensure(cids.len() == sizes.len(), "Fula", "InvalidArrayLength")?;
+                let cids: Vec<Vec<u8>> = cids.into_iter().map(|cid| cid.0).collect();
+                for (cid, size) in cids.iter().zip(sizes.iter()) {
+                    self.update_file_size(who, cid, pool_id, *size)?;
+                }
+                RawEvent::new(&events::UpdateFileSizesOutput {
+                    account: who.clone(),
+                    pool_id,
+                    cids,
+                    sizes,
+                })
+            }
+        };
+        Ok(vec![event])
+    }
+}
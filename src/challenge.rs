@@ -1,5 +1,7 @@
+use std::collections::{BTreeMap, HashMap};
 use std::str::FromStr;
 
+use crate::chain::Storage;
 use crate::fula::get_vec_cids_from_input;
 use crate::fula::get_vec_cids_from_node;
 use crate::fula::transform_vec_uploader_data_runtime_to_vec_uploader_data;
@@ -9,6 +11,7 @@ use crate::util::*;
 use actix_web::{error, web, HttpResponse};
 use codec::Decode;
 use serde_json::json;
+use sp_core::Pair;
 use subxt::ext::sp_core::sr25519::Public;
 use subxt::tx::PairSigner;
 use subxt::utils::AccountId32;
@@ -16,12 +19,24 @@ use sugarfunge_api_types::challenge::*;
 use sugarfunge_api_types::primitives::*;
 use sugarfunge_api_types::sugarfunge;
 use sugarfunge_api_types::sugarfunge::runtime_types::functionland_fula::{
-    Challenge as ChallengeRuntime, ClaimData as ClaimRuntime, Manifest as ManifestRuntime,
+    Challenge as ChallengeRuntime, ChallengeState, ClaimData as ClaimRuntime,
+    Manifest as ManifestRuntime, ManifestStorageData as ManifestStorageDataRuntime,
 };
 
+#[utoipa::path(
+    post,
+    path = "/fula/challenge/generate",
+    tag = "fula/challenge",
+    request_body = GenerateChallengeInput,
+    responses(
+        (status = 200, body = GenerateChallengeOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["fula:write"]), ("bearer" = ["fula:write"])),
+)]
 pub async fn generate_challenge(
     data: web::Data<AppState>,
-    req: web::Json<GenerateChallengeInput>,
+    req: Json<GenerateChallengeInput>,
 ) -> error::Result<HttpResponse> {
     let pair = get_pair_from_seed(&req.seed.clone())?;
     let signer = PairSigner::new(pair);
@@ -31,11 +46,7 @@ pub async fn generate_challenge(
     let call = sugarfunge::tx().fula().generate_challenge();
 
     let result = api
-        .tx()
-        .sign_and_submit_then_watch(&call, &signer, Default::default())
-        .await
-        .map_err(map_subxt_err)?
-        .wait_for_finalized_success()
+        .sign_and_submit(&call, &signer)
         .await
         .map_err(map_fula_err)?;
     let result = result
@@ -55,9 +66,20 @@ pub async fn generate_challenge(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/fula/challenge/verify",
+    tag = "fula/challenge",
+    request_body = VerifyChallengeInput,
+    responses(
+        (status = 200, body = VerifyChallengeOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["fula:write"]), ("bearer" = ["fula:write"])),
+)]
 pub async fn verify_challenge(
     data: web::Data<AppState>,
-    req: web::Json<VerifyChallengeInput>,
+    req: Json<VerifyChallengeInput>,
 ) -> error::Result<HttpResponse> {
     let pair = get_pair_from_seed(&req.seed.clone())?;
     let signer = PairSigner::new(pair);
@@ -74,11 +96,7 @@ pub async fn verify_challenge(
     );
 
     let result = api
-        .tx()
-        .sign_and_submit_then_watch(&call, &signer, Default::default())
-        .await
-        .map_err(map_subxt_err)?
-        .wait_for_finalized_success()
+        .sign_and_submit(&call, &signer)
         .await
         .map_err(map_fula_err)?;
     let result = result
@@ -99,9 +117,20 @@ pub async fn verify_challenge(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/fula/mint_labor_tokens",
+    tag = "fula",
+    request_body = MintLaborTokensInput,
+    responses(
+        (status = 200, body = MintLaborTokensOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["fula:write"]), ("bearer" = ["fula:write"])),
+)]
 pub async fn mint_labor_tokens(
     data: web::Data<AppState>,
-    req: web::Json<MintLaborTokensInput>,
+    req: Json<MintLaborTokensInput>,
 ) -> error::Result<HttpResponse> {
     let pair = get_pair_from_seed(&req.seed.clone())?;
     let signer = PairSigner::new(pair);
@@ -115,11 +144,7 @@ pub async fn mint_labor_tokens(
     );
 
     let result = api
-        .tx()
-        .sign_and_submit_then_watch(&call, &signer, Default::default())
-        .await
-        .map_err(map_subxt_err)?
-        .wait_for_finalized_success()
+        .sign_and_submit(&call, &signer)
         .await
         .map_err(map_fula_err)?;
     let result = result
@@ -140,9 +165,20 @@ pub async fn mint_labor_tokens(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/fula/challenge/pending",
+    tag = "fula/challenge",
+    request_body = VerifyPendingChallengeInput,
+    responses(
+        (status = 200, body = VerifyPendingChallengeOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["read"]), ("bearer" = ["read"])),
+)]
 pub async fn verify_pending_challenge(
     data: web::Data<AppState>,
-    req: web::Json<VerifyPendingChallengeInput>,
+    req: Json<VerifyPendingChallengeInput>,
 ) -> error::Result<HttpResponse> {
     let api = &data.api;
     let mut result = false;
@@ -154,7 +190,7 @@ pub async fn verify_pending_challenge(
 
     // println!("query_key account_to len: {}", query_key.len());
 
-    let storage = api.storage().at_latest().await.map_err(map_subxt_err)?;
+    let storage = api.storage().await.map_err(map_subxt_err)?;
 
     let keys = storage
         .fetch_keys(&query_key, 1000, None)
@@ -181,9 +217,357 @@ pub async fn verify_pending_challenge(
     }))
 }
 
+/// Challenger and cid of the open challenges sent to `account`
+pub async fn open_challenges(
+    api: &ClientAPI,
+    account: &AccountId32,
+) -> Result<Vec<(AccountId32, String)>, subxt::Error> {
+    open_challenges_at(&api.storage().await?, account).await
+}
+
+/// [`open_challenges`] read from a pinned block
+pub async fn open_challenges_at(
+    storage: &Storage<'_>,
+    account: &AccountId32,
+) -> Result<Vec<(AccountId32, String)>, subxt::Error> {
+    let mut query_key = sugarfunge::storage()
+        .fula()
+        .challenge_requests_root()
+        .to_root_bytes();
+    query_key.extend(blake2_128_concat(account));
+
+    let keys = storage.fetch_all_keys(&query_key).await?;
+
+    let mut result_array = Vec::new();
+    for key in keys.iter() {
+        let cid_idx = 96;
+        let cid = String::decode(&mut &key.0[cid_idx..])?;
+
+        if let Some(storage_data) = storage.fetch_raw(&key.0).await? {
+            let value = ChallengeRuntime::<AccountId32>::decode(&mut &storage_data[..])?;
+            if let ChallengeState::Open = value.challenge_state {
+                result_array.push((value.challenger, cid));
+            }
+        }
+    }
+    Ok(result_array)
+}
+
+/// Open challenges of every challenged account, read from a pinned block
+pub async fn open_challenge_counts_at(
+    storage: &Storage<'_>,
+) -> Result<HashMap<AccountId32, u64>, subxt::Error> {
+    let query_key = sugarfunge::storage()
+        .fula()
+        .challenge_requests_root()
+        .to_root_bytes();
+
+    let keys = storage.fetch_all_keys(&query_key).await?;
+
+    let mut counts = HashMap::new();
+    for key in keys.iter() {
+        let account_idx = 48;
+        let account = AccountId32::decode(&mut &key.0[account_idx..(account_idx + 32)])?;
+
+        if let Some(storage_data) = storage.fetch_raw(&key.0).await? {
+            let value = ChallengeRuntime::<AccountId32>::decode(&mut &storage_data[..])?;
+            if let ChallengeState::Open = value.challenge_state {
+                *counts.entry(account).or_default() += 1;
+            }
+        }
+    }
+    Ok(counts)
+}
+
+#[utoipa::path(
+    post,
+    path = "/fula/challenge/open",
+    tag = "fula/challenge",
+    request_body = GetOpenChallengesInput,
+    responses(
+        (status = 200, body = GetOpenChallengesOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["read"]), ("bearer" = ["read"])),
+)]
+pub async fn get_open_challenges(
+    data: web::Data<AppState>,
+    req: Json<GetOpenChallengesInput>,
+) -> error::Result<HttpResponse> {
+    let account = AccountId32::try_from(&req.account).map_err(map_account_err)?;
+
+    let challenges = open_challenges(&data.api, &account)
+        .await
+        .map_err(map_subxt_err)?;
+
+    Ok(HttpResponse::Ok().json(GetOpenChallengesOutput {
+        account: req.account.clone(),
+        challenges: challenges
+            .into_iter()
+            .map(|(challenger, cid)| OpenChallenge {
+                challenger: challenger.into(),
+                cid: Cid::from(cid),
+            })
+            .collect(),
+    }))
+}
+
+/// Answer the open challenges of the signer with the cids it holds, checked
+/// on the IPFS node of the gateway unless the request lists them
+#[utoipa::path(
+    post,
+    path = "/fula/challenge/respond",
+    tag = "fula/challenge",
+    request_body = RespondChallengeInput,
+    responses(
+        (status = 200, body = RespondChallengeOutput),
+        (status = 400, body = RequestError),
+        (status = 502, body = RequestError),
+    ),
+    security(("api_key" = ["fula:write"]), ("bearer" = ["fula:write"])),
+)]
+pub async fn respond_challenge(
+    data: web::Data<AppState>,
+    req: Json<RespondChallengeInput>,
+) -> error::Result<HttpResponse> {
+    let pair = get_pair_from_seed(&req.seed.clone())?;
+    let account = AccountId32::from(pair.public().0);
+    let signer = PairSigner::new(pair);
+
+    let api = &data.api;
+    let class_id = data.config.tokens.challenge_token_class_id;
+    let asset_id = data.config.tokens.challenge_token_asset_id;
+
+    let pool_id = match req.pool_id {
+        Some(pool_id) => u32::from(pool_id),
+        None => {
+            let user = api
+                .storage()
+                .await
+                .map_err(map_subxt_err)?
+                .fetch(&sugarfunge::storage().pool().users(&account))
+                .await
+                .map_err(map_subxt_err)?;
+            match user.and_then(|user| user.pool_id) {
+                Some(pool_id) => pool_id,
+                None => {
+                    return Ok(HttpResponse::BadRequest().json(RequestError {
+                        message: json!("Account is not a member of a pool"),
+                        description: "Error in challenge::respond_challenge".to_string(),
+                    }))
+                }
+            }
+        }
+    };
+
+    let challenges = open_challenges(api, &account)
+        .await
+        .map_err(map_subxt_err)?;
+
+    let mut held = Vec::new();
+    for (_, cid) in challenges.iter() {
+        let is_held = match &req.held_cids {
+            Some(held_cids) => held_cids.iter().any(|held_cid| held_cid.as_str() == cid),
+            None => data.ipfs.has(cid).await.map_err(map_ipfs_err)?,
+        };
+        if is_held {
+            held.push(Cid::from(cid.clone()));
+        }
+    }
+
+    let mut output = RespondChallengeOutput {
+        account: account.clone().into(),
+        pool_id: pool_id.into(),
+        successful_cids: vec![],
+        failed_cids: vec![],
+        class_id: class_id.into(),
+        asset_id: asset_id.into(),
+        minted: Balance::from(0u128),
+    };
+    if challenges.is_empty() {
+        return Ok(HttpResponse::Ok().json(output));
+    }
+
+    let call = sugarfunge::tx().fula().verify_challenge(
+        pool_id,
+        get_vec_cids_from_input(held),
+        class_id,
+        asset_id,
+    );
+
+    let events = api
+        .sign_and_submit(&call, &signer)
+        .await
+        .map_err(map_fula_err)?;
+    let result = events
+        .find_first::<sugarfunge::fula::events::VerifiedChallenges>()
+        .map_err(map_subxt_err)?;
+    let event = match result {
+        Some(event) => event,
+        None => {
+            return Ok(HttpResponse::BadRequest().json(RequestError {
+                message: json!("Failed to find sugarfunge::fula::events::VerifiedChallenges"),
+                description: format!(""),
+            }))
+        }
+    };
+
+    // Minted by this extrinsic only, whatever else moves the balance
+    let minted: u128 = events
+        .find::<sugarfunge::asset::events::Mint>()
+        .map_err(map_subxt_err)?
+        .into_iter()
+        .filter(|mint| mint.to == account && mint.class_id == class_id && mint.asset_id == asset_id)
+        .map(|mint| mint.amount)
+        .sum();
+
+    output.successful_cids = get_vec_cids_from_node(event.successful);
+    output.failed_cids = get_vec_cids_from_node(event.failed);
+    output.minted = minted.into();
+    Ok(HttpResponse::Ok().json(output))
+}
+
+/// Current storer data of one account, summed over its stored manifests
+#[derive(Default)]
+struct StorerTotals {
+    manifests_stored: u64,
+    active_cycles: u64,
+    missed_cycles: u64,
+    active_days: i64,
+    failed_cids: Vec<String>,
+}
+
+/// Reliability profile of storers, from the indexed challenge events and
+/// the current storer data
+#[utoipa::path(
+    post,
+    path = "/fula/challenge/reliability",
+    tag = "fula/challenge",
+    request_body = GetStorerReliabilityInput,
+    responses(
+        (status = 200, body = GetStorerReliabilityOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["read"]), ("bearer" = ["read"])),
+)]
+pub async fn get_storer_reliability(
+    data: web::Data<AppState>,
+    req: Json<GetStorerReliabilityInput>,
+) -> error::Result<HttpResponse> {
+    let api = &data.api;
+    let history = &data.challenge_history;
+    let account_filter = match &req.account {
+        Some(account) => Some(AccountId32::try_from(account).map_err(map_account_err)?),
+        None => None,
+    };
+
+    let query_key = sugarfunge::storage()
+        .fula()
+        .manifests_storer_data_root()
+        .to_root_bytes();
+
+    let storage = api.storage().await.map_err(map_subxt_err)?;
+
+    let keys = storage
+        .fetch_all_keys(&query_key)
+        .await
+        .map_err(map_subxt_err)?;
+
+    let mut totals: HashMap<AccountId32, StorerTotals> = HashMap::new();
+    for key in keys.iter() {
+        let account_idx = 68;
+        let account_id = AccountId32::decode(&mut &key.0[account_idx..(account_idx + 32)])
+            .map_err(|e| map_subxt_err(e.into()))?;
+        if account_filter
+            .as_ref()
+            .map_or(false, |account| *account != account_id)
+        {
+            continue;
+        }
+
+        let cid_idx = 116;
+        let cid = String::decode(&mut &key.0[cid_idx..]).map_err(|e| map_subxt_err(e.into()))?;
+
+        if let Some(storage_data) = storage.fetch_raw(&key.0).await.map_err(map_subxt_err)? {
+            let value = ManifestStorageDataRuntime::decode(&mut &storage_data[..])
+                .map_err(|e| map_subxt_err(e.into()))?;
+            let storer = totals.entry(account_id).or_default();
+            storer.manifests_stored += 1;
+            storer.active_cycles += value.active_cycles as u64;
+            storer.missed_cycles += value.missed_cycles as u64;
+            storer.active_days += value.active_days as i64;
+            if let ChallengeState::Failed = value.challenge_state {
+                storer.failed_cids.push(cid);
+            }
+        }
+    }
+
+    let mut accounts: Vec<AccountId32> = match account_filter {
+        Some(account) => vec![account],
+        None => {
+            let mut accounts: Vec<AccountId32> = totals.keys().cloned().collect();
+            accounts.extend(history.storers());
+            accounts
+        }
+    };
+    accounts.sort_by_key(|account| account.to_string());
+    accounts.dedup();
+
+    let storers = accounts
+        .into_iter()
+        .map(|account| {
+            let indexed = history.storer(&account);
+            let current = totals.remove(&account).unwrap_or_default();
+            let mut failed_cids = current.failed_cids;
+            for cid in indexed.failed_cids {
+                if !failed_cids.contains(&cid) {
+                    failed_cids.push(cid);
+                }
+            }
+            let verified = indexed.successful + indexed.failed;
+            StorerReliability {
+                account: account.into(),
+                challenges_received: indexed.received,
+                challenges_successful: indexed.successful,
+                challenges_failed: indexed.failed,
+                success_rate: (verified > 0).then_some(indexed.successful as f64 / verified as f64),
+                manifests_stored: current.manifests_stored,
+                active_cycles: current.active_cycles,
+                missed_cycles: current.missed_cycles,
+                active_days: current.active_days,
+                missed_cycles_trend: indexed
+                    .missed_cycles_trend
+                    .into_iter()
+                    .map(|sample| MissedCyclesSample {
+                        timestamp: sample.timestamp,
+                        missed_cycles: sample.missed_cycles,
+                    })
+                    .collect(),
+                failed_cids: failed_cids.into_iter().map(Cid::from).collect(),
+            }
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(GetStorerReliabilityOutput {
+        indexed_since: history.since(),
+        storers,
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/fula/file/verify",
+    tag = "fula",
+    request_body = VerifyFileSizeInput,
+    responses(
+        (status = 200, body = VerifyFileSizeOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["read"]), ("bearer" = ["read"])),
+)]
 pub async fn verify_file_size(
     data: web::Data<AppState>,
-    req: web::Json<VerifyFileSizeInput>,
+    req: Json<VerifyFileSizeInput>,
 ) -> error::Result<HttpResponse> {
     let api = &data.api;
     let mut result_array = Vec::new();
@@ -195,7 +579,7 @@ pub async fn verify_file_size(
 
     // println!("query_key account_to len: {}", query_key.len());
 
-    let storage = api.storage().at_latest().await.map_err(map_subxt_err)?;
+    let storage = api.storage().await.map_err(map_subxt_err)?;
 
     let keys = storage
         .fetch_keys(&query_key, 1000, None)
@@ -234,9 +618,20 @@ pub async fn verify_file_size(
     }))
 }
 
+#[utoipa::path(
+    post,
+    path = "/fula/file/provide",
+    tag = "fula",
+    request_body = ProvideFileSizeInput,
+    responses(
+        (status = 200, body = ProvideFileSizeOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["fula:write"]), ("bearer" = ["fula:write"])),
+)]
 pub async fn provide_file_size(
     data: web::Data<AppState>,
-    req: web::Json<ProvideFileSizeInput>,
+    req: Json<ProvideFileSizeInput>,
 ) -> error::Result<HttpResponse> {
     let pair = get_pair_from_seed(&req.seed.clone())?;
     let signer = PairSigner::new(pair);
@@ -251,11 +646,7 @@ pub async fn provide_file_size(
             .update_file_sizes(cids, req.pool_id.into(), req.sizes.to_vec());
 
     let result = api
-        .tx()
-        .sign_and_submit_then_watch(&call, &signer, Default::default())
-        .await
-        .map_err(map_subxt_err)?
-        .wait_for_finalized_success()
+        .sign_and_submit(&call, &signer)
         .await
         .map_err(map_fula_err)?;
     let result = result
@@ -275,6 +666,16 @@ pub async fn provide_file_size(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/fula/challenge",
+    tag = "fula/challenge",
+    responses(
+        (status = 200, body = GetChallengesOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["read"]), ("bearer" = ["read"])),
+)]
 pub async fn get_challenges(data: web::Data<AppState>) -> error::Result<HttpResponse> {
     let api = &data.api;
     let mut result_array = Vec::new();
@@ -286,7 +687,7 @@ pub async fn get_challenges(data: web::Data<AppState>) -> error::Result<HttpResp
 
     // println!("query_key account_to len: {}", query_key.len());
 
-    let storage = api.storage().at_latest().await.map_err(map_subxt_err)?;
+    let storage = api.storage().await.map_err(map_subxt_err)?;
 
     let keys = storage
         .fetch_keys(&query_key, 1000, None)
@@ -317,6 +718,16 @@ pub async fn get_challenges(data: web::Data<AppState>) -> error::Result<HttpResp
     }))
 }
 
+#[utoipa::path(
+    post,
+    path = "/fula/claims",
+    tag = "fula",
+    responses(
+        (status = 200, body = GetClaimDataOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["read"]), ("bearer" = ["read"])),
+)]
 pub async fn get_claims(data: web::Data<AppState>) -> error::Result<HttpResponse> {
     let api = &data.api;
     let mut result_array = Vec::new();
@@ -325,7 +736,7 @@ pub async fn get_claims(data: web::Data<AppState>) -> error::Result<HttpResponse
 
     // println!("query_key account_to len: {}", query_key.len());
 
-    let storage = api.storage().at_latest().await.map_err(map_subxt_err)?;
+    let storage = api.storage().await.map_err(map_subxt_err)?;
 
     let keys = storage
         .fetch_keys(&query_key, 1000, None)
@@ -356,3 +767,128 @@ pub async fn get_claims(data: web::Data<AppState>) -> error::Result<HttpResponse
         claims: result_array,
     }))
 }
+
+/// Labor and challenge tokens an account would earn from its stored
+/// manifests, compared with what it already minted
+#[utoipa::path(
+    post,
+    path = "/fula/rewards",
+    tag = "fula",
+    request_body = GetRewardsInput,
+    responses(
+        (status = 200, body = GetRewardsOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["read"]), ("bearer" = ["read"])),
+)]
+pub async fn get_rewards(
+    data: web::Data<AppState>,
+    req: Json<GetRewardsInput>,
+) -> error::Result<HttpResponse> {
+    let api = &data.api;
+    let tokens = &data.config.tokens;
+    let account = AccountId32::try_from(&req.account).map_err(map_account_err)?;
+
+    let mut query_key = sugarfunge::storage()
+        .fula()
+        .manifests_storer_data_root()
+        .to_root_bytes();
+    // Storer data is keyed by pool, then account, so the account narrows
+    // the keys only within a pool
+    if let Some(pool_id) = req.pool_id {
+        query_key.extend(blake2_128_concat(&u32::from(pool_id)));
+        query_key.extend(blake2_128_concat(&account));
+    }
+
+    let storage = api.storage().await.map_err(map_subxt_err)?;
+
+    let keys = storage
+        .fetch_all_keys(&query_key)
+        .await
+        .map_err(map_subxt_err)?;
+
+    let mut pools: BTreeMap<u32, Vec<CidRewards>> = BTreeMap::new();
+    for key in keys.iter() {
+        let account_idx = 68;
+        let account_id = AccountId32::decode(&mut &key.0[account_idx..(account_idx + 32)])
+            .map_err(|e| map_subxt_err(e.into()))?;
+        if account_id != account {
+            continue;
+        }
+
+        let pool_id_idx = 48;
+        let pool_id = u32::decode(&mut &key.0[pool_id_idx..(pool_id_idx + 4)])
+            .map_err(|e| map_subxt_err(e.into()))?;
+
+        let cid_idx = 116;
+        let cid = String::decode(&mut &key.0[cid_idx..]).map_err(|e| map_subxt_err(e.into()))?;
+
+        if let Some(storage_data) = storage.fetch_raw(&key.0).await.map_err(map_subxt_err)? {
+            let value = ManifestStorageDataRuntime::decode(&mut &storage_data[..])
+                .map_err(|e| map_subxt_err(e.into()))?;
+            let challenge_tokens = match value.challenge_state {
+                ChallengeState::Successful => 1,
+                _ => 0,
+            };
+            pools.entry(pool_id).or_default().push(CidRewards {
+                cid: Cid::from(cid),
+                active_days: value.active_days,
+                active_cycles: value.active_cycles,
+                missed_cycles: value.missed_cycles,
+                state: value.challenge_state.into(),
+                labor_tokens: (value.active_cycles as u128).into(),
+                challenge_tokens: Balance::from(challenge_tokens),
+            });
+        }
+    }
+
+    let claim = storage
+        .fetch(&sugarfunge::storage().fula().claims(&account))
+        .await
+        .map_err(map_subxt_err)?;
+    let (minted_labor_tokens, minted_challenge_tokens) = claim
+        .map(|claim| (claim.minted_labor_tokens, claim.challenge_tokens))
+        .unwrap_or_default();
+
+    let mut expected_labor_tokens = 0;
+    let mut expected_challenge_tokens = 0;
+    let pools: Vec<PoolRewards> = pools
+        .into_iter()
+        .map(|(pool_id, cids)| {
+            let labor_tokens: u128 = cids.iter().map(|cid| u128::from(cid.labor_tokens)).sum();
+            let challenge_tokens: u128 = cids
+                .iter()
+                .map(|cid| u128::from(cid.challenge_tokens))
+                .sum();
+            expected_labor_tokens += labor_tokens;
+            expected_challenge_tokens += challenge_tokens;
+            PoolRewards {
+                pool_id: pool_id.into(),
+                labor_tokens: labor_tokens.into(),
+                challenge_tokens: challenge_tokens.into(),
+                cids,
+            }
+        })
+        .collect();
+
+    let claimable_tokens = std::cmp::min(
+        expected_labor_tokens / tokens.labor_token_value,
+        expected_challenge_tokens / tokens.challenge_token_value,
+    );
+
+    Ok(HttpResponse::Ok().json(GetRewardsOutput {
+        account: req.account.clone(),
+        expected_labor_tokens: expected_labor_tokens.into(),
+        expected_challenge_tokens: expected_challenge_tokens.into(),
+        minted_labor_tokens: minted_labor_tokens.into(),
+        minted_challenge_tokens: minted_challenge_tokens.into(),
+        pending_labor_tokens: expected_labor_tokens
+            .saturating_sub(minted_labor_tokens)
+            .into(),
+        pending_challenge_tokens: expected_challenge_tokens
+            .saturating_sub(minted_challenge_tokens)
+            .into(),
+        claimable_tokens: claimable_tokens.into(),
+        pools,
+    }))
+}
@@ -1,7 +1,124 @@
+use crate::args::Args;
+use crate::auth::Scope;
+use crate::region::RegionRegistry;
+use derive_more::Display;
+use serde::Deserialize;
+use std::collections::BTreeMap;
 use std::env;
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use sugarfunge_api_types::account::{REFUND_FEE_VALUE, REFUND_SEED};
+use sugarfunge_api_types::primitives::Seed;
+use url::Url;
 
-#[derive(Clone, Debug)]
+#[derive(Debug, Display)]
+pub enum ConfigError {
+    #[display(fmt = "could not read {}: {}", path, error)]
+    Io { path: String, error: String },
+    #[display(fmt = "could not parse config file {}: {}", path, error)]
+    Parse { path: String, error: String },
+    #[display(fmt = "invalid value for {}: {}", field, reason)]
+    Invalid { field: String, reason: String },
+}
+
+impl ConfigError {
+    pub(crate) fn invalid(field: &str, reason: impl Into<String>) -> ConfigError {
+        ConfigError::Invalid {
+            field: field.to_string(),
+            reason: reason.into(),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl From<ConfigError> for std::io::Error {
+    fn from(e: ConfigError) -> std::io::Error {
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, e.to_string())
+    }
+}
+
+/// Server configuration, layered from a TOML file, environment variables
+/// and command line flags (in increasing order of precedence)
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct Config {
+    pub server: ServerConfig,
+    pub node: NodeConfig,
+    pub cors: CorsConfig,
+    pub tokens: TokenConfig,
+    pub refund: RefundConfig,
+    pub contract: ContractConfig,
+    pub rate_limit: RateLimitConfig,
+    pub auth: AuthConfig,
+    pub agent: AgentConfig,
+    pub manifest: ManifestConfig,
+    pub regions: RegionConfig,
+    pub index: IndexConfig,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    pub listen: Url,
+    pub tls: Option<TlsConfig>,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        ServerConfig {
+            listen: Url::parse("http://127.0.0.1:4000").unwrap(),
+            tls: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TlsConfig {
+    pub cert_path: PathBuf,
+    pub key_path: PathBuf,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NodeConfig {
+    /// Node endpoints, tried in order until one accepts the connection
+    pub urls: Vec<Url>,
+}
+
+impl Default for NodeConfig {
+    fn default() -> Self {
+        NodeConfig {
+            urls: vec![Url::parse("ws://127.0.0.1:9944").unwrap()],
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CorsConfig {
+    pub allowed_origins: Vec<String>,
+    /// Accept any `http://localhost*` origin, useful while developing a frontend
+    pub allow_localhost: bool,
+    pub max_age: usize,
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        CorsConfig {
+            allowed_origins: vec!["http://localhost:8080".into()],
+            allow_localhost: true,
+            max_age: 3600,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TokenConfig {
     pub labor_token_class_id: u64,
     pub labor_token_asset_id: u64,
     pub labor_token_value: u128,
@@ -12,41 +129,631 @@ pub struct Config {
     pub claimed_token_asset_id: u64,
 }
 
-pub fn init() -> Config {
-    let panic_message: String = "enviroment variable is not set".to_string();
-
-    Config {
-        labor_token_class_id: match env::var("LABOR_TOKEN_CLASS_ID") {
-            Ok(var) => var.parse::<u64>().unwrap(),
-            Err(_) => panic!("LABOR_TOKEN_CLASS_ID {}", panic_message),
-        },
-        labor_token_asset_id: match env::var("LABOR_TOKEN_ASSET_ID") {
-            Ok(var) => var.parse::<u64>().unwrap(),
-            Err(_) => panic!("LABOR_TOKEN_ASSET_ID {}", panic_message),
-        },
-        labor_token_value: match env::var("LABOR_TOKEN_VALUE") {
-            Ok(var) => var.parse::<u128>().unwrap(),
-            Err(_) => panic!("LABOR_TOKEN_VALUE {}", panic_message),
-        },
-        challenge_token_class_id: match env::var("CHALLENGE_TOKEN_CLASS_ID") {
-            Ok(var) => var.parse::<u64>().unwrap(),
-            Err(_) => panic!("CHALLENGE_TOKEN_CLASS_ID {}", panic_message),
-        },
-        challenge_token_asset_id: match env::var("CHALLENGE_TOKEN_ASSET_ID") {
-            Ok(var) => var.parse::<u64>().unwrap(),
-            Err(_) => panic!("CHALLENGE_TOKEN_ASSET_ID {}", panic_message),
-        },
-        challenge_token_value: match env::var("CHALLENGE_TOKEN_VALUE") {
-            Ok(var) => var.parse::<u128>().unwrap(),
-            Err(_) => panic!("CHALLENGE_TOKEN_VALUE {}", panic_message),
-        },
-        claimed_token_class_id: match env::var("CLAIMED_TOKEN_CLASS_ID") {
-            Ok(var) => var.parse::<u64>().unwrap(),
-            Err(_) => panic!("CLAIMED_TOKEN_CLASS_ID {}", panic_message),
-        },
-        claimed_token_asset_id: match env::var("CLAIMED_TOKEN_ASSET_ID") {
-            Ok(var) => var.parse::<u64>().unwrap(),
-            Err(_) => panic!("CLAIMED_TOKEN_ASSET_ID {}", panic_message),
-        },
+impl Default for TokenConfig {
+    fn default() -> Self {
+        TokenConfig {
+            labor_token_class_id: 100,
+            labor_token_asset_id: 100,
+            labor_token_value: 1,
+            challenge_token_class_id: 110,
+            challenge_token_asset_id: 100,
+            challenge_token_value: 1,
+            claimed_token_class_id: 120,
+            claimed_token_asset_id: 100,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RefundConfig {
+    /// Reimburse the fees of manifest and pool calls from the sponsor account
+    pub enabled: bool,
+    pub sponsor_seed: String,
+    pub fee_value: u128,
+}
+
+impl Default for RefundConfig {
+    fn default() -> Self {
+        RefundConfig {
+            enabled: true,
+            sponsor_seed: REFUND_SEED.into(),
+            fee_value: REFUND_FEE_VALUE,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ContractConfig {
+    pub goerli_contract_address: Option<String>,
+    pub goerli_url: Option<String>,
+    pub mumbai_contract_address: Option<String>,
+    pub mumbai_url: Option<String>,
+    pub account_address: Option<String>,
+    pub account_private_key: Option<String>,
+    pub number_confirmations: Option<u64>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ManifestConfig {
+    /// Reject uploaded metadata that does not match `ManifestMetadata`
+    pub strict_metadata: bool,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RegionConfig {
+    /// Regions accepted next to the ISO 3166 codes
+    pub custom: Vec<CustomRegionConfig>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct IndexConfig {
+    /// Latest finalized blocks indexed at startup, a day of blocks when
+    /// unset. A value above the chain height indexes every block since
+    /// genesis
+    pub backfill_blocks: Option<u32>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomRegionConfig {
+    pub code: String,
+    /// Region this one lies within, an ISO 3166 code or a custom region
+    /// listed before it
+    pub parent: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RateLimitConfig {
+    /// Requests per minute allowed on read-only routes
+    pub read_per_minute: u32,
+    /// Requests per minute allowed on routes that sign extrinsics or contract calls
+    pub sign_per_minute: u32,
+    /// Key IP buckets on `Forwarded`/`X-Forwarded-For`, only safe behind a proxy
+    pub trust_forwarded_for: bool,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        RateLimitConfig {
+            read_per_minute: 600,
+            sign_per_minute: 30,
+            trust_forwarded_for: false,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AuthConfig {
+    /// When disabled every route is reachable without credentials
+    pub enabled: bool,
+    /// Secret used to verify HS256 signed bearer tokens
+    pub jwt_secret: Option<String>,
+    /// Expected `iss` claim of bearer tokens, if any
+    pub jwt_issuer: Option<String>,
+    pub api_keys: Vec<ApiKeyConfig>,
+    /// Lifetime of the bearer tokens issued on sign-in with an account
+    pub session_ttl_secs: u64,
+    /// Time left to sign a sign-in nonce
+    pub nonce_ttl_secs: u64,
+}
+
+impl Default for AuthConfig {
+    fn default() -> Self {
+        AuthConfig {
+            enabled: true,
+            jwt_secret: None,
+            jwt_issuer: None,
+            api_keys: Vec::new(),
+            session_ttl_secs: 3600,
+            nonce_ttl_secs: 300,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ApiKeyConfig {
+    pub name: String,
+    pub key: String,
+    pub scopes: Vec<Scope>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AgentConfig {
+    /// Run the storage provider agent next to the gateway
+    pub enabled: bool,
+    /// Name of the `keys` entry the agent signs with
+    pub key: Option<String>,
+    /// Seeds the agent can sign with, by name, so that seeds stay out of argv
+    pub keys: BTreeMap<String, String>,
+    /// Pool whose manifests the agent stores
+    pub pool_id: Option<u32>,
+    /// Maximum number of manifests the agent stores at once
+    pub capacity: usize,
+    /// HTTP RPC endpoint of the local IPFS (Kubo) node, also used by
+    /// `/fula/challenge/respond` to check the challenged content
+    pub ipfs_api: Url,
+}
+
+impl Default for AgentConfig {
+    fn default() -> Self {
+        AgentConfig {
+            enabled: false,
+            key: None,
+            keys: BTreeMap::new(),
+            pool_id: None,
+            capacity: 100,
+            ipfs_api: Url::parse("http://127.0.0.1:5001").unwrap(),
+        }
+    }
+}
+
+impl AgentConfig {
+    /// Seed of the selected key
+    pub fn seed(&self) -> Option<Seed> {
+        let key = self.key.as_ref()?;
+        self.keys.get(key).cloned().map(Seed::from)
+    }
+}
+
+impl Config {
+    /// Load the configuration file (if any), then apply environment
+    /// variables and command line flags on top of it and validate the result
+    pub fn load(args: &Args) -> Result<Config, ConfigError> {
+        let mut config = match &args.config {
+            Some(path) => Config::from_file(path)?,
+            None => Config::default(),
+        };
+        config.apply_env()?;
+        config.apply_args(args);
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn from_file(path: &Path) -> Result<Config, ConfigError> {
+        let content = fs::read_to_string(path).map_err(|e| ConfigError::Io {
+            path: path.display().to_string(),
+            error: e.to_string(),
+        })?;
+        toml::from_str(&content).map_err(|e| ConfigError::Parse {
+            path: path.display().to_string(),
+            error: e.to_string(),
+        })
+    }
+
+    fn apply_env(&mut self) -> Result<(), ConfigError> {
+        if let Some(listen) = env_value::<Url>("LISTEN")? {
+            self.server.listen = listen;
+        }
+        if let Some(urls) = env_list::<Url>("NODE_SERVER")? {
+            self.node.urls = urls;
+        }
+        match (
+            env_value::<PathBuf>("TLS_CERT_PATH")?,
+            env_value::<PathBuf>("TLS_KEY_PATH")?,
+        ) {
+            (Some(cert_path), Some(key_path)) => {
+                self.server.tls = Some(TlsConfig {
+                    cert_path,
+                    key_path,
+                })
+            }
+            (None, None) => {}
+            _ => {
+                return Err(ConfigError::invalid(
+                    "TLS_CERT_PATH/TLS_KEY_PATH",
+                    "both variables must be set together",
+                ))
+            }
+        }
+        if let Some(origins) = env_list::<String>("CORS_ALLOWED_ORIGINS")? {
+            self.cors.allowed_origins = origins;
+        }
+        env_override("CORS_ALLOW_LOCALHOST", &mut self.cors.allow_localhost)?;
+
+        let tokens = &mut self.tokens;
+        env_override("LABOR_TOKEN_CLASS_ID", &mut tokens.labor_token_class_id)?;
+        env_override("LABOR_TOKEN_ASSET_ID", &mut tokens.labor_token_asset_id)?;
+        env_override("LABOR_TOKEN_VALUE", &mut tokens.labor_token_value)?;
+        env_override(
+            "CHALLENGE_TOKEN_CLASS_ID",
+            &mut tokens.challenge_token_class_id,
+        )?;
+        env_override(
+            "CHALLENGE_TOKEN_ASSET_ID",
+            &mut tokens.challenge_token_asset_id,
+        )?;
+        env_override("CHALLENGE_TOKEN_VALUE", &mut tokens.challenge_token_value)?;
+        env_override("CLAIMED_TOKEN_CLASS_ID", &mut tokens.claimed_token_class_id)?;
+        env_override("CLAIMED_TOKEN_ASSET_ID", &mut tokens.claimed_token_asset_id)?;
+
+        env_override("REFUND_ENABLED", &mut self.refund.enabled)?;
+        env_override("REFUND_SPONSOR_SEED", &mut self.refund.sponsor_seed)?;
+        env_override("REFUND_FEE_VALUE", &mut self.refund.fee_value)?;
+
+        let contract = &mut self.contract;
+        env_override_option(
+            "GOERLI_CONTRACT_ADDRESS",
+            &mut contract.goerli_contract_address,
+        )?;
+        env_override_option("GOERLI_URL_ID", &mut contract.goerli_url)?;
+        env_override_option(
+            "MUMBAI_CONTRACT_ADDRESS",
+            &mut contract.mumbai_contract_address,
+        )?;
+        env_override_option("MUMBAI_URL_ID", &mut contract.mumbai_url)?;
+        env_override_option("ACCOUNT_ADDRESS", &mut contract.account_address)?;
+        env_override_option("ACCOUNT_PRIVATE_KEY", &mut contract.account_private_key)?;
+        env_override_option("NUMBER_CONFIRMATIONS", &mut contract.number_confirmations)?;
+
+        env_override(
+            "RATE_LIMIT_READ_PER_MINUTE",
+            &mut self.rate_limit.read_per_minute,
+        )?;
+        env_override(
+            "RATE_LIMIT_SIGN_PER_MINUTE",
+            &mut self.rate_limit.sign_per_minute,
+        )?;
+        env_override(
+            "RATE_LIMIT_TRUST_FORWARDED_FOR",
+            &mut self.rate_limit.trust_forwarded_for,
+        )?;
+
+        env_override("AUTH_ENABLED", &mut self.auth.enabled)?;
+        env_override_option("AUTH_JWT_SECRET", &mut self.auth.jwt_secret)?;
+        env_override_option("AUTH_JWT_ISSUER", &mut self.auth.jwt_issuer)?;
+        env_override("AUTH_SESSION_TTL_SECS", &mut self.auth.session_ttl_secs)?;
+        env_override("AUTH_NONCE_TTL_SECS", &mut self.auth.nonce_ttl_secs)?;
+
+        let agent = &mut self.agent;
+        env_override("AGENT_ENABLED", &mut agent.enabled)?;
+        env_override_option("AGENT_KEY", &mut agent.key)?;
+        env_override_option("AGENT_POOL_ID", &mut agent.pool_id)?;
+        env_override("AGENT_CAPACITY", &mut agent.capacity)?;
+        env_override("AGENT_IPFS_API", &mut agent.ipfs_api)?;
+
+        env_override(
+            "MANIFEST_STRICT_METADATA",
+            &mut self.manifest.strict_metadata,
+        )?;
+
+        env_override_option("INDEX_BACKFILL_BLOCKS", &mut self.index.backfill_blocks)?;
+        Ok(())
+    }
+
+    fn apply_args(&mut self, args: &Args) {
+        if let Some(listen) = &args.listen {
+            self.server.listen = listen.clone();
+        }
+        if let Some(node_server) = &args.node_server {
+            self.node.urls = vec![node_server.clone()];
+        }
+        if args.agent {
+            self.agent.enabled = true;
+        }
+        if let Some(key) = &args.key {
+            self.agent.key = Some(key.clone());
+        }
+        if let Some(pool) = args.pool {
+            self.agent.pool_id = Some(pool);
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let listen = &self.server.listen;
+        if listen.host_str().is_none() || listen.port_or_known_default().is_none() {
+            return Err(ConfigError::invalid(
+                "server.listen",
+                format!("{} must include a host and a port", listen),
+            ));
+        }
+        if let Some(tls) = &self.server.tls {
+            for (field, path) in [
+                ("server.tls.cert_path", &tls.cert_path),
+                ("server.tls.key_path", &tls.key_path),
+            ] {
+                if !path.is_file() {
+                    return Err(ConfigError::invalid(
+                        field,
+                        format!("{} is not a readable file", path.display()),
+                    ));
+                }
+            }
+        }
+
+        if self.node.urls.is_empty() {
+            return Err(ConfigError::invalid(
+                "node.urls",
+                "at least one node url is required",
+            ));
+        }
+        for url in self.node.urls.iter() {
+            if url.scheme() != "ws" && url.scheme() != "wss" {
+                return Err(ConfigError::invalid(
+                    "node.urls",
+                    format!("{} must use the ws or wss scheme", url),
+                ));
+            }
+        }
+
+        for origin in self.cors.allowed_origins.iter() {
+            if Url::parse(origin).is_err() {
+                return Err(ConfigError::invalid(
+                    "cors.allowed_origins",
+                    format!("{} is not a valid origin", origin),
+                ));
+            }
+        }
+
+        let tokens = &self.tokens;
+        if (tokens.labor_token_class_id, tokens.labor_token_asset_id)
+            == (
+                tokens.challenge_token_class_id,
+                tokens.challenge_token_asset_id,
+            )
+        {
+            return Err(ConfigError::invalid(
+                "tokens",
+                "labor and challenge tokens must use different class/asset ids",
+            ));
+        }
+        if tokens.labor_token_value == 0 || tokens.challenge_token_value == 0 {
+            return Err(ConfigError::invalid(
+                "tokens",
+                "labor_token_value and challenge_token_value must be greater than zero",
+            ));
+        }
+
+        if self.refund.enabled {
+            if let Err(e) = <sp_core::sr25519::Pair as sp_core::Pair>::from_string(
+                &self.refund.sponsor_seed,
+                None,
+            ) {
+                return Err(ConfigError::invalid(
+                    "refund.sponsor_seed",
+                    format!("{:?}", e),
+                ));
+            }
+        }
+
+        let contract = &self.contract;
+        for (field, address) in [
+            (
+                "contract.goerli_contract_address",
+                &contract.goerli_contract_address,
+            ),
+            (
+                "contract.mumbai_contract_address",
+                &contract.mumbai_contract_address,
+            ),
+            ("contract.account_address", &contract.account_address),
+        ] {
+            if let Some(address) = address {
+                if !is_eth_address(address) {
+                    return Err(ConfigError::invalid(
+                        field,
+                        format!("{} is not a 0x-prefixed 20 byte address", address),
+                    ));
+                }
+            }
+        }
+
+        RegionRegistry::new(&self.regions)?;
+
+        if self.rate_limit.read_per_minute == 0 || self.rate_limit.sign_per_minute == 0 {
+            return Err(ConfigError::invalid(
+                "rate_limit",
+                "budgets must be greater than zero",
+            ));
+        }
+
+        let auth = &self.auth;
+        if auth.enabled {
+            if auth.jwt_secret.is_none() && auth.api_keys.is_empty() {
+                return Err(ConfigError::invalid(
+                    "auth",
+                    "set auth.jwt_secret or at least one auth.api_keys entry, or disable auth",
+                ));
+            }
+            if let Some(secret) = &auth.jwt_secret {
+                if secret.len() < 32 {
+                    return Err(ConfigError::invalid(
+                        "auth.jwt_secret",
+                        "must be at least 32 characters long",
+                    ));
+                }
+            }
+            if auth.session_ttl_secs == 0 || auth.nonce_ttl_secs == 0 {
+                return Err(ConfigError::invalid(
+                    "auth",
+                    "session_ttl_secs and nonce_ttl_secs must be greater than zero",
+                ));
+            }
+            for api_key in auth.api_keys.iter() {
+                if api_key.key.len() < 16 {
+                    return Err(ConfigError::invalid(
+                        "auth.api_keys",
+                        format!(
+                            "key of {} must be at least 16 characters long",
+                            api_key.name
+                        ),
+                    ));
+                }
+                if api_key.scopes.is_empty() {
+                    return Err(ConfigError::invalid(
+                        "auth.api_keys",
+                        format!("key of {} has no scopes", api_key.name),
+                    ));
+                }
+            }
+        }
+
+        let agent = &self.agent;
+        if agent.enabled {
+            let key = agent
+                .key
+                .as_ref()
+                .ok_or_else(|| ConfigError::invalid("agent.key", "required by the agent"))?;
+            let seed = agent.keys.get(key).ok_or_else(|| {
+                ConfigError::invalid("agent.key", format!("no agent.keys entry named {}", key))
+            })?;
+            if let Err(e) = <sp_core::sr25519::Pair as sp_core::Pair>::from_string(seed, None) {
+                return Err(ConfigError::invalid(
+                    "agent.keys",
+                    format!("{}: {:?}", key, e),
+                ));
+            }
+            if agent.pool_id.is_none() {
+                return Err(ConfigError::invalid(
+                    "agent.pool_id",
+                    "required by the agent",
+                ));
+            }
+            if agent.capacity == 0 {
+                return Err(ConfigError::invalid(
+                    "agent.capacity",
+                    "must be greater than zero",
+                ));
+            }
+        }
+        if agent.ipfs_api.scheme() != "http" && agent.ipfs_api.scheme() != "https" {
+            return Err(ConfigError::invalid(
+                "agent.ipfs_api",
+                format!("{} must use the http or https scheme", agent.ipfs_api),
+            ));
+        }
+        Ok(())
+    }
+
+    /// The contract calls read their settings from the process environment,
+    /// so values coming from the config file are exported there
+    pub fn export_contract_env(&self) {
+        let contract = &self.contract;
+        let values = [
+            (
+                "GOERLI_CONTRACT_ADDRESS",
+                contract.goerli_contract_address.clone(),
+            ),
+            ("GOERLI_URL_ID", contract.goerli_url.clone()),
+            (
+                "MUMBAI_CONTRACT_ADDRESS",
+                contract.mumbai_contract_address.clone(),
+            ),
+            ("MUMBAI_URL_ID", contract.mumbai_url.clone()),
+            ("ACCOUNT_ADDRESS", contract.account_address.clone()),
+            ("ACCOUNT_PRIVATE_KEY", contract.account_private_key.clone()),
+            (
+                "NUMBER_CONFIRMATIONS",
+                contract.number_confirmations.map(|value| value.to_string()),
+            ),
+        ];
+        for (key, value) in values {
+            if let Some(value) = value {
+                env::set_var(key, value);
+            }
+        }
+    }
+}
+
+impl TlsConfig {
+    pub fn rustls_config(&self) -> Result<rustls::ServerConfig, ConfigError> {
+        let io_err = |path: &Path, e: std::io::Error| ConfigError::Io {
+            path: path.display().to_string(),
+            error: e.to_string(),
+        };
+
+        let cert_file = File::open(&self.cert_path).map_err(|e| io_err(&self.cert_path, e))?;
+        let certs = rustls_pemfile::certs(&mut BufReader::new(cert_file))
+            .map_err(|e| io_err(&self.cert_path, e))?
+            .into_iter()
+            .map(rustls::Certificate)
+            .collect();
+
+        let key_file = File::open(&self.key_path).map_err(|e| io_err(&self.key_path, e))?;
+        let mut keys = rustls_pemfile::pkcs8_private_keys(&mut BufReader::new(key_file))
+            .map_err(|e| io_err(&self.key_path, e))?;
+        if keys.is_empty() {
+            return Err(ConfigError::invalid(
+                "server.tls.key_path",
+                "no PKCS#8 private key found",
+            ));
+        }
+
+        rustls::ServerConfig::builder()
+            .with_safe_defaults()
+            .with_no_client_auth()
+            .with_single_cert(certs, rustls::PrivateKey(keys.remove(0)))
+            .map_err(|e| ConfigError::invalid("server.tls", e.to_string()))
+    }
+}
+
+fn is_eth_address(address: &str) -> bool {
+    match address.strip_prefix("0x") {
+        Some(hex_value) => hex_value.len() == 40 && hex::decode(hex_value).is_ok(),
+        None => false,
+    }
+}
+
+fn env_value<T>(key: &str) -> Result<Option<T>, ConfigError>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    match env::var(key) {
+        Ok(value) if !value.trim().is_empty() => value
+            .trim()
+            .parse::<T>()
+            .map(Some)
+            .map_err(|e| ConfigError::invalid(key, e.to_string())),
+        _ => Ok(None),
+    }
+}
+
+fn env_list<T>(key: &str) -> Result<Option<Vec<T>>, ConfigError>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    match env::var(key) {
+        Ok(value) if !value.trim().is_empty() => value
+            .split(',')
+            .map(|item| {
+                item.trim()
+                    .parse::<T>()
+                    .map_err(|e| ConfigError::invalid(key, e.to_string()))
+            })
+            .collect::<Result<Vec<T>, ConfigError>>()
+            .map(Some),
+        _ => Ok(None),
+    }
+}
+
+fn env_override<T>(key: &str, target: &mut T) -> Result<(), ConfigError>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    if let Some(value) = env_value(key)? {
+        *target = value;
+    }
+    Ok(())
+}
+
+fn env_override_option<T>(key: &str, target: &mut Option<T>) -> Result<(), ConfigError>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    if let Some(value) = env_value(key)? {
+        *target = Some(value);
     }
+    Ok(())
 }
@@ -1,11 +1,9 @@
 use crate::bundle::*;
-use crate::config;
 use crate::state::AppState;
 use crate::util::*;
 use actix_web::{error, web, HttpResponse};
 use codec::Encode;
 use contract_integration::calls::*;
-use dotenv::dotenv;
 use hex::ToHex;
 use serde_json::json;
 use sp_core::U256;
@@ -19,9 +17,18 @@ use sugarfunge_api_types::primitives::*;
 use sugarfunge_api_types::sugarfunge;
 use sugarfunge_api_types::sugarfunge::runtime_types::sp_core::bounded::bounded_vec::BoundedVec;
 
-pub async fn contract_mint_to(
-    req: web::Json<ContractTransactionInput>,
-) -> error::Result<HttpResponse> {
+#[utoipa::path(
+    post,
+    path = "/fula/contract/mint",
+    tag = "fula/contract",
+    request_body = ContractTransactionInput,
+    responses(
+        (status = 200, description = "Transaction receipt", body = serde_json::Value),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["contract:admin"]), ("bearer" = ["contract:admin"])),
+)]
+pub async fn contract_mint_to(req: Json<ContractTransactionInput>) -> error::Result<HttpResponse> {
     let result = goerli_mint_to(req.account_address.as_str(), U256::from(req.amount)).await;
     match result {
         Ok(event) => Ok(HttpResponse::Ok().json(event)),
@@ -32,8 +39,19 @@ pub async fn contract_mint_to(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/fula/contract/burn",
+    tag = "fula/contract",
+    request_body = ContractTransactionInput,
+    responses(
+        (status = 200, description = "Transaction receipt", body = serde_json::Value),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["contract:admin"]), ("bearer" = ["contract:admin"])),
+)]
 pub async fn contract_burn_from(
-    req: web::Json<ContractTransactionInput>,
+    req: Json<ContractTransactionInput>,
 ) -> error::Result<HttpResponse> {
     let result = goerli_burn_from(req.account_address.as_str(), U256::from(req.amount)).await;
 
@@ -46,9 +64,18 @@ pub async fn contract_burn_from(
     }
 }
 
-pub async fn contract_transfer(
-    req: web::Json<ContractTransactionInput>,
-) -> error::Result<HttpResponse> {
+#[utoipa::path(
+    post,
+    path = "/fula/contract/transfer",
+    tag = "fula/contract",
+    request_body = ContractTransactionInput,
+    responses(
+        (status = 200, description = "Transaction receipt", body = serde_json::Value),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["contract:admin"]), ("bearer" = ["contract:admin"])),
+)]
+pub async fn contract_transfer(req: Json<ContractTransactionInput>) -> error::Result<HttpResponse> {
     let result = goerli_transfer(req.account_address.as_str(), U256::from(req.amount)).await;
 
     match result {
@@ -60,6 +87,16 @@ pub async fn contract_transfer(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/fula/contract/supply",
+    tag = "fula/contract",
+    responses(
+        (status = 200, body = ContractTotalSupplyOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["read"]), ("bearer" = ["read"])),
+)]
 pub async fn contract_total_supply() -> error::Result<HttpResponse> {
     let result = goerli_total_supply().await;
 
@@ -74,9 +111,18 @@ pub async fn contract_total_supply() -> error::Result<HttpResponse> {
     }
 }
 
-pub async fn contract_allowance(
-    req: web::Json<ContractAllowanceInput>,
-) -> error::Result<HttpResponse> {
+#[utoipa::path(
+    post,
+    path = "/fula/contract/allowance",
+    tag = "fula/contract",
+    request_body = ContractAllowanceInput,
+    responses(
+        (status = 200, body = ContractAllowanceOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["read"]), ("bearer" = ["read"])),
+)]
+pub async fn contract_allowance(req: Json<ContractAllowanceInput>) -> error::Result<HttpResponse> {
     let result = goerli_allowance(req.owner_address.as_str(), req.spender_address.as_str()).await;
 
     match result {
@@ -90,8 +136,19 @@ pub async fn contract_allowance(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/fula/contract/increase_allowance",
+    tag = "fula/contract",
+    request_body = ContractTransactionInput,
+    responses(
+        (status = 200, description = "Transaction receipt", body = serde_json::Value),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["contract:admin"]), ("bearer" = ["contract:admin"])),
+)]
 pub async fn contract_increase_allowance(
-    req: web::Json<ContractTransactionInput>,
+    req: Json<ContractTransactionInput>,
 ) -> error::Result<HttpResponse> {
     let result =
         goerli_increase_allowance(req.account_address.as_str(), U256::from(req.amount)).await;
@@ -105,8 +162,19 @@ pub async fn contract_increase_allowance(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/fula/contract/decrease_allowance",
+    tag = "fula/contract",
+    request_body = ContractTransactionInput,
+    responses(
+        (status = 200, description = "Transaction receipt", body = serde_json::Value),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["contract:admin"]), ("bearer" = ["contract:admin"])),
+)]
 pub async fn contract_decrease_allowance(
-    req: web::Json<ContractTransactionInput>,
+    req: Json<ContractTransactionInput>,
 ) -> error::Result<HttpResponse> {
     let result =
         goerli_decrease_allowance(req.account_address.as_str(), U256::from(req.amount)).await;
@@ -124,12 +192,22 @@ fn hash(s: &[u8]) -> sp_core::H256 {
     sp_io::hashing::blake2_256(s).into()
 }
 
+#[utoipa::path(
+    post,
+    path = "/fula/goerli/convert_tokens",
+    tag = "fula",
+    request_body = ConvertFulaInput,
+    responses(
+        (status = 200, description = "Transaction receipt", body = serde_json::Value),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["contract:admin"]), ("bearer" = ["contract:admin"])),
+)]
 pub async fn goerli_convert_to_fula(
     data: web::Data<AppState>,
-    req: web::Json<ConvertFulaInput>,
+    req: Json<ConvertFulaInput>,
 ) -> error::Result<HttpResponse> {
-    dotenv().ok();
-    let env = config::init();
+    let env = &data.config.tokens;
 
     let pair = get_pair_from_seed(&req.seed)?;
     let signer = PairSigner::new(pair);
@@ -175,11 +253,7 @@ pub async fn goerli_convert_to_fula(
             );
 
             let _result = api
-                .tx()
-                .sign_and_submit_then_watch(&call, &signer, Default::default())
-                .await
-                .map_err(map_subxt_err)?
-                .wait_for_finalized_success()
+                .sign_and_submit(&call, &signer)
                 .await
                 .map_err(map_sf_err)?;
             // println!("4. BUNDLE CREATED");
@@ -197,11 +271,7 @@ pub async fn goerli_convert_to_fula(
         );
 
         let result = api
-            .tx()
-            .sign_and_submit_then_watch(&call, &signer, Default::default())
-            .await
-            .map_err(map_subxt_err)?
-            .wait_for_finalized_success()
+            .sign_and_submit(&call, &signer)
             .await
             .map_err(map_sf_err)?;
         let result = result
@@ -238,12 +308,22 @@ pub async fn goerli_convert_to_fula(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/fula/mumbai/convert_tokens",
+    tag = "fula",
+    request_body = ConvertFulaInput,
+    responses(
+        (status = 200, description = "Transaction receipt", body = serde_json::Value),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["contract:admin"]), ("bearer" = ["contract:admin"])),
+)]
 pub async fn mumbai_convert_to_fula(
     data: web::Data<AppState>,
-    req: web::Json<ConvertFulaInput>,
+    req: Json<ConvertFulaInput>,
 ) -> error::Result<HttpResponse> {
-    dotenv().ok();
-    let env = config::init();
+    let env = &data.config.tokens;
 
     let pair = get_pair_from_seed(&req.seed)?;
     let signer = PairSigner::new(pair);
@@ -289,11 +369,7 @@ pub async fn mumbai_convert_to_fula(
             );
 
             let _result = api
-                .tx()
-                .sign_and_submit_then_watch(&call, &signer, Default::default())
-                .await
-                .map_err(map_subxt_err)?
-                .wait_for_finalized_success()
+                .sign_and_submit(&call, &signer)
                 .await
                 .map_err(map_sf_err)?;
             // println!("4. BUNDLE CREATED");
@@ -311,11 +387,7 @@ pub async fn mumbai_convert_to_fula(
         );
 
         let result = api
-            .tx()
-            .sign_and_submit_then_watch(&call, &signer, Default::default())
-            .await
-            .map_err(map_subxt_err)?
-            .wait_for_finalized_success()
+            .sign_and_submit(&call, &signer)
             .await
             .map_err(map_sf_err)?;
         let result = result
@@ -0,0 +1,154 @@
+//! Indexes built from finalized blocks.
+//!
+//! The chain does not keep everything the gateway answers with (when join
+//! requests were made, past challenges, what an account did), so indexes are
+//! rebuilt from the blocks. At startup an index catches up on the finalized
+//! blocks it backfills, then follows every block finalized from then on.
+//! Blocks the subscription skips, or that are finalized while it is renewed,
+//! are fetched by number, so every block is indexed once and in order.
+
+use crate::chain::Block;
+use crate::config::IndexConfig;
+use crate::state::ClientAPI;
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Delay before subscribing again when the block stream ends
+const RESUBSCRIBE_DELAY: Duration = Duration::from_secs(5);
+
+/// Blocks backfilled when the configuration leaves it unset, a day of blocks
+pub const DEFAULT_BACKFILL_BLOCKS: u32 = 14_400;
+
+type Blocks = BoxStream<'static, Result<Block, subxt::Error>>;
+
+#[async_trait]
+pub trait BlockIndex: Send + Sync + 'static {
+    /// Name used in the logs
+    const NAME: &'static str;
+
+    /// Called once before the first block is indexed
+    async fn start(&self, _api: &ClientAPI) -> Result<(), subxt::Error> {
+        Ok(())
+    }
+
+    async fn index(&self, api: &ClientAPI, block: &Block) -> Result<(), subxt::Error>;
+}
+
+/// Finalized blocks indexed before the ones finalized after subscribing
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Backfill {
+    None,
+    /// The latest finalized blocks, every block since genesis when the chain
+    /// is shorter
+    Blocks(u32),
+}
+
+impl From<&IndexConfig> for Backfill {
+    fn from(config: &IndexConfig) -> Backfill {
+        Backfill::Blocks(config.backfill_blocks.unwrap_or(DEFAULT_BACKFILL_BLOCKS))
+    }
+}
+
+/// Subscribe to finalized blocks and index them with `index` in the
+/// background, after the blocks of `backfill`
+pub async fn follow<I: BlockIndex>(
+    index: Arc<I>,
+    api: ClientAPI,
+    backfill: Backfill,
+) -> Result<(), subxt::Error> {
+    let blocks = api.watch_blocks().await?;
+    let next = match backfill {
+        Backfill::None => None,
+        // The genesis block has no extrinsics
+        Backfill::Blocks(blocks) => {
+            let finalized = api.finalized_block_number().await?;
+            Some((finalized + 1).saturating_sub(blocks).max(1))
+        }
+    };
+    let follower = Follower { index, api, next };
+    actix_web::rt::spawn(async move { follower.run(blocks).await });
+    Ok(())
+}
+
+struct Follower<I> {
+    index: Arc<I>,
+    api: ClientAPI,
+    /// Number of the next block to index, none until the first one
+    next: Option<u32>,
+}
+
+impl<I: BlockIndex> Follower<I> {
+    async fn run(mut self, mut blocks: Blocks) {
+        if let Err(e) = self.index.start(&self.api).await {
+            log::warn!("{} could not start: {}", I::NAME, e);
+        }
+        if self.next.is_some() {
+            match self.api.finalized_block_number().await {
+                Ok(finalized) => self.catch_up(finalized).await,
+                Err(e) => log::warn!("{} could not backfill: {}", I::NAME, e),
+            }
+        }
+        loop {
+            while let Some(block) = blocks.next().await {
+                match block {
+                    Ok(block) => self.follow(block).await,
+                    Err(e) => log::warn!("{} block error: {}", I::NAME, e),
+                }
+            }
+            log::warn!("{} block stream ended, subscribing again", I::NAME);
+            loop {
+                actix_web::rt::time::sleep(RESUBSCRIBE_DELAY).await;
+                match self.api.watch_blocks().await {
+                    Ok(stream) => {
+                        blocks = stream;
+                        break;
+                    }
+                    Err(e) => log::warn!("{} could not watch blocks: {}", I::NAME, e),
+                }
+            }
+        }
+    }
+
+    async fn follow(&mut self, block: Block) {
+        match self.next {
+            // Indexed while catching up
+            Some(next) if block.number < next => return,
+            Some(next) if block.number > next => self.catch_up(block.number - 1).await,
+            _ => {}
+        }
+        self.index_block(&block).await;
+    }
+
+    /// Index the blocks from the next one to `until`, fetched by number
+    async fn catch_up(&mut self, until: u32) {
+        while let Some(next) = self.next.filter(|next| *next <= until) {
+            match self.api.block(next).await {
+                Ok(Some(block)) => self.index_block(&block).await,
+                Ok(None) => {
+                    log::warn!("{} could not find block {}", I::NAME, next);
+                    self.next = Some(next + 1);
+                }
+                Err(e) => {
+                    log::warn!(
+                        "{} could not fetch blocks {} to {}, they are not indexed: {}",
+                        I::NAME,
+                        next,
+                        until,
+                        e
+                    );
+                    self.next = Some(until + 1);
+                }
+            }
+        }
+    }
+
+    async fn index_block(&mut self, block: &Block) {
+        if let Err(e) = self.index.index(&self.api, block).await {
+            log::warn!("{} could not index block {}: {}", I::NAME, block.number, e);
+        }
+        self.next = Some(block.number + 1);
+    }
+}
@@ -21,13 +21,26 @@ use sugarfunge_api_types::sugarfunge::runtime_types::functionland_fula::{
 use sugarfunge_api_types::sugarfunge::runtime_types::sp_core::bounded::bounded_vec::BoundedVec;
 // use sugarfunge_api_types::sugarfunge::runtime_types::sp_runtime::bounded::bounded_vec::BoundedVec;
 
+#[utoipa::path(
+    post,
+    path = "/fula/manifest/upload",
+    tag = "fula",
+    request_body = UploadManifestInput,
+    responses(
+        (status = 200, body = UploadManifestOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["fula:write"]), ("bearer" = ["fula:write"])),
+)]
 pub async fn upload_manifest(
     data: web::Data<AppState>,
-    req: web::Json<UploadManifestInput>,
+    req: Json<UploadManifestInput>,
 ) -> error::Result<HttpResponse> {
     let pair = get_pair_from_seed(&req.seed.clone())?;
     let signer = PairSigner::new(pair);
 
+    check_manifest_metadata(&data, "manifest_metadata", &req.manifest_metadata, &req.cid)?;
+
     let cid: Vec<u8> = String::from(&req.cid.clone()).into_bytes();
     let cid = BoundedVec(cid);
 
@@ -43,11 +56,7 @@ pub async fn upload_manifest(
     );
 
     let result = api
-        .tx()
-        .sign_and_submit_then_watch(&call, &signer, Default::default())
-        .await
-        .map_err(map_subxt_err)?
-        .wait_for_finalized_success()
+        .sign_and_submit(&call, &signer)
         .await
         .map_err(map_fula_err)?;
     let result = result
@@ -57,13 +66,17 @@ pub async fn upload_manifest(
         return Err(value_error);
     }
     match result {
-        Some(event) => Ok(HttpResponse::Ok().json(UploadManifestOutput {
-            uploader: event.uploader.into(),
-            storers: transform_vec_string_to_account(transform_storage_output(event.storer)),
-            manifest_metadata: serde_json::from_slice(event.manifest.as_slice())
-                .unwrap_or_default(),
-            pool_id: event.pool_id.into(),
-        })),
+        Some(event) => {
+            let (manifest_metadata, metadata_undecodable) =
+                decode_manifest_metadata(event.manifest.as_slice());
+            Ok(HttpResponse::Ok().json(UploadManifestOutput {
+                uploader: event.uploader.into(),
+                storers: transform_vec_string_to_account(transform_storage_output(event.storer)),
+                manifest_metadata,
+                metadata_undecodable,
+                pool_id: event.pool_id.into(),
+            }))
+        }
         None => Ok(HttpResponse::BadRequest().json(RequestError {
             message: json!("Failed to find sugarfunge::fula::events::UploadManifests"),
             description: format!(""),
@@ -71,13 +84,51 @@ pub async fn upload_manifest(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/fula/manifest/batch_upload",
+    tag = "fula",
+    request_body = BatchUploadManifestInput,
+    responses(
+        (status = 200, body = BatchUploadManifestOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["fula:write"]), ("bearer" = ["fula:write"])),
+)]
 pub async fn batch_upload_manifest(
     data: web::Data<AppState>,
-    req: web::Json<BatchUploadManifestInput>,
+    req: Json<BatchUploadManifestInput>,
 ) -> error::Result<HttpResponse> {
     let pair = get_pair_from_seed(&req.seed.clone())?;
     let signer = PairSigner::new(pair);
 
+    let len = req.cid.len();
+    if req.manifest_metadata.len() != len
+        || req.pool_id.len() != len
+        || req.replication_factor.len() != len
+    {
+        let req_error = RequestError {
+            message: json!(format!(
+                "manifest_metadata has {}, cid {}, pool_id {} and replication_factor {} items",
+                req.manifest_metadata.len(),
+                len,
+                req.pool_id.len(),
+                req.replication_factor.len()
+            )),
+            description: "Every list must have one item per manifest".into(),
+        };
+        return Err(error::ErrorBadRequest(
+            serde_json::to_string_pretty(&req_error).unwrap(),
+        ));
+    }
+
+    for (index, (manifest_metadata, cid)) in
+        req.manifest_metadata.iter().zip(req.cid.iter()).enumerate()
+    {
+        let field = format!("manifest_metadata[{}]", index);
+        check_manifest_metadata(&data, &field, manifest_metadata, cid)?;
+    }
+
     let pool_ids = get_vec_pool_id_from_input(req.pool_id.to_vec());
     let cids = get_vec_cids_from_input(req.cid.to_vec());
     let manifests = get_vec_manifests_from_input(req.manifest_metadata.to_vec());
@@ -94,22 +145,23 @@ pub async fn batch_upload_manifest(
     );
 
     let result = api
-        .tx()
-        .sign_and_submit_then_watch(&call, &signer, Default::default())
-        .await
-        .map_err(map_subxt_err)?
-        .wait_for_finalized_success()
+        .sign_and_submit(&call, &signer)
         .await
         .map_err(map_fula_err)?;
     let result = result
         .find_first::<sugarfunge::fula::events::BatchManifestOutput>()
         .map_err(map_subxt_err)?;
     match result {
-        Some(event) => Ok(HttpResponse::Ok().json(BatchUploadManifestOutput {
-            uploader: event.uploader.into(),
-            manifest_metadata: get_vec_manifests_from_node(event.manifests),
-            pool_id: get_vec_pool_id_from_node(event.pool_ids),
-        })),
+        Some(event) => {
+            let (manifest_metadata, metadata_undecodable) =
+                get_vec_manifests_from_node(event.manifests);
+            Ok(HttpResponse::Ok().json(BatchUploadManifestOutput {
+                uploader: event.uploader.into(),
+                manifest_metadata,
+                metadata_undecodable,
+                pool_id: get_vec_pool_id_from_node(event.pool_ids),
+            }))
+        }
         None => Ok(HttpResponse::BadRequest().json(RequestError {
             message: json!("Failed to find sugarfunge::fula::events::BatchUploadManifests"),
             description: format!(""),
@@ -117,9 +169,20 @@ pub async fn batch_upload_manifest(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/fula/manifest/storage",
+    tag = "fula",
+    request_body = StorageManifestInput,
+    responses(
+        (status = 200, body = StorageManifestOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["fula:write"]), ("bearer" = ["fula:write"])),
+)]
 pub async fn storage_manifest(
     data: web::Data<AppState>,
-    req: web::Json<StorageManifestInput>,
+    req: Json<StorageManifestInput>,
 ) -> error::Result<HttpResponse> {
     let pair = get_pair_from_seed(&req.seed)?;
     let signer = PairSigner::new(pair);
@@ -133,11 +196,7 @@ pub async fn storage_manifest(
         .storage_manifest(cid, req.pool_id.into());
 
     let result = api
-        .tx()
-        .sign_and_submit_then_watch(&call, &signer, Default::default())
-        .await
-        .map_err(map_subxt_err)?
-        .wait_for_finalized_success()
+        .sign_and_submit(&call, &signer)
         .await
         .map_err(map_fula_err)?;
     let result = result
@@ -159,9 +218,20 @@ pub async fn storage_manifest(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/fula/manifest/batch_storage",
+    tag = "fula",
+    request_body = BatchStorageManifestInput,
+    responses(
+        (status = 200, body = BatchStorageManifestOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["fula:write"]), ("bearer" = ["fula:write"])),
+)]
 pub async fn batch_storage_manifest(
     data: web::Data<AppState>,
-    req: web::Json<BatchStorageManifestInput>,
+    req: Json<BatchStorageManifestInput>,
 ) -> error::Result<HttpResponse> {
     let pair = get_pair_from_seed(&req.seed.clone())?;
     let signer = PairSigner::new(pair);
@@ -175,11 +245,7 @@ pub async fn batch_storage_manifest(
         .batch_storage_manifest(cids, req.pool_id.into());
 
     let result = api
-        .tx()
-        .sign_and_submit_then_watch(&call, &signer, Default::default())
-        .await
-        .map_err(map_subxt_err)?
-        .wait_for_finalized_success()
+        .sign_and_submit(&call, &signer)
         .await
         .map_err(map_fula_err)?;
     let result = result
@@ -198,9 +264,20 @@ pub async fn batch_storage_manifest(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/fula/manifest/remove",
+    tag = "fula",
+    request_body = RemoveManifestInput,
+    responses(
+        (status = 200, body = RemoveManifestOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["fula:write"]), ("bearer" = ["fula:write"])),
+)]
 pub async fn remove_manifest(
     data: web::Data<AppState>,
-    req: web::Json<RemoveManifestInput>,
+    req: Json<RemoveManifestInput>,
 ) -> error::Result<HttpResponse> {
     let pair = get_pair_from_seed(&req.seed)?;
     let signer = PairSigner::new(pair);
@@ -213,11 +290,7 @@ pub async fn remove_manifest(
         .remove_manifest(cid, req.pool_id.into());
 
     let result = api
-        .tx()
-        .sign_and_submit_then_watch(&call, &signer, Default::default())
-        .await
-        .map_err(map_subxt_err)?
-        .wait_for_finalized_success()
+        .sign_and_submit(&call, &signer)
         .await
         .map_err(map_fula_err)?;
     let result = result
@@ -239,9 +312,20 @@ pub async fn remove_manifest(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/fula/manifest/batch_remove",
+    tag = "fula",
+    request_body = BatchRemoveManifestInput,
+    responses(
+        (status = 200, body = BatchRemoveManifestOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["fula:write"]), ("bearer" = ["fula:write"])),
+)]
 pub async fn batch_remove_manifest(
     data: web::Data<AppState>,
-    req: web::Json<BatchRemoveManifestInput>,
+    req: Json<BatchRemoveManifestInput>,
 ) -> error::Result<HttpResponse> {
     let pair = get_pair_from_seed(&req.seed)?;
     let signer = PairSigner::new(pair);
@@ -256,11 +340,7 @@ pub async fn batch_remove_manifest(
         .batch_remove_manifest(cids, pool_ids);
 
     let result = api
-        .tx()
-        .sign_and_submit_then_watch(&call, &signer, Default::default())
-        .await
-        .map_err(map_subxt_err)?
-        .wait_for_finalized_success()
+        .sign_and_submit(&call, &signer)
         .await
         .map_err(map_fula_err)?;
     let result = result
@@ -279,9 +359,20 @@ pub async fn batch_remove_manifest(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/fula/manifest/remove_stored_manifest",
+    tag = "fula",
+    request_body = RemoveStoringManifestInput,
+    responses(
+        (status = 200, body = RemoveStoringManifestOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["fula:write"]), ("bearer" = ["fula:write"])),
+)]
 pub async fn remove_stored_manifest(
     data: web::Data<AppState>,
-    req: web::Json<RemoveStoringManifestInput>,
+    req: Json<RemoveStoringManifestInput>,
 ) -> error::Result<HttpResponse> {
     let pair = get_pair_from_seed(&req.seed)?;
     let signer = PairSigner::new(pair);
@@ -296,11 +387,7 @@ pub async fn remove_stored_manifest(
         .remove_stored_manifest(cid, req.pool_id.into());
 
     let result = api
-        .tx()
-        .sign_and_submit_then_watch(&call, &signer, Default::default())
-        .await
-        .map_err(map_subxt_err)?
-        .wait_for_finalized_success()
+        .sign_and_submit(&call, &signer)
         .await
         .map_err(map_fula_err)?;
     let result = result
@@ -322,9 +409,20 @@ pub async fn remove_stored_manifest(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/fula/manifest/batch_remove_stored_manifest",
+    tag = "fula",
+    request_body = BatchRemoveStoringManifestInput,
+    responses(
+        (status = 200, body = BatchRemoveStoringManifestOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["fula:write"]), ("bearer" = ["fula:write"])),
+)]
 pub async fn batch_remove_stored_manifest(
     data: web::Data<AppState>,
-    req: web::Json<BatchRemoveStoringManifestInput>,
+    req: Json<BatchRemoveStoringManifestInput>,
 ) -> error::Result<HttpResponse> {
     let pair = get_pair_from_seed(&req.seed)?;
     let signer = PairSigner::new(pair);
@@ -338,11 +436,7 @@ pub async fn batch_remove_stored_manifest(
         .batch_remove_stored_manifest(cids, req.pool_id.into());
 
     let result = api
-        .tx()
-        .sign_and_submit_then_watch(&call, &signer, Default::default())
-        .await
-        .map_err(map_subxt_err)?
-        .wait_for_finalized_success()
+        .sign_and_submit(&call, &signer)
         .await
         .map_err(map_fula_err)?;
     let result = result
@@ -361,9 +455,20 @@ pub async fn batch_remove_stored_manifest(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/fula/manifest/verify",
+    tag = "fula",
+    request_body = VerifyManifestsInput,
+    responses(
+        (status = 200, body = VerifyManifestsOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["fula:write"]), ("bearer" = ["fula:write"])),
+)]
 pub async fn verify_manifest(
     data: web::Data<AppState>,
-    req: web::Json<VerifyManifestsInput>,
+    req: Json<VerifyManifestsInput>,
 ) -> error::Result<HttpResponse> {
     let pair = get_pair_from_seed(&req.seed.clone())?;
     let signer = PairSigner::new(pair);
@@ -373,11 +478,7 @@ pub async fn verify_manifest(
     let call = sugarfunge::tx().fula().verify_manifests();
 
     let result = api
-        .tx()
-        .sign_and_submit_then_watch(&call, &signer, Default::default())
-        .await
-        .map_err(map_subxt_err)?
-        .wait_for_finalized_success()
+        .sign_and_submit(&call, &signer)
         .await
         .map_err(map_fula_err)?;
     let result = result
@@ -398,9 +499,20 @@ pub async fn verify_manifest(
         })),
     }
 }
+#[utoipa::path(
+    post,
+    path = "/fula/manifest/update",
+    tag = "fula",
+    request_body = UpdateManifestInput,
+    responses(
+        (status = 200, body = UpdatedManifestOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["fula:write"]), ("bearer" = ["fula:write"])),
+)]
 pub async fn update_manifest(
     data: web::Data<AppState>,
-    req: web::Json<UpdateManifestInput>,
+    req: Json<UpdateManifestInput>,
 ) -> error::Result<HttpResponse> {
     let pair = get_pair_from_seed(&req.seed)?;
     let signer = PairSigner::new(pair);
@@ -419,11 +531,7 @@ pub async fn update_manifest(
     );
 
     let result = api
-        .tx()
-        .sign_and_submit_then_watch(&call, &signer, Default::default())
-        .await
-        .map_err(map_subxt_err)?
-        .wait_for_finalized_success()
+        .sign_and_submit(&call, &signer)
         .await
         .map_err(map_fula_err)?;
     let result = result
@@ -445,9 +553,20 @@ pub async fn update_manifest(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/fula/manifest",
+    tag = "fula",
+    request_body = GetAllManifestsInput,
+    responses(
+        (status = 200, body = GetAllManifestsOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["read"]), ("bearer" = ["read"])),
+)]
 pub async fn get_all_manifests(
     data: web::Data<AppState>,
-    req: web::Json<GetAllManifestsInput>,
+    req: Json<GetAllManifestsInput>,
 ) -> error::Result<HttpResponse> {
     let api = &data.api;
     let mut result_array = Vec::new();
@@ -464,7 +583,7 @@ pub async fn get_all_manifests(
     }
     // println!("query_key account_to len: {}", query_key.len());
 
-    let storage = api.storage().at_latest().await.map_err(map_subxt_err)?;
+    let storage = api.storage().await.map_err(map_subxt_err)?;
 
     let keys = storage
         .fetch_keys(&query_key, 1000, None)
@@ -509,11 +628,13 @@ pub async fn get_all_manifests(
             }
 
             if meet_requirements {
+                let (manifest_metadata, metadata_undecodable) =
+                    decode_manifest_metadata(value.manifest_metadata.as_slice());
                 result_array.push(Manifest {
                     pool_id: pool_id.into(),
                     uploaders: uploaders_data.to_owned(),
-                    manifest_metadata: serde_json::from_slice(value.manifest_metadata.as_slice())
-                        .unwrap_or_default(),
+                    manifest_metadata,
+                    metadata_undecodable,
                     size: value.size,
                 });
             }
@@ -524,9 +645,20 @@ pub async fn get_all_manifests(
     }))
 }
 
+#[utoipa::path(
+    post,
+    path = "/fula/manifest/available",
+    tag = "fula",
+    request_body = GetAvailableManifestsInput,
+    responses(
+        (status = 200, body = GetAvailableManifestsOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["read"]), ("bearer" = ["read"])),
+)]
 pub async fn get_available_manifests(
     data: web::Data<AppState>,
-    req: web::Json<GetAvailableManifestsInput>,
+    req: Json<GetAvailableManifestsInput>,
 ) -> error::Result<HttpResponse> {
     let api = &data.api;
     let mut result_array = Vec::new();
@@ -542,7 +674,7 @@ pub async fn get_available_manifests(
         query_key.extend(subxt::ext::sp_core::blake2_128(&key_value.encode()));
     }
 
-    let storage = api.storage().at_latest().await.map_err(map_subxt_err)?;
+    let storage = api.storage().await.map_err(map_subxt_err)?;
 
     let keys = storage
         .fetch_keys(&query_key, 1000, None)
@@ -564,10 +696,12 @@ pub async fn get_available_manifests(
             let uploaders_data =
                 transform_vec_uploader_data_runtime_to_vec_uploader_data(value.users_data);
             if verify_availability(uploaders_data.to_vec()) {
+                let (manifest_metadata, metadata_undecodable) =
+                    decode_manifest_metadata(value.manifest_metadata.as_slice());
                 result_array.push(ManifestAvailable {
                     pool_id: pool_id.into(),
-                    manifest_metadata: serde_json::from_slice(value.manifest_metadata.as_slice())
-                        .unwrap_or_default(),
+                    manifest_metadata,
+                    metadata_undecodable,
                     replication_available: get_added_replication(uploaders_data.to_owned()),
                 })
             }
@@ -578,9 +712,20 @@ pub async fn get_available_manifests(
     }))
 }
 
+#[utoipa::path(
+    post,
+    path = "/fula/manifest/storer_data",
+    tag = "fula",
+    request_body = GetAllManifestsStorerDataInput,
+    responses(
+        (status = 200, body = GetAllManifestsStorerDataOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["read"]), ("bearer" = ["read"])),
+)]
 pub async fn get_all_manifests_storer_data(
     data: web::Data<AppState>,
-    req: web::Json<GetAllManifestsStorerDataInput>,
+    req: Json<GetAllManifestsStorerDataInput>,
 ) -> error::Result<HttpResponse> {
     let api = &data.api;
     let mut result_array = Vec::new();
@@ -597,7 +742,7 @@ pub async fn get_all_manifests_storer_data(
         // println!("query_key pool_id len: {}", query_key.len());
     }
 
-    let storage = api.storage().at_latest().await.map_err(map_subxt_err)?;
+    let storage = api.storage().await.map_err(map_subxt_err)?;
 
     let keys = storage
         .fetch_keys(&query_key, 1000, None)
@@ -659,9 +804,20 @@ pub async fn get_all_manifests_storer_data(
     }))
 }
 
+#[utoipa::path(
+    post,
+    path = "/fula/manifest/alter",
+    tag = "fula",
+    request_body = GetAllManifestsInput,
+    responses(
+        (status = 200, body = GetAllManifestsOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["fula:write"]), ("bearer" = ["fula:write"])),
+)]
 pub async fn get_all_manifests_alter(
     data: web::Data<AppState>,
-    req: web::Json<GetAllManifestsInput>,
+    req: Json<GetAllManifestsInput>,
 ) -> error::Result<HttpResponse> {
     let pool_id = transform_option_pool_id_value_reverse(req.pool_id);
     let uploader = transform_option_account_value_reverse(req.uploader.clone()).await;
@@ -677,11 +833,7 @@ pub async fn get_all_manifests_alter(
         .get_manifests(pool_id, uploader.unwrap(), storer.unwrap());
 
     let result = api
-        .tx()
-        .sign_and_submit_then_watch(&call, &signer, Default::default())
-        .await
-        .map_err(map_subxt_err)?
-        .wait_for_finalized_success()
+        .sign_and_submit(&call, &signer)
         .await
         .map_err(map_fula_err)?;
     let result = result
@@ -698,9 +850,20 @@ pub async fn get_all_manifests_alter(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/fula/manifest/available/alter",
+    tag = "fula",
+    request_body = GetAvailableManifestsInput,
+    responses(
+        (status = 200, body = GetAvailableManifestsOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["fula:write"]), ("bearer" = ["fula:write"])),
+)]
 pub async fn get_all_available_manifests_alter(
     data: web::Data<AppState>,
-    req: web::Json<GetAvailableManifestsInput>,
+    req: Json<GetAvailableManifestsInput>,
 ) -> error::Result<HttpResponse> {
     let pool_id = transform_option_pool_id_value_reverse(req.pool_id);
 
@@ -712,11 +875,7 @@ pub async fn get_all_available_manifests_alter(
     let call = sugarfunge::tx().fula().get_available_manifests(pool_id);
 
     let result = api
-        .tx()
-        .sign_and_submit_then_watch(&call, &signer, Default::default())
-        .await
-        .map_err(map_subxt_err)?
-        .wait_for_finalized_success()
+        .sign_and_submit(&call, &signer)
         .await
         .map_err(map_fula_err)?;
     let result = result
@@ -733,9 +892,20 @@ pub async fn get_all_available_manifests_alter(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/fula/manifest/storer_data/alter",
+    tag = "fula",
+    request_body = GetAllManifestsStorerDataInput,
+    responses(
+        (status = 200, body = GetAllManifestsStorerDataOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["fula:write"]), ("bearer" = ["fula:write"])),
+)]
 pub async fn get_all_manifests_storer_data_alter(
     data: web::Data<AppState>,
-    req: web::Json<GetAllManifestsStorerDataInput>,
+    req: Json<GetAllManifestsStorerDataInput>,
 ) -> error::Result<HttpResponse> {
     let pool_id = transform_option_pool_id_value_reverse(req.pool_id);
 
@@ -751,11 +921,7 @@ pub async fn get_all_manifests_storer_data_alter(
         .get_manifests_storer_data(pool_id, storer.unwrap());
 
     let result = api
-        .tx()
-        .sign_and_submit_then_watch(&call, &signer, Default::default())
-        .await
-        .map_err(map_subxt_err)?
-        .wait_for_finalized_success()
+        .sign_and_submit(&call, &signer)
         .await
         .map_err(map_fula_err)?;
     let result = result
@@ -845,11 +1011,38 @@ pub fn get_vec_cids_from_node(cids: Vec<Vec<u8>>) -> Vec<Cid> {
         .collect();
 }
 
-pub fn get_vec_manifests_from_node(manifests: Vec<Vec<u8>>) -> Vec<serde_json::Value> {
+pub fn get_vec_manifests_from_node(manifests: Vec<Vec<u8>>) -> (Vec<serde_json::Value>, Vec<bool>) {
     return manifests
         .iter()
-        .map(|manifest_data| serde_json::from_slice(manifest_data.as_slice()).unwrap_or_default())
-        .collect();
+        .map(|manifest_data| decode_manifest_metadata(manifest_data.as_slice()))
+        .unzip();
+}
+
+/// Stored manifest metadata as JSON, or hex encoded and flagged when it is not JSON
+pub fn decode_manifest_metadata(manifest_data: &[u8]) -> (serde_json::Value, bool) {
+    match serde_json::from_slice(manifest_data) {
+        Ok(value) => (value, false),
+        Err(_) => (json!(format!("0x{}", hex::encode(manifest_data))), true),
+    }
+}
+
+/// With `manifest.strict_metadata`, the metadata must be a valid `ManifestMetadata` for `cid`
+fn check_manifest_metadata(
+    data: &AppState,
+    field: &str,
+    manifest_metadata: &serde_json::Value,
+    cid: &Cid,
+) -> error::Result<()> {
+    if !data.config.manifest.strict_metadata {
+        return Ok(());
+    }
+    ManifestMetadata::validate(manifest_metadata, cid).map_err(|e| {
+        let req_error = RequestError {
+            message: json!(format!("{}: {}", field, e)),
+            description: "Invalid manifest metadata".into(),
+        };
+        error::ErrorBadRequest(serde_json::to_string_pretty(&req_error).unwrap())
+    })
 }
 
 pub fn get_vec_pool_id_from_input(pool_ids: Vec<PoolId>) -> Vec<u32> {
@@ -907,13 +1100,15 @@ pub fn transform_get_manifests(
 ) -> Vec<Manifest> {
     let mut result = Vec::new();
     for manifest in manifests {
+        let (manifest_metadata, metadata_undecodable) =
+            decode_manifest_metadata(manifest.manifest_metadata.0.as_slice());
         result.push(Manifest {
             pool_id: manifest.pool_id.into(),
             uploaders: transform_vec_uploader_data_runtime_to_vec_uploader_data(
                 manifest.users_data,
             ),
-            manifest_metadata: serde_json::from_slice(manifest.manifest_metadata.0.as_slice())
-                .unwrap_or_default(),
+            manifest_metadata,
+            metadata_undecodable,
             size: manifest.size,
         })
     }
@@ -925,10 +1120,12 @@ pub fn transform_get_available_manifests(
 ) -> Vec<ManifestAvailable> {
     let mut result = Vec::new();
     for manifest in manifests {
+        let (manifest_metadata, metadata_undecodable) =
+            decode_manifest_metadata(manifest.manifest_metadata.0.as_slice());
         result.push(ManifestAvailable {
             pool_id: manifest.pool_id.into(),
-            manifest_metadata: serde_json::from_slice(manifest.manifest_metadata.0.as_slice())
-                .unwrap_or_default(),
+            manifest_metadata,
+            metadata_undecodable,
             replication_available: manifest.replication_factor.into(),
         })
     }
@@ -0,0 +1,146 @@
+//! Challenge history of storers, indexed from finalized events.
+//!
+//! The chain only keeps the open `ChallengeRequests` and the state of the
+//! last challenge per stored cid, so the gateway indexes the `Challenge`,
+//! `VerifiedChallenges` and `ManifestStorageUpdated` events of the finalized
+//! blocks, backfilled at startup, and keeps the counts in memory.
+
+use crate::chain::Block;
+use crate::follower::BlockIndex;
+use crate::state::ClientAPI;
+use async_trait::async_trait;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use subxt::utils::AccountId32;
+use sugarfunge_api_types::sugarfunge;
+
+/// Failed cids and missed cycle samples kept per storer
+const MAX_ENTRIES: usize = 100;
+
+/// Total missed cycles of a storer when they last changed
+#[derive(Clone, Debug, PartialEq)]
+pub struct MissedCyclesSample {
+    /// Unix time in seconds of the block they changed in
+    pub timestamp: u64,
+    pub missed_cycles: u64,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct StorerHistory {
+    pub received: u64,
+    pub successful: u64,
+    pub failed: u64,
+    /// Most recently failed cids, oldest first
+    pub failed_cids: VecDeque<String>,
+    pub missed_cycles_trend: VecDeque<MissedCyclesSample>,
+    /// Last reported missed cycles by cid
+    missed_cycles: BTreeMap<String, u16>,
+}
+
+pub struct ChallengeHistory {
+    /// Timestamp of the first block indexed
+    since: Mutex<Option<u64>>,
+    storers: Mutex<HashMap<AccountId32, StorerHistory>>,
+}
+
+impl Default for ChallengeHistory {
+    fn default() -> Self {
+        ChallengeHistory::new()
+    }
+}
+
+impl ChallengeHistory {
+    pub fn new() -> ChallengeHistory {
+        ChallengeHistory {
+            since: Mutex::new(None),
+            storers: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Unix time in seconds the history starts at, the current time until a
+    /// block is indexed
+    pub fn since(&self) -> u64 {
+        self.since.lock().unwrap().unwrap_or_else(now)
+    }
+
+    pub fn storer(&self, account: &AccountId32) -> StorerHistory {
+        self.storers
+            .lock()
+            .unwrap()
+            .get(account)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Accounts with any recorded history
+    pub fn storers(&self) -> Vec<AccountId32> {
+        self.storers.lock().unwrap().keys().cloned().collect()
+    }
+
+    /// Index the events of one block
+    pub fn record(&self, block: &Block) {
+        use sugarfunge::fula::events;
+        let timestamp = block.timestamp / 1000;
+        self.since.lock().unwrap().get_or_insert(timestamp);
+        let mut storers = self.storers.lock().unwrap();
+        for event in block.events.iter().filter(|event| event.pallet == "Fula") {
+            if let Ok(Some(event)) = event.as_event::<events::Challenge>() {
+                storers.entry(event.challenged).or_default().received += 1;
+            } else if let Ok(Some(event)) = event.as_event::<events::VerifiedChallenges>() {
+                let storer = storers.entry(event.challenged).or_default();
+                storer.successful += event.successful.len() as u64;
+                storer.failed += event.failed.len() as u64;
+                for cid in event.failed {
+                    let cid = String::from_utf8(cid).unwrap_or_default();
+                    storer.failed_cids.retain(|failed| *failed != cid);
+                    push_bounded(&mut storer.failed_cids, cid);
+                }
+            } else if let Ok(Some(event)) = event.as_event::<events::ManifestStorageUpdated>() {
+                let storer = storers.entry(event.storer).or_default();
+                let cid = String::from_utf8(event.cid).unwrap_or_default();
+                if storer.missed_cycles.insert(cid, event.missed_cycles)
+                    == Some(event.missed_cycles)
+                {
+                    continue;
+                }
+                let missed_cycles = storer
+                    .missed_cycles
+                    .values()
+                    .map(|cycles| *cycles as u64)
+                    .sum();
+                push_bounded(
+                    &mut storer.missed_cycles_trend,
+                    MissedCyclesSample {
+                        timestamp,
+                        missed_cycles,
+                    },
+                );
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl BlockIndex for ChallengeHistory {
+    const NAME: &'static str = "Challenge history";
+
+    async fn index(&self, _api: &ClientAPI, block: &Block) -> Result<(), subxt::Error> {
+        self.record(block);
+        Ok(())
+    }
+}
+
+fn push_bounded<T>(entries: &mut VecDeque<T>, entry: T) {
+    if entries.len() == MAX_ENTRIES {
+        entries.pop_front();
+    }
+    entries.push_back(entry);
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
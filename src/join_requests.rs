@@ -0,0 +1,59 @@
+//! Blocks pool join requests were made in, indexed from finalized events.
+//!
+//! `PoolRequests` does not record when a request was opened, so the gateway
+//! indexes the `JoinRequested` events of the finalized blocks, backfilled at
+//! startup, and forgets the request once it is withdrawn or voted on.
+
+use crate::chain::{Block, RawEvent};
+use crate::follower::BlockIndex;
+use crate::state::ClientAPI;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use subxt::utils::AccountId32;
+use sugarfunge_api_types::sugarfunge;
+
+#[derive(Default)]
+pub struct JoinRequestIndex {
+    requested_at: Mutex<HashMap<(u32, AccountId32), u32>>,
+}
+
+impl JoinRequestIndex {
+    pub fn new() -> JoinRequestIndex {
+        JoinRequestIndex::default()
+    }
+
+    /// Block the request of `account` to join `pool_id` was seen in
+    pub fn requested_at(&self, pool_id: u32, account: &AccountId32) -> Option<u32> {
+        self.requested_at
+            .lock()
+            .unwrap()
+            .get(&(pool_id, account.clone()))
+            .copied()
+    }
+
+    /// Index the pool events of `block`
+    pub fn record(&self, block: u32, events: &[RawEvent]) {
+        use sugarfunge::pool::events;
+        let mut requested_at = self.requested_at.lock().unwrap();
+        for event in events.iter().filter(|event| event.pallet == "Pool") {
+            if let Ok(Some(event)) = event.as_event::<events::JoinRequested>() {
+                requested_at.insert((event.pool_id, event.account), block);
+            } else if let Ok(Some(event)) = event.as_event::<events::RequestWithdrawn>() {
+                requested_at.remove(&(event.pool_id, event.account));
+            } else if let Ok(Some(event)) = event.as_event::<events::VotingResult>() {
+                requested_at.remove(&(event.pool_id, event.account));
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl BlockIndex for JoinRequestIndex {
+    const NAME: &'static str = "Join request index";
+
+    async fn index(&self, _api: &ClientAPI, block: &Block) -> Result<(), subxt::Error> {
+        self.record(block.number, &block.events);
+        Ok(())
+    }
+}
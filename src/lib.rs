@@ -0,0 +1,28 @@
+pub mod account;
+pub mod activity;
+pub mod agent;
+pub mod args;
+pub mod asset;
+pub mod auth;
+pub mod bag;
+pub mod bundle;
+pub mod chain;
+pub mod challenge;
+pub mod config;
+pub mod contract;
+pub mod follower;
+pub mod fula;
+pub mod history;
+pub mod join_requests;
+pub mod market;
+pub mod openapi;
+pub mod pool;
+pub mod rate_limit;
+pub mod region;
+pub mod replication;
+pub mod routes;
+pub mod session;
+pub mod state;
+pub mod subscription;
+pub mod util;
+pub mod validator;
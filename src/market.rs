@@ -36,9 +36,20 @@ fn transform_balances(
         .collect()
 }
 
+#[utoipa::path(
+    post,
+    path = "/market/create_market",
+    tag = "market",
+    request_body = CreateMarketInput,
+    responses(
+        (status = 200, body = CreateMarketOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["asset:write"]), ("bearer" = ["asset:write"])),
+)]
 pub async fn create_market(
     data: web::Data<AppState>,
-    req: web::Json<CreateMarketInput>,
+    req: Json<CreateMarketInput>,
 ) -> error::Result<HttpResponse> {
     let pair = get_pair_from_seed(&req.seed)?;
     let signer = PairSigner::new(pair);
@@ -49,11 +60,7 @@ pub async fn create_market(
         .create_market(req.market_id.into());
 
     let result = api
-        .tx()
-        .sign_and_submit_then_watch(&call, &signer, Default::default())
-        .await
-        .map_err(map_subxt_err)?
-        .wait_for_finalized_success()
+        .sign_and_submit(&call, &signer)
         .await
         .map_err(map_sf_err)?;
     let result = result
@@ -71,9 +78,20 @@ pub async fn create_market(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/market/create_market_rate",
+    tag = "market",
+    request_body = CreateMarketRateInput,
+    responses(
+        (status = 200, body = CreateMarketRateOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["asset:write"]), ("bearer" = ["asset:write"])),
+)]
 pub async fn create_market_rate(
     data: web::Data<AppState>,
-    req: web::Json<CreateMarketRateInput>,
+    req: Json<CreateMarketRateInput>,
 ) -> error::Result<HttpResponse> {
     let pair = get_pair_from_seed(&req.seed)?;
     let signer = PairSigner::new(pair);
@@ -89,11 +107,7 @@ pub async fn create_market_rate(
     );
 
     let result = api
-        .tx()
-        .sign_and_submit_then_watch(&call, &signer, Default::default())
-        .await
-        .map_err(map_subxt_err)?
-        .wait_for_finalized_success()
+        .sign_and_submit(&call, &signer)
         .await
         .map_err(map_sf_err)?;
     let result = result
@@ -112,9 +126,20 @@ pub async fn create_market_rate(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/market/deposit_assets",
+    tag = "market",
+    request_body = DepositAssetsInput,
+    responses(
+        (status = 200, body = DepositAssetsOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["asset:write"]), ("bearer" = ["asset:write"])),
+)]
 pub async fn deposit_assets(
     data: web::Data<AppState>,
-    req: web::Json<DepositAssetsInput>,
+    req: Json<DepositAssetsInput>,
 ) -> error::Result<HttpResponse> {
     let pair = get_pair_from_seed(&req.seed)?;
     let signer = PairSigner::new(pair);
@@ -127,11 +152,7 @@ pub async fn deposit_assets(
     );
 
     let result = api
-        .tx()
-        .sign_and_submit_then_watch(&call, &signer, Default::default())
-        .await
-        .map_err(map_subxt_err)?
-        .wait_for_finalized_success()
+        .sign_and_submit(&call, &signer)
         .await
         .map_err(map_sf_err)?;
     let result = result
@@ -153,9 +174,20 @@ pub async fn deposit_assets(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/market/exchange_assets",
+    tag = "market",
+    request_body = ExchangeAssetsInput,
+    responses(
+        (status = 200, body = ExchangeAssetsOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["asset:write"]), ("bearer" = ["asset:write"])),
+)]
 pub async fn exchange_assets(
     data: web::Data<AppState>,
-    req: web::Json<ExchangeAssetsInput>,
+    req: Json<ExchangeAssetsInput>,
 ) -> error::Result<HttpResponse> {
     let pair = get_pair_from_seed(&req.seed)?;
     let signer = PairSigner::new(pair);
@@ -168,11 +200,7 @@ pub async fn exchange_assets(
     );
 
     let result = api
-        .tx()
-        .sign_and_submit_then_watch(&call, &signer, Default::default())
-        .await
-        .map_err(map_subxt_err)?
-        .wait_for_finalized_success()
+        .sign_and_submit(&call, &signer)
         .await
         .map_err(map_sf_err)?;
     let result = result
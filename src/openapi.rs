@@ -0,0 +1,456 @@
+use crate::auth::API_KEY_HEADER;
+use crate::util::RequestError;
+use crate::{
+    account, asset, bag, bundle, challenge, contract, fula, market, pool, rate_limit, replication,
+    session, subscription, util, validator,
+};
+use sugarfunge_api_types as types;
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+use utoipa_swagger_ui::SwaggerUi;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        subscription::ws,
+        util::health_check,
+        account::seeded,
+        account::exists,
+        account::create,
+        account::fund,
+        account::transfer_all,
+        account::batch_transfer,
+        account::balance,
+        account::portfolio,
+        account::activity,
+        account::sign,
+        account::verify,
+        session::nonce,
+        session::sign_in,
+        session::info,
+        asset::create_class,
+        asset::class_info,
+        asset::create,
+        asset::info,
+        asset::update_metadata,
+        asset::mint,
+        asset::burn,
+        asset::balance,
+        asset::balances,
+        asset::transfer_from,
+        bag::register,
+        bag::create,
+        bag::sweep,
+        bag::deposit,
+        bundle::register_bundle,
+        bundle::mint_bundle,
+        bundle::burn_bundle,
+        bundle::get_bundles_id,
+        bundle::get_bundles_data,
+        validator::add_validator,
+        validator::remove_validator,
+        market::create_market,
+        market::create_market_rate,
+        market::deposit_assets,
+        market::exchange_assets,
+        fula::update_manifest,
+        fula::get_all_manifests,
+        fula::get_all_manifests_alter,
+        fula::remove_manifest,
+        fula::batch_remove_manifest,
+        fula::remove_stored_manifest,
+        fula::batch_remove_stored_manifest,
+        fula::upload_manifest,
+        fula::batch_upload_manifest,
+        fula::get_available_manifests,
+        fula::get_all_available_manifests_alter,
+        fula::storage_manifest,
+        fula::batch_storage_manifest,
+        fula::get_all_manifests_storer_data,
+        fula::get_all_manifests_storer_data_alter,
+        fula::verify_manifest,
+        pool::create_pool,
+        pool::leave_pool,
+        pool::join_pool,
+        pool::cancel_join_pool,
+        pool::vote,
+        pool::get_all_pools,
+        pool::get_pool_stats,
+        pool::get_pool_tree,
+        pool::get_pool_ancestry,
+        pool::get_peer,
+        pool::get_pool_peers,
+        pool::get_all_pool_requests,
+        pool::get_pool_governance,
+        pool::get_pending_votes,
+        pool::get_all_pool_users,
+        contract::contract_mint_to,
+        contract::contract_total_supply,
+        contract::contract_allowance,
+        contract::contract_increase_allowance,
+        contract::contract_decrease_allowance,
+        contract::contract_burn_from,
+        contract::contract_transfer,
+        challenge::generate_challenge,
+        challenge::verify_challenge,
+        challenge::mint_labor_tokens,
+        challenge::verify_pending_challenge,
+        challenge::get_open_challenges,
+        challenge::respond_challenge,
+        challenge::get_storer_reliability,
+        challenge::verify_file_size,
+        challenge::provide_file_size,
+        challenge::get_challenges,
+        challenge::get_claims,
+        challenge::get_rewards,
+        replication::report,
+        contract::goerli_convert_to_fula,
+        contract::mumbai_convert_to_fula,
+        rate_limit::get_rate_limits
+    ),
+    components(
+        schemas(
+            types::primitives::Seed,
+            types::primitives::Account,
+            types::primitives::MarketId,
+            types::primitives::ClassId,
+            types::primitives::AssetId,
+            types::primitives::Balance,
+            types::primitives::Amount,
+            types::primitives::BundleId,
+            types::primitives::ValidatorId,
+            types::primitives::Cid,
+            types::primitives::Name,
+            types::primitives::PeerId,
+            types::primitives::PoolId,
+            types::primitives::ReplicationFactor,
+            types::account::CreateAccountInput,
+            types::account::CreateAccountOutput,
+            types::account::KeyScheme,
+            types::account::FundAccountInput,
+            types::account::FundAccountOutput,
+            types::account::TransferAllInput,
+            types::account::BatchTransferInput,
+            types::account::TransferItem,
+            types::account::BatchTransferOutput,
+            types::account::TransferWarning,
+            types::account::AccountBalanceInput,
+            types::account::AccountBalanceOutput,
+            types::account::AccountExistsInput,
+            types::account::AccountExistsOutput,
+            types::account::SeededAccountInput,
+            types::account::SeededAccountOutput,
+            types::activity::GetAccountActivityInput,
+            types::activity::GetAccountActivityOutput,
+            types::activity::ActivityEntry,
+            types::activity::ActivityPayload,
+            types::account::SignMessageInput,
+            types::account::SignMessageOutput,
+            types::account::VerifyMessageInput,
+            types::account::VerifyMessageOutput,
+            types::account::AccountPortfolioInput,
+            types::account::AccountPortfolioOutput,
+            types::account::NativeBalance,
+            types::account::PortfolioAsset,
+            types::account::PortfolioManifest,
+            types::asset::CreateClassInput,
+            types::asset::CreateClassOutput,
+            types::asset::ClassInfoInput,
+            types::asset::ClassInfo,
+            types::asset::ClassInfoOutput,
+            types::asset::CreateInput,
+            types::asset::CreateOutput,
+            types::asset::AssetInfoInput,
+            types::asset::AssetInfo,
+            types::asset::AssetInfoOutput,
+            types::asset::UpdateMetadataInput,
+            types::asset::UpdateMetadataOutput,
+            types::asset::MintInput,
+            types::asset::MintOutput,
+            types::asset::BurnInput,
+            types::asset::BurnOutput,
+            types::asset::AssetBalanceInput,
+            types::asset::AssetBalanceOutput,
+            types::asset::AssetBalancesInput,
+            types::asset::AssetBalancesOutput,
+            types::asset::AssetBalanceItemOutput,
+            types::asset::TransferFromInput,
+            types::asset::TransferFromOutput,
+            types::bag::RegisterInput,
+            types::bag::RegisterOutput,
+            types::bag::CreateInput,
+            types::bag::CreateOutput,
+            types::bag::SweepInput,
+            types::bag::SweepOutput,
+            types::bag::DepositInput,
+            types::bag::DepositOutput,
+            types::bundle::BundleSchema,
+            types::bundle::RegisterBundleInput,
+            types::bundle::RegisterBundleOutput,
+            types::bundle::MintBundleInput,
+            types::bundle::MintBundleOutput,
+            types::bundle::BurnBundleInput,
+            types::bundle::BurnBundleOutput,
+            types::bundle::GetBundles,
+            types::bundle::BundleItem,
+            types::bundle::GetBundlesData,
+            types::bundle::BundleDataItem,
+            types::challenge::ChallengeStateValue,
+            types::challenge::GenerateChallengeInput,
+            types::challenge::GenerateChallengeOutput,
+            types::challenge::VerifyChallengeInput,
+            types::challenge::VerifyChallengeOutput,
+            types::challenge::MintLaborTokensInput,
+            types::challenge::MintLaborTokensOutput,
+            types::challenge::VerifyPendingChallengeInput,
+            types::challenge::VerifyPendingChallengeOutput,
+            types::challenge::GetOpenChallengesInput,
+            types::challenge::GetOpenChallengesOutput,
+            types::challenge::OpenChallenge,
+            types::challenge::RespondChallengeInput,
+            types::challenge::RespondChallengeOutput,
+            types::challenge::GetStorerReliabilityInput,
+            types::challenge::GetStorerReliabilityOutput,
+            types::challenge::StorerReliability,
+            types::challenge::MissedCyclesSample,
+            types::challenge::VerifyFileSizeInput,
+            types::challenge::VerifyFileSizeOutput,
+            types::challenge::ProvideFileSizeInput,
+            types::challenge::ProvideFileSizeOutput,
+            types::challenge::GetChallengesOutput,
+            types::challenge::ChallengeData,
+            types::challenge::GetClaimDataOutput,
+            types::challenge::ClaimData,
+            types::challenge::GetRewardsInput,
+            types::challenge::GetRewardsOutput,
+            types::challenge::PoolRewards,
+            types::challenge::CidRewards,
+            types::replication::GetReplicationReportInput,
+            types::replication::GetReplicationReportOutput,
+            types::replication::PoolReplication,
+            types::replication::ManifestReplication,
+            types::replication::ReplicationAlert,
+            types::replication::ReplicationAlertKind,
+            types::contract::ContractTransactionInput,
+            types::contract::ContractTotalSupplyOutput,
+            types::contract::ContractAllowanceInput,
+            types::contract::ContractAllowanceOutput,
+            types::contract::ConvertFulaInput,
+            types::fula::ManifestMetadata,
+            types::fula::ManifestJob,
+            types::fula::ManifestEncryption,
+            types::fula::VerifyManifestsInput,
+            types::fula::VerifyManifestsOutput,
+            types::fula::UploadManifestInput,
+            types::fula::UploadManifestOutput,
+            types::fula::BatchUploadManifestInput,
+            types::fula::BatchUploadManifestOutput,
+            types::fula::UpdateManifestInput,
+            types::fula::UpdatedManifestOutput,
+            types::fula::StorageManifestInput,
+            types::fula::StorageManifestOutput,
+            types::fula::BatchStorageManifestInput,
+            types::fula::BatchStorageManifestOutput,
+            types::fula::RemoveManifestInput,
+            types::fula::RemoveManifestOutput,
+            types::fula::BatchRemoveManifestInput,
+            types::fula::BatchRemoveManifestOutput,
+            types::fula::RemoveStoringManifestInput,
+            types::fula::RemoveStoringManifestOutput,
+            types::fula::BatchRemoveStoringManifestInput,
+            types::fula::BatchRemoveStoringManifestOutput,
+            types::fula::GetAllManifestsInput,
+            types::fula::GetAllManifestsOutput,
+            types::fula::GetAllManifestsStorerDataInput,
+            types::fula::GetAllManifestsStorerDataOutput,
+            types::fula::ManifestStorageData,
+            types::fula::GetAvailableManifestsInput,
+            types::fula::GetAvailableManifestsOutput,
+            types::fula::ManifestAvailable,
+            types::fula::Manifest,
+            types::fula::UploaderData,
+            types::market::AmountOp,
+            types::market::AMM,
+            types::market::RateAction,
+            types::market::RateAccount,
+            types::market::AssetRate,
+            types::market::RateBalance,
+            types::market::Rates,
+            types::market::CreateMarketInput,
+            types::market::CreateMarketOutput,
+            types::market::CreateMarketRateInput,
+            types::market::CreateMarketRateOutput,
+            types::market::DepositAssetsInput,
+            types::market::DepositAssetsOutput,
+            types::market::ExchangeAssetsInput,
+            types::market::ExchangeAssetsOutput,
+            types::pool::CreatePoolInput,
+            types::pool::CreatePoolOutput,
+            types::pool::LeavePoolInput,
+            types::pool::LeavePoolOutput,
+            types::pool::JoinPoolInput,
+            types::pool::JoinPoolOutput,
+            types::pool::CancelJoinPoolInput,
+            types::pool::CancelJoinPoolOutput,
+            types::pool::VoteInput,
+            types::pool::VoteOutput,
+            types::pool::GetAllPoolInput,
+            types::pool::GetAllPoolsOutput,
+            types::pool::PoolData,
+            types::pool::GetPoolStatsInput,
+            types::pool::GetPoolStatsOutput,
+            types::pool::PoolStatsSort,
+            types::pool::PoolStats,
+            types::pool::GetPoolTreeInput,
+            types::pool::GetPoolTreeOutput,
+            types::pool::PoolNode,
+            types::pool::GetPoolAncestryInput,
+            types::pool::GetPoolAncestryOutput,
+            types::pool::GetAllPoolUsersInput,
+            types::pool::GetAllPoolUsersOutput,
+            types::pool::PoolUserData,
+            types::pool::GetPeerInput,
+            types::pool::GetPeerOutput,
+            types::pool::GetPoolPeersInput,
+            types::pool::GetPoolPeersOutput,
+            types::pool::PeerStatus,
+            types::pool::PeerRecord,
+            types::pool::GetAllPoolRequestInput,
+            types::pool::GetAllPoolRequestsOutput,
+            types::pool::PoolRequestData,
+            types::pool::GetPoolGovernanceInput,
+            types::pool::GetPoolGovernanceOutput,
+            types::pool::GetPendingVotesInput,
+            types::pool::GetPendingVotesOutput,
+            types::pool::PoolRequestVotes,
+            types::rate_limit::GetRateLimitsInput,
+            types::rate_limit::GetRateLimitsOutput,
+            types::rate_limit::RateLimitBucket,
+            types::session::SessionNonceInput,
+            types::session::SessionNonceOutput,
+            types::session::SignInInput,
+            types::session::SignInOutput,
+            types::session::SessionInfoOutput,
+            types::validator::AddValidatorInput,
+            types::validator::AddValidatorOutput,
+            types::validator::RemoveValidatorInput,
+            types::validator::RemoveValidatorOutput,
+            RequestError
+        )
+    ),
+    modifiers(&SecurityAddon)
+)]
+pub struct ApiDoc;
+
+/// The credentials accepted by `auth::RequireScope`, each route lists the
+/// scope it needs
+struct SecurityAddon;
+
+impl Modify for SecurityAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "api_key",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new(API_KEY_HEADER))),
+        );
+        components.add_security_scheme(
+            "bearer",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("JWT")
+                    .build(),
+            ),
+        );
+    }
+}
+
+/// Swagger UI at `/swagger-ui/` reading the specification from `/openapi.json`,
+/// both served without credentials
+pub fn swagger_ui() -> SwaggerUi {
+    SwaggerUi::new("/swagger-ui/{_:.*}").url("/openapi.json", ApiDoc::openapi())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::routes::ROUTES;
+    use serde_json::Value;
+
+    fn spec() -> Value {
+        serde_json::to_value(ApiDoc::openapi()).unwrap()
+    }
+
+    fn normalize(path: &str) -> String {
+        format!("/{}", path.trim_start_matches('/'))
+    }
+
+    #[test]
+    fn every_route_has_an_operation() {
+        let spec = spec();
+        for (path, method, scope) in ROUTES.iter() {
+            let operation = &spec["paths"][normalize(path)][method];
+            assert!(
+                operation.is_object(),
+                "route {} {} is registered without an OpenAPI path",
+                method,
+                path
+            );
+            for requirement in operation["security"].as_array().unwrap() {
+                for scopes in requirement.as_object().unwrap().values() {
+                    assert_eq!(
+                        scopes,
+                        &serde_json::json!([scope.as_str()]),
+                        "route {} documents the wrong scope",
+                        path
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn every_operation_is_registered() {
+        let spec = spec();
+        for (path, item) in spec["paths"].as_object().unwrap() {
+            for method in item.as_object().unwrap().keys() {
+                assert!(
+                    ROUTES
+                        .iter()
+                        .any(|(p, m, _)| normalize(p) == *path && *m == method.as_str()),
+                    "OpenAPI path {} {} is not registered in routes.rs",
+                    method,
+                    path
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn every_schema_reference_resolves() {
+        fn collect<'a>(value: &'a Value, refs: &mut Vec<&'a str>) {
+            match value {
+                Value::Object(map) => {
+                    if let Some(Value::String(reference)) = map.get("$ref") {
+                        refs.push(reference);
+                    }
+                    map.values().for_each(|value| collect(value, refs));
+                }
+                Value::Array(values) => values.iter().for_each(|value| collect(value, refs)),
+                _ => {}
+            }
+        }
+
+        let spec = spec();
+        let mut refs = Vec::new();
+        collect(&spec, &mut refs);
+        for reference in refs {
+            let name = reference.trim_start_matches("#/components/schemas/");
+            assert!(
+                spec["components"]["schemas"][name].is_object(),
+                "{} is referenced but has no schema",
+                reference
+            );
+        }
+    }
+}
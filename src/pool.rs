@@ -1,12 +1,18 @@
+use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
 
 use crate::account;
+use crate::chain::Storage;
+use crate::challenge;
+use crate::fula::get_vec_cids_from_node;
+use crate::replication;
 use crate::state::*;
 use crate::util::*;
 use actix_web::{error, web, HttpResponse};
 use codec::Decode;
 use codec::Encode;
 use serde_json::json;
+use sp_core::Pair;
 use subxt::ext::sp_core::sr25519::Public;
 use subxt::tx::PairSigner;
 use subxt::utils::AccountId32;
@@ -19,18 +25,42 @@ use sugarfunge_api_types::sugarfunge::runtime_types::fula_pool::User as UserRunt
 use sugarfunge_api_types::sugarfunge::runtime_types::sp_core::bounded::bounded_vec::BoundedVec;
 // use sugarfunge_api_types::sugarfunge::runtime_types::sp_runtime::bounded::bounded_vec::BoundedVec;
 
+/// Cids released per `batch_remove_stored_manifest` extrinsic when leaving
+/// a pool, so that each stays well within the block weight
+const RELEASE_BATCH_SIZE: usize = 100;
+
+#[utoipa::path(
+    post,
+    path = "/fula/pool/create",
+    tag = "fula/pool",
+    request_body = CreatePoolInput,
+    responses(
+        (status = 200, body = CreatePoolOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["pool:write"]), ("bearer" = ["pool:write"])),
+)]
 pub async fn create_pool(
     data: web::Data<AppState>,
-    req: web::Json<CreatePoolInput>,
+    req: Json<CreatePoolInput>,
 ) -> error::Result<HttpResponse> {
     let pair = get_pair_from_seed(&req.seed)?;
     let signer = PairSigner::new(pair);
 
     let pool_name = String::from(&req.pool_name).into_bytes();
 
-    let region = &req.region;
-    let region: Region = region.into();
-    let region: Vec<u8> = region.into();
+    let region = match data.regions.resolve(&req.region) {
+        Some(region) => region.into_bytes(),
+        None => {
+            return Ok(HttpResponse::BadRequest().json(RequestError {
+                message: json!("Unknown region"),
+                description: format!(
+                    "{} is not an ISO 3166 code or a configured region",
+                    req.region
+                ),
+            }))
+        }
+    };
 
     let peer_id = String::from(&req.peer_id).into_bytes();
     let peer_id = BoundedVec(peer_id);
@@ -40,11 +70,7 @@ pub async fn create_pool(
     let call = sugarfunge::tx().pool().create(pool_name, region, peer_id);
 
     let result = api
-        .tx()
-        .sign_and_submit_then_watch(&call, &signer, Default::default())
-        .await
-        .map_err(map_subxt_err)?
-        .wait_for_finalized_success()
+        .sign_and_submit(&call, &signer)
         .await
         .map_err(map_fula_pool_err)?;
     let result = result
@@ -62,25 +88,72 @@ pub async fn create_pool(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/fula/pool/leave",
+    tag = "fula/pool",
+    request_body = LeavePoolInput,
+    responses(
+        (status = 200, body = LeavePoolOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["pool:write"]), ("bearer" = ["pool:write"])),
+)]
 pub async fn leave_pool(
     data: web::Data<AppState>,
-    req: web::Json<LeavePoolInput>,
+    req: Json<LeavePoolInput>,
 ) -> error::Result<HttpResponse> {
     let pair = get_pair_from_seed(&req.seed)?;
+    let account = AccountId32::from(pair.public().0);
     let signer = PairSigner::new(pair);
 
     let api = &data.api;
 
-    let call = sugarfunge::tx().pool().leave_pool(req.pool_id.into());
+    let pool_id: u32 = req.pool_id.into();
+    let stored_cids = if req.release_storage || req.preview {
+        stored_manifest_cids(api, pool_id, &account)
+            .await
+            .map_err(map_subxt_err)?
+    } else {
+        Vec::new()
+    };
+
+    if req.preview {
+        return Ok(HttpResponse::Ok().json(LeavePoolOutput {
+            account: account.into(),
+            pool_id: req.pool_id,
+            released_cids: stored_cids.into_iter().map(Cid::from).collect(),
+            preview: true,
+        }));
+    }
+
+    let mut released_cids = Vec::new();
+    for chunk in stored_cids.chunks(RELEASE_BATCH_SIZE) {
+        let cids = chunk
+            .iter()
+            .map(|cid| BoundedVec(cid.clone().into_bytes()))
+            .collect();
+        let call = sugarfunge::tx()
+            .fula()
+            .batch_remove_stored_manifest(cids, pool_id);
+        let result = api
+            .sign_and_submit(&call, &signer)
+            .await
+            .map_err(|e| map_release_err(e, "Fula Pallet", &released_cids))?;
+        let result = result
+            .find_first::<sugarfunge::fula::events::BatchRemoveStorerOutput>()
+            .map_err(map_subxt_err)?;
+        if let Some(event) = result {
+            released_cids.extend(get_vec_cids_from_node(event.cids));
+        }
+    }
+
+    let call = sugarfunge::tx().pool().leave_pool(pool_id);
 
     let result = api
-        .tx()
-        .sign_and_submit_then_watch(&call, &signer, Default::default())
+        .sign_and_submit(&call, &signer)
         .await
-        .map_err(map_subxt_err)?
-        .wait_for_finalized_success()
-        .await
-        .map_err(map_fula_pool_err)?;
+        .map_err(|e| map_release_err(e, "Fula-Pool Pallet", &released_cids))?;
     let result = result
         .find_first::<sugarfunge::pool::events::ParticipantLeft>()
         .map_err(map_subxt_err)?;
@@ -91,6 +164,8 @@ pub async fn leave_pool(
         Some(event) => Ok(HttpResponse::Ok().json(LeavePoolOutput {
             account: event.account.into(),
             pool_id: event.pool_id.into(),
+            released_cids,
+            preview: false,
         })),
         None => Ok(HttpResponse::BadRequest().json(RequestError {
             message: json!("Failed to find sugarfunge::pool::events::ParticipantLeft"),
@@ -99,9 +174,68 @@ pub async fn leave_pool(
     }
 }
 
+/// Error of a leave that failed after the storage of `released_cids` was
+/// released, which stays released
+fn map_release_err(e: subxt::Error, pallet: &str, released_cids: &[Cid]) -> actix_web::Error {
+    let description = match released_cids.len() {
+        0 => format!("{} error", pallet),
+        _ => format!(
+            "{} error, the storage of these cids was released before: {}",
+            pallet,
+            released_cids
+                .iter()
+                .map(Cid::as_str)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    };
+    let req_error = RequestError {
+        message: json!(e.to_string().replace("\"", "")),
+        description,
+    };
+    let req_error = serde_json::to_string_pretty(&req_error).unwrap();
+    error::ErrorBadRequest(req_error)
+}
+
+/// Cids of the manifests `account` stores in `pool_id`
+async fn stored_manifest_cids(
+    api: &ClientAPI,
+    pool_id: u32,
+    account: &AccountId32,
+) -> Result<Vec<String>, subxt::Error> {
+    let mut query_key = sugarfunge::storage()
+        .fula()
+        .manifests_storer_data_root()
+        .to_root_bytes();
+    query_key.extend(blake2_128_concat(&pool_id));
+    query_key.extend(blake2_128_concat(account));
+
+    let storage = api.storage().await?;
+
+    let keys = storage.fetch_all_keys(&query_key).await?;
+
+    let mut cids = Vec::new();
+    for key in keys.iter() {
+        let cid_idx = 116;
+        cids.push(String::decode(&mut &key.0[cid_idx..])?);
+    }
+    Ok(cids)
+}
+
+#[utoipa::path(
+    post,
+    path = "/fula/pool/join",
+    tag = "fula/pool",
+    request_body = JoinPoolInput,
+    responses(
+        (status = 200, body = JoinPoolOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["pool:write"]), ("bearer" = ["pool:write"])),
+)]
 pub async fn join_pool(
     data: web::Data<AppState>,
-    req: web::Json<JoinPoolInput>,
+    req: Json<JoinPoolInput>,
 ) -> error::Result<HttpResponse> {
     let pair = get_pair_from_seed(&req.seed)?;
     let signer = PairSigner::new(pair);
@@ -114,11 +248,7 @@ pub async fn join_pool(
     let call = sugarfunge::tx().pool().join(req.pool_id.into(), peer_id);
 
     let result = api
-        .tx()
-        .sign_and_submit_then_watch(&call, &signer, Default::default())
-        .await
-        .map_err(map_subxt_err)?
-        .wait_for_finalized_success()
+        .sign_and_submit(&call, &signer)
         .await
         .map_err(map_fula_pool_err)?;
     let result = result
@@ -139,9 +269,20 @@ pub async fn join_pool(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/fula/pool/cancel_join",
+    tag = "fula/pool",
+    request_body = CancelJoinPoolInput,
+    responses(
+        (status = 200, body = CancelJoinPoolOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["pool:write"]), ("bearer" = ["pool:write"])),
+)]
 pub async fn cancel_join_pool(
     data: web::Data<AppState>,
-    req: web::Json<CancelJoinPoolInput>,
+    req: Json<CancelJoinPoolInput>,
 ) -> error::Result<HttpResponse> {
     let pair = get_pair_from_seed(&req.seed)?;
     let signer = PairSigner::new(pair);
@@ -151,11 +292,7 @@ pub async fn cancel_join_pool(
     let call = sugarfunge::tx().pool().cancel_join(req.pool_id.into());
 
     let result = api
-        .tx()
-        .sign_and_submit_then_watch(&call, &signer, Default::default())
-        .await
-        .map_err(map_subxt_err)?
-        .wait_for_finalized_success()
+        .sign_and_submit(&call, &signer)
         .await
         .map_err(map_fula_pool_err)?;
     let result = result
@@ -176,10 +313,18 @@ pub async fn cancel_join_pool(
     }
 }
 
-pub async fn vote(
-    data: web::Data<AppState>,
-    req: web::Json<VoteInput>,
-) -> error::Result<HttpResponse> {
+#[utoipa::path(
+    post,
+    path = "/fula/pool/vote",
+    tag = "fula/pool",
+    request_body = VoteInput,
+    responses(
+        (status = 200, body = VoteOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["pool:write"]), ("bearer" = ["pool:write"])),
+)]
+pub async fn vote(data: web::Data<AppState>, req: Json<VoteInput>) -> error::Result<HttpResponse> {
     let pair = get_pair_from_seed(&req.seed)?;
     let signer = PairSigner::new(pair);
 
@@ -195,11 +340,7 @@ pub async fn vote(
         .vote(req.pool_id.into(), account, req.vote_value, peer_id);
 
     let result = api
-        .tx()
-        .sign_and_submit_then_watch(&call, &signer, Default::default())
-        .await
-        .map_err(map_subxt_err)?
-        .wait_for_finalized_success()
+        .sign_and_submit(&call, &signer)
         .await
         .map_err(map_fula_pool_err)?;
     let result = result
@@ -221,77 +362,558 @@ pub async fn vote(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/fula/pool",
+    tag = "fula/pool",
+    request_body = GetAllPoolInput,
+    responses(
+        (status = 200, body = GetAllPoolsOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["read"]), ("bearer" = ["read"])),
+)]
 pub async fn get_all_pools(
     data: web::Data<AppState>,
-    req: web::Json<GetAllPoolInput>,
+    req: Json<GetAllPoolInput>,
 ) -> error::Result<HttpResponse> {
-    let api = &data.api;
-    let mut result_array = Vec::new();
+    let mut result_array = fetch_pools(&data.api).await.map_err(map_subxt_err)?;
 
-    let query_key = sugarfunge::storage().pool().pools_root().to_root_bytes();
-    // println!("query_key pool_root len: {}", query_key.len());
+    if let Some(region) = &req.region {
+        result_array.retain(|pool| data.regions.matches(region, &pool.region));
+    }
+
+    Ok(HttpResponse::Ok().json(GetAllPoolsOutput {
+        pools: result_array,
+    }))
+}
 
-    let storage = api.storage().at_latest().await.map_err(map_subxt_err)?;
+#[utoipa::path(
+    post,
+    path = "/fula/pool/stats",
+    tag = "fula/pool",
+    request_body = GetPoolStatsInput,
+    responses(
+        (status = 200, body = GetPoolStatsOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["read"]), ("bearer" = ["read"])),
+)]
+pub async fn get_pool_stats(
+    data: web::Data<AppState>,
+    req: Json<GetPoolStatsInput>,
+) -> error::Result<HttpResponse> {
+    // Every figure is read from the same block
+    let storage = data.api.storage().await.map_err(map_subxt_err)?;
+    let mut pools = fetch_pools_at(&storage).await.map_err(map_subxt_err)?;
+    if let Some(pool_id) = req.pool_id {
+        pools.retain(|pool| u32::from(pool.pool_id) == u32::from(pool_id));
+    }
 
+    let mut pending_requests: HashMap<u32, u64> = HashMap::new();
+    let query_key = sugarfunge::storage()
+        .pool()
+        .pool_requests_root()
+        .to_root_bytes();
     let keys = storage
-        .fetch_keys(&query_key, 1000, None)
+        .fetch_all_keys(&query_key)
         .await
         .map_err(map_subxt_err)?;
-
-    // println!("Obtained keys:");
     for key in keys.iter() {
-        let mut meet_requirements = true;
-        // println!("Key: len: {} 0x{}", key.0.len(), hex::encode(&key));
-
         let pool_id_idx = 48;
-        let pool_id_key = key.0.as_slice()[pool_id_idx..(pool_id_idx + 4)].to_vec();
-        let pool_id_id = u32::decode(&mut &pool_id_key[..]);
-        let pool_id = pool_id_id.unwrap();
-        // println!("pool_id: {:?}", pool_id);
+        let pool_id = u32::decode(&mut &key.0[pool_id_idx..(pool_id_idx + 4)])
+            .map_err(|e| map_subxt_err(e.into()))?;
+        *pending_requests.entry(pool_id).or_default() += 1;
+    }
 
-        if let Some(storage_data) = storage.fetch_raw(&key.0).await.map_err(map_subxt_err)? {
-            let value = PoolRuntime::decode(&mut &storage_data[..]);
-            let pool_value = value.unwrap();
+    let manifests = replication::fetch_manifests_at(&storage, req.pool_id.map(u32::from))
+        .await
+        .map_err(map_subxt_err)?;
+    let open_challenges = challenge::open_challenge_counts_at(&storage)
+        .await
+        .map_err(map_subxt_err)?;
 
-            let storage = pool_value.participants.0;
+    let mut result_array = Vec::new();
+    for pool in pools {
+        let pool_id = u32::from(pool.pool_id);
+        let mut stats = PoolStats {
+            pool_id: pool.pool_id,
+            pool_name: pool.pool_name,
+            region: pool.region,
+            participants: pool.participants.len() as u64,
+            pending_requests: pending_requests.get(&pool_id).copied().unwrap_or_default(),
+            manifests: 0,
+            total_size: 0,
+            unsized_manifests: 0,
+            under_replicated: 0,
+            open_challenges: 0,
+            average_reliability: None,
+        };
 
-            let mut storage_vec: Vec<Account> = Vec::new();
+        for (_, cid, value) in manifests
+            .iter()
+            .filter(|(manifest_pool_id, _, _)| *manifest_pool_id == pool_id)
+        {
+            stats.manifests += 1;
+            match value.size {
+                Some(size) => stats.total_size += size,
+                None => stats.unsized_manifests += 1,
+            }
+            if replication::replication(pool_id, cid.clone(), value).deficit > 0 {
+                stats.under_replicated += 1;
+            }
+        }
 
-            for storer in storage {
-                let current_account = Account::try_from(storer).unwrap();
-                storage_vec.push(current_account);
+        let mut success_rates = Vec::new();
+        for participant in pool.participants.iter() {
+            let account = AccountId32::try_from(participant).map_err(map_account_err)?;
+            stats.open_challenges += open_challenges.get(&account).copied().unwrap_or_default();
+            let history = data.challenge_history.storer(&account);
+            let verified = history.successful + history.failed;
+            if verified > 0 {
+                success_rates.push(history.successful as f64 / verified as f64);
             }
+        }
+        if !success_rates.is_empty() {
+            stats.average_reliability =
+                Some(success_rates.iter().sum::<f64>() / success_rates.len() as f64);
+        }
+        result_array.push(stats);
+    }
 
-            let pool_region = String::from_utf8(pool_value.region.0).unwrap_or_default();
+    result_array.sort_by_key(|stats| u32::from(stats.pool_id));
+    match req.sort_by {
+        Some(sort_by) => {
+            let metric = |stats: &PoolStats| match sort_by {
+                PoolStatsSort::Participants => stats.participants as f64,
+                PoolStatsSort::PendingRequests => stats.pending_requests as f64,
+                PoolStatsSort::Manifests => stats.manifests as f64,
+                PoolStatsSort::TotalSize => stats.total_size as f64,
+                PoolStatsSort::UnderReplicated => stats.under_replicated as f64,
+                PoolStatsSort::OpenChallenges => stats.open_challenges as f64,
+                // Pools without verified challenges sort before any rate
+                PoolStatsSort::Reliability => stats.average_reliability.unwrap_or(-1.0),
+            };
+            // Stable, so pools with the same value stay in pool id order
+            result_array.sort_by(|a, b| match req.descending {
+                false => metric(a).total_cmp(&metric(b)),
+                true => metric(b).total_cmp(&metric(a)),
+            });
+        }
+        None if req.descending => result_array.reverse(),
+        None => {}
+    }
 
-            if let Some(region) = &req.region {
-                if *region != pool_region {
-                    meet_requirements = false;
-                }
+    Ok(HttpResponse::Ok().json(GetPoolStatsOutput {
+        pools: result_array,
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/fula/pool/tree",
+    tag = "fula/pool",
+    request_body = GetPoolTreeInput,
+    responses(
+        (status = 200, body = GetPoolTreeOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["read"]), ("bearer" = ["read"])),
+)]
+pub async fn get_pool_tree(
+    data: web::Data<AppState>,
+    req: Json<GetPoolTreeInput>,
+) -> error::Result<HttpResponse> {
+    let pools = fetch_pools(&data.api).await.map_err(map_subxt_err)?;
+
+    let pool_ids: HashSet<u32> = pools.iter().map(|pool| pool.pool_id.into()).collect();
+    let mut root_ids: Vec<u32> = match req.pool_id {
+        Some(pool_id) => {
+            if !pool_ids.contains(&u32::from(pool_id)) {
+                return Ok(HttpResponse::BadRequest().json(RequestError {
+                    message: json!("Pool does not exist"),
+                    description: "Error in pool::get_pool_tree".to_string(),
+                }));
             }
+            vec![pool_id.into()]
+        }
+        // Pools whose parent is unknown are roots too
+        None => pools
+            .iter()
+            .filter(|pool| {
+                pool.parent
+                    .map_or(true, |parent| !pool_ids.contains(&u32::from(parent)))
+            })
+            .map(|pool| pool.pool_id.into())
+            .collect(),
+    };
 
-            if meet_requirements {
-                result_array.push(PoolData {
-                    pool_id: pool_id.into(),
-                    pool_name: String::from_utf8(pool_value.name.0)
-                        .unwrap_or_default()
-                        .into(),
-                    region: pool_region,
-                    creator: transform_option_account_value(pool_value.owner),
-                    parent: transform_option_pool_value(pool_value.parent),
-                    participants: storage_vec,
-                });
+    let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+    for pool in pools.iter() {
+        if let Some(parent) = pool.parent {
+            children
+                .entry(parent.into())
+                .or_default()
+                .push(pool.pool_id.into());
+        }
+    }
+    for pool_ids in children.values_mut() {
+        pool_ids.sort();
+    }
+    let mut pools: HashMap<u32, PoolData> = pools
+        .into_iter()
+        .map(|pool| (pool.pool_id.into(), pool))
+        .collect();
+    let cyclic_ids = cyclic_pools(&pools);
+
+    root_ids.sort();
+    let roots: Vec<PoolNode> = root_ids
+        .into_iter()
+        .filter_map(|pool_id| pool_node(pool_id, &mut pools, &children))
+        .collect();
+
+    let mut cyclic: Vec<u32> = match req.pool_id {
+        // Pools of the subtree were taken out of `pools`
+        Some(_) => cyclic_ids
+            .into_iter()
+            .filter(|pool_id| !pools.contains_key(pool_id))
+            .collect(),
+        None => cyclic_ids.into_iter().collect(),
+    };
+    cyclic.sort();
+    Ok(HttpResponse::Ok().json(GetPoolTreeOutput {
+        roots,
+        cyclic: cyclic.into_iter().map(PoolId::from).collect(),
+    }))
+}
+
+/// Pools whose chain of parents loops, either in a cycle or below one
+fn cyclic_pools(pools: &HashMap<u32, PoolData>) -> HashSet<u32> {
+    let mut cyclic = HashSet::new();
+    for pool_id in pools.keys() {
+        let mut seen = HashSet::from([*pool_id]);
+        let mut parent = pools[pool_id].parent.map(u32::from);
+        while let Some(parent_id) = parent {
+            if !seen.insert(parent_id) || cyclic.contains(&parent_id) {
+                cyclic.insert(*pool_id);
+                break;
             }
+            parent = pools
+                .get(&parent_id)
+                .and_then(|pool| pool.parent.map(u32::from));
         }
     }
-    Ok(HttpResponse::Ok().json(GetAllPoolsOutput {
-        pools: result_array,
+    cyclic
+}
+
+/// Subtree rooted at `pool_id`, taking its pools out of `pools` so that a
+/// parent cycle ends the recursion
+fn pool_node(
+    pool_id: u32,
+    pools: &mut HashMap<u32, PoolData>,
+    children: &HashMap<u32, Vec<u32>>,
+) -> Option<PoolNode> {
+    let pool = pools.remove(&pool_id)?;
+    let nodes: Vec<PoolNode> = children
+        .get(&pool_id)
+        .into_iter()
+        .flatten()
+        .filter_map(|child| pool_node(*child, pools, children))
+        .collect();
+    Some(PoolNode {
+        subtree_pools: 1 + nodes.iter().map(|node| node.subtree_pools).sum::<u64>(),
+        subtree_participants: pool.participants.len() as u64
+            + nodes
+                .iter()
+                .map(|node| node.subtree_participants)
+                .sum::<u64>(),
+        pool,
+        children: nodes,
+    })
+}
+
+#[utoipa::path(
+    post,
+    path = "/fula/pool/ancestry",
+    tag = "fula/pool",
+    request_body = GetPoolAncestryInput,
+    responses(
+        (status = 200, body = GetPoolAncestryOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["read"]), ("bearer" = ["read"])),
+)]
+pub async fn get_pool_ancestry(
+    data: web::Data<AppState>,
+    req: Json<GetPoolAncestryInput>,
+) -> error::Result<HttpResponse> {
+    let mut pools: HashMap<u32, PoolData> = fetch_pools(&data.api)
+        .await
+        .map_err(map_subxt_err)?
+        .into_iter()
+        .map(|pool| (pool.pool_id.into(), pool))
+        .collect();
+
+    let pool_id = u32::from(req.pool_id);
+    let mut parent = match pools.remove(&pool_id) {
+        Some(pool) => pool.parent,
+        None => {
+            return Ok(HttpResponse::BadRequest().json(RequestError {
+                message: json!("Pool does not exist"),
+                description: "Error in pool::get_pool_ancestry".to_string(),
+            }))
+        }
+    };
+    let mut ancestors = Vec::new();
+    let mut visited = HashSet::from([pool_id]);
+    let mut cyclic = false;
+    while let Some(parent_id) = parent.map(u32::from) {
+        if !visited.insert(parent_id) {
+            cyclic = true;
+            break;
+        }
+        match pools.remove(&parent_id) {
+            Some(pool) => {
+                parent = pool.parent;
+                ancestors.push(pool);
+            }
+            None => break,
+        }
+    }
+
+    Ok(HttpResponse::Ok().json(GetPoolAncestryOutput {
+        pool_id: req.pool_id,
+        ancestors,
+        cyclic,
+    }))
+}
+
+/// Every pool on chain
+async fn fetch_pools(api: &ClientAPI) -> Result<Vec<PoolData>, subxt::Error> {
+    fetch_pools_at(&api.storage().await?).await
+}
+
+/// [`fetch_pools`] read from a pinned block
+async fn fetch_pools_at(storage: &Storage<'_>) -> Result<Vec<PoolData>, subxt::Error> {
+    let query_key = sugarfunge::storage().pool().pools_root().to_root_bytes();
+
+    let keys = storage.fetch_all_keys(&query_key).await?;
+
+    let mut result_array = Vec::new();
+    for key in keys.iter() {
+        let pool_id_idx = 48;
+        let pool_id = u32::decode(&mut &key.0[pool_id_idx..(pool_id_idx + 4)])?;
+
+        if let Some(storage_data) = storage.fetch_raw(&key.0).await? {
+            let pool_value = PoolRuntime::decode(&mut &storage_data[..])?;
+
+            result_array.push(PoolData {
+                pool_id: pool_id.into(),
+                pool_name: String::from_utf8(pool_value.name.0)
+                    .unwrap_or_default()
+                    .into(),
+                region: String::from_utf8(pool_value.region.0).unwrap_or_default(),
+                creator: transform_option_account_value(pool_value.owner),
+                parent: transform_option_pool_value(pool_value.parent),
+                participants: pool_value
+                    .participants
+                    .0
+                    .into_iter()
+                    .map(Account::from)
+                    .collect(),
+            });
+        }
+    }
+    Ok(result_array)
+}
+
+#[utoipa::path(
+    post,
+    path = "/fula/pool/peer",
+    tag = "fula/pool",
+    request_body = GetPeerInput,
+    responses(
+        (status = 200, body = GetPeerOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["read"]), ("bearer" = ["read"])),
+)]
+pub async fn get_peer(
+    data: web::Data<AppState>,
+    req: Json<GetPeerInput>,
+) -> error::Result<HttpResponse> {
+    let accounts: Vec<PeerRecord> = peer_directory(&data.api)
+        .await
+        .map_err(map_subxt_err)?
+        .into_iter()
+        .filter(|record| record.peer_id.as_str() == req.peer_id.as_str())
+        .collect();
+    Ok(HttpResponse::Ok().json(GetPeerOutput {
+        peer_id: req.peer_id.clone(),
+        duplicate: accounts.iter().any(|record| record.duplicate),
+        accounts,
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/fula/pool/peers",
+    tag = "fula/pool",
+    request_body = GetPoolPeersInput,
+    responses(
+        (status = 200, body = GetPoolPeersOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["read"]), ("bearer" = ["read"])),
+)]
+pub async fn get_pool_peers(
+    data: web::Data<AppState>,
+    req: Json<GetPoolPeersInput>,
+) -> error::Result<HttpResponse> {
+    let pool_id = u32::from(req.pool_id);
+    let pool = data
+        .api
+        .storage()
+        .await
+        .map_err(map_subxt_err)?
+        .fetch(&sugarfunge::storage().pool().pools(pool_id))
+        .await
+        .map_err(map_subxt_err)?;
+    if pool.is_none() {
+        return Ok(HttpResponse::BadRequest().json(RequestError {
+            message: json!("Pool does not exist"),
+            description: "Error in pool::get_pool_peers".to_string(),
+        }));
+    }
+
+    let peers: Vec<PeerRecord> = peer_directory(&data.api)
+        .await
+        .map_err(map_subxt_err)?
+        .into_iter()
+        .filter(|record| {
+            record.pool_id.map(u32::from) == Some(pool_id)
+                && (record.status == PeerStatus::Participant
+                    || (req.include_requests && record.status == PeerStatus::Requested))
+        })
+        .collect();
+    let mut duplicates: Vec<PeerId> = Vec::new();
+    for record in peers.iter().filter(|record| record.duplicate) {
+        if !duplicates
+            .iter()
+            .any(|peer_id| peer_id.as_str() == record.peer_id.as_str())
+        {
+            duplicates.push(record.peer_id.clone());
+        }
+    }
+
+    Ok(HttpResponse::Ok().json(GetPoolPeersOutput {
+        pool_id: req.pool_id,
+        peers,
+        duplicates,
     }))
 }
 
+/// Peer id of every account in `Users` with the votes on its open join
+/// request, sorted by peer id. Requests of accounts without a user entry
+/// are listed from `PoolRequests`.
+async fn peer_directory(api: &ClientAPI) -> Result<Vec<PeerRecord>, subxt::Error> {
+    let storage = api.storage().await?;
+
+    let query_key = sugarfunge::storage()
+        .pool()
+        .pool_requests_root()
+        .to_root_bytes();
+    let mut requests = HashMap::new();
+    for key in storage.fetch_all_keys(&query_key).await?.iter() {
+        let pool_id_idx = 48;
+        let pool_id = u32::decode(&mut &key.0[pool_id_idx..(pool_id_idx + 4)])?;
+
+        let account_idx = 68;
+        let account_id = AccountId32::decode(&mut &key.0[account_idx..(account_idx + 32)])?;
+
+        if let Some(storage_data) = storage.fetch_raw(&key.0).await? {
+            let request = PoolRequestRuntime::decode(&mut &storage_data[..])?;
+            requests.insert((pool_id, account_id), request);
+        }
+    }
+
+    let query_key = sugarfunge::storage().pool().users_root().to_root_bytes();
+    let mut records = Vec::new();
+    for key in storage.fetch_all_keys(&query_key).await?.iter() {
+        let account_idx = 48;
+        let account_id = AccountId32::decode(&mut &key.0[account_idx..(account_idx + 32)])?;
+
+        let user = match storage.fetch_raw(&key.0).await? {
+            Some(storage_data) => UserRuntime::<BoundedVec<u8>>::decode(&mut &storage_data[..])?,
+            None => continue,
+        };
+        let (status, pool_id) = match (user.pool_id, user.request_pool_id) {
+            (Some(pool_id), _) => (PeerStatus::Participant, Some(pool_id)),
+            (None, Some(pool_id)) => (PeerStatus::Requested, Some(pool_id)),
+            (None, None) => (PeerStatus::Unaffiliated, None),
+        };
+        let request = match (status, pool_id) {
+            (PeerStatus::Requested, Some(pool_id)) => {
+                requests.remove(&(pool_id, account_id.clone()))
+            }
+            _ => None,
+        };
+        records.push(PeerRecord {
+            peer_id: String::from_utf8(user.peer_id.0).unwrap_or_default().into(),
+            account: account_id.into(),
+            status,
+            pool_id: pool_id.map(PoolId::from),
+            positive_votes: request.as_ref().map(|request| request.positive_votes),
+            voted: request
+                .map(|request| request.voted.0.into_iter().map(Account::from).collect())
+                .unwrap_or_default(),
+            duplicate: false,
+        });
+    }
+    for ((pool_id, account_id), request) in requests {
+        records.push(PeerRecord {
+            peer_id: String::from_utf8(request.peer_id.0)
+                .unwrap_or_default()
+                .into(),
+            account: account_id.into(),
+            status: PeerStatus::Requested,
+            pool_id: Some(pool_id.into()),
+            positive_votes: Some(request.positive_votes),
+            voted: request.voted.0.into_iter().map(Account::from).collect(),
+            duplicate: false,
+        });
+    }
+
+    let mut claimed_by: HashMap<String, HashSet<String>> = HashMap::new();
+    for record in records.iter() {
+        claimed_by
+            .entry(record.peer_id.as_str().to_string())
+            .or_default()
+            .insert(record.account.as_str().to_string());
+    }
+    for record in records.iter_mut() {
+        record.duplicate = claimed_by[record.peer_id.as_str()].len() > 1;
+    }
+    records.sort_by(|a, b| {
+        (a.peer_id.as_str(), a.account.as_str()).cmp(&(b.peer_id.as_str(), b.account.as_str()))
+    });
+    Ok(records)
+}
+
+#[utoipa::path(
+    post,
+    path = "/fula/pool/poolrequests",
+    tag = "fula/pool",
+    request_body = GetAllPoolRequestInput,
+    responses(
+        (status = 200, body = GetAllPoolRequestsOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["read"]), ("bearer" = ["read"])),
+)]
 pub async fn get_all_pool_requests(
     data: web::Data<AppState>,
-    req: web::Json<GetAllPoolRequestInput>,
+    req: Json<GetAllPoolRequestInput>,
 ) -> error::Result<HttpResponse> {
     let api = &data.api;
     let mut result_array = Vec::new();
@@ -308,7 +930,7 @@ pub async fn get_all_pool_requests(
         // println!("query_key pool_id len: {}", query_key.len());
     }
 
-    let storage = api.storage().at_latest().await.map_err(map_subxt_err)?;
+    let storage = api.storage().await.map_err(map_subxt_err)?;
 
     let keys = storage
         .fetch_keys(&query_key, 1000, None)
@@ -373,9 +995,141 @@ pub async fn get_all_pool_requests(
     }))
 }
 
+#[utoipa::path(
+    post,
+    path = "/fula/pool/governance",
+    tag = "fula/pool",
+    request_body = GetPoolGovernanceInput,
+    responses(
+        (status = 200, body = GetPoolGovernanceOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["read"]), ("bearer" = ["read"])),
+)]
+pub async fn get_pool_governance(
+    data: web::Data<AppState>,
+    req: Json<GetPoolGovernanceInput>,
+) -> error::Result<HttpResponse> {
+    let requests = pool_request_votes(&data, req.pool_id.map(u32::from))
+        .await
+        .map_err(map_subxt_err)?;
+    Ok(HttpResponse::Ok().json(GetPoolGovernanceOutput { requests }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/fula/pool/pending_votes",
+    tag = "fula/pool",
+    request_body = GetPendingVotesInput,
+    responses(
+        (status = 200, body = GetPendingVotesOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["read"]), ("bearer" = ["read"])),
+)]
+pub async fn get_pending_votes(
+    data: web::Data<AppState>,
+    req: Json<GetPendingVotesInput>,
+) -> error::Result<HttpResponse> {
+    let account = AccountId32::try_from(&req.account).map_err(map_account_err)?;
+    let voter = Account::from(account.clone());
+    let requests = pool_request_votes(&data, None)
+        .await
+        .map_err(map_subxt_err)?
+        .into_iter()
+        .filter(|request| {
+            request
+                .not_voted
+                .iter()
+                .any(|not_voted| not_voted.as_str() == voter.as_str())
+        })
+        .collect();
+    Ok(HttpResponse::Ok().json(GetPendingVotesOutput {
+        account: account.into(),
+        requests,
+    }))
+}
+
+/// Votes on the open join requests, of every pool or of `pool_id`
+async fn pool_request_votes(
+    data: &AppState,
+    pool_id: Option<u32>,
+) -> Result<Vec<PoolRequestVotes>, subxt::Error> {
+    let mut query_key = sugarfunge::storage()
+        .pool()
+        .pool_requests_root()
+        .to_root_bytes();
+    if let Some(pool_id) = pool_id {
+        query_key.extend(blake2_128_concat(&pool_id));
+    }
+
+    let storage = data.api.storage().await?;
+
+    let keys = storage.fetch_all_keys(&query_key).await?;
+
+    let mut participants: HashMap<u32, Vec<AccountId32>> = HashMap::new();
+    let mut result_array = Vec::new();
+    for key in keys.iter() {
+        let pool_id_idx = 48;
+        let pool_id = u32::decode(&mut &key.0[pool_id_idx..(pool_id_idx + 4)])?;
+
+        let account_idx = 68;
+        let account_id = AccountId32::decode(&mut &key.0[account_idx..(account_idx + 32)])?;
+
+        let request = match storage.fetch_raw(&key.0).await? {
+            Some(storage_data) => PoolRequestRuntime::decode(&mut &storage_data[..])?,
+            None => continue,
+        };
+
+        if !participants.contains_key(&pool_id) {
+            let pool = storage
+                .fetch(&sugarfunge::storage().pool().pools(pool_id))
+                .await?;
+            let pool_participants = pool.map(|pool| pool.participants.0).unwrap_or_default();
+            participants.insert(pool_id, pool_participants);
+        }
+        let eligible_voters = &participants[&pool_id];
+
+        let not_voted = eligible_voters
+            .iter()
+            .filter(|voter| !request.voted.0.contains(voter))
+            .cloned()
+            .map(Account::from)
+            .collect();
+        let approvals_needed = (eligible_voters.len() / 2 + 1) as u16;
+        result_array.push(PoolRequestVotes {
+            pool_id: pool_id.into(),
+            account: account_id.clone().into(),
+            peer_id: String::from_utf8(request.peer_id.0)
+                .unwrap_or_default()
+                .into(),
+            eligible_voters: eligible_voters.iter().cloned().map(Account::from).collect(),
+            not_voted,
+            positive_votes: request.positive_votes,
+            negative_votes: (request.voted.0.len() as u16).saturating_sub(request.positive_votes),
+            approvals_needed,
+            remaining_approvals: approvals_needed.saturating_sub(request.positive_votes),
+            requested_at: data.join_requests.requested_at(pool_id, &account_id),
+            voted: request.voted.0.into_iter().map(Account::from).collect(),
+        });
+    }
+    Ok(result_array)
+}
+
+#[utoipa::path(
+    post,
+    path = "/fula/pool/users",
+    tag = "fula/pool",
+    request_body = GetAllPoolUsersInput,
+    responses(
+        (status = 200, body = GetAllPoolUsersOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["read"]), ("bearer" = ["read"])),
+)]
 pub async fn get_all_pool_users(
     data: web::Data<AppState>,
-    req: web::Json<GetAllPoolUsersInput>,
+    req: Json<GetAllPoolUsersInput>,
 ) -> error::Result<HttpResponse> {
     let api = &data.api;
     let mut result_array = Vec::new();
@@ -389,7 +1143,7 @@ pub async fn get_all_pool_users(
     //     // println!("query_key class_id len: {}", query_key.len());
     // }
 
-    let storage = api.storage().at_latest().await.map_err(map_subxt_err)?;
+    let storage = api.storage().await.map_err(map_subxt_err)?;
 
     let keys = storage
         .fetch_keys(&query_key, 1000, None)
@@ -0,0 +1,330 @@
+use crate::auth::{Identity, Scope};
+use crate::config::RateLimitConfig;
+use crate::state::AppState;
+use crate::util::*;
+use actix_web::body::EitherBody;
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::{error, http, web, Error, HttpMessage, HttpRequest, HttpResponse};
+use futures::future::{ready, LocalBoxFuture, Ready};
+use futures::StreamExt;
+use serde_json::json;
+use sp_core::Pair;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use sugarfunge_api_types::rate_limit::*;
+
+/// Largest body buffered to find the signer of a request, the limit the
+/// handlers read bodies with by default
+const MAX_SIGN_BODY: usize = 262_144;
+
+/// How often buckets that refilled completely are dropped. Buckets refill in
+/// a minute, so idle callers are forgotten within two.
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Routes that sign extrinsics cost fees (and refunds), so they get their own budget
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RouteClass {
+    Read,
+    Sign,
+}
+
+impl RouteClass {
+    pub fn from_scope(scope: Scope) -> RouteClass {
+        match scope {
+            Scope::Public | Scope::Read => RouteClass::Read,
+            _ => RouteClass::Sign,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            RouteClass::Read => "read",
+            RouteClass::Sign => "sign",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum BucketKey {
+    ApiKey(String),
+    Ip(String),
+    Account(String),
+}
+
+impl BucketKey {
+    fn parts(&self) -> (&'static str, &str) {
+        match self {
+            BucketKey::ApiKey(key) => ("api_key", key),
+            BucketKey::Ip(key) => ("ip", key),
+            BucketKey::Account(key) => ("account", key),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+/// Token buckets keyed by caller and route class, refilled continuously at
+/// the configured per-minute rate
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    buckets: Mutex<HashMap<(BucketKey, RouteClass), TokenBucket>>,
+    pruned_at: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config,
+            buckets: Mutex::new(HashMap::new()),
+            pruned_at: Mutex::new(Instant::now()),
+        }
+    }
+
+    fn capacity(&self, class: RouteClass) -> f64 {
+        match class {
+            RouteClass::Read => self.config.read_per_minute as f64,
+            RouteClass::Sign => self.config.sign_per_minute as f64,
+        }
+    }
+
+    fn refill(&self, bucket: &mut TokenBucket, class: RouteClass, now: Instant) {
+        let capacity = self.capacity(class);
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * capacity / 60.0).min(capacity);
+        bucket.last_refill = now;
+    }
+
+    /// Take one token from every bucket, or none of them if any is empty.
+    /// On rejection returns how long until all buckets have a token again.
+    pub fn acquire(&self, keys: &[BucketKey], class: RouteClass) -> Result<(), Duration> {
+        let now = Instant::now();
+        let capacity = self.capacity(class);
+        let mut buckets = self.buckets.lock().unwrap();
+        {
+            let mut pruned_at = self.pruned_at.lock().unwrap();
+            if now.duration_since(*pruned_at) >= PRUNE_INTERVAL {
+                self.retain_partial(&mut buckets, now);
+                *pruned_at = now;
+            }
+        }
+
+        let mut wait: f64 = 0.0;
+        for key in keys.iter() {
+            let bucket = buckets
+                .entry((key.clone(), class))
+                .or_insert_with(|| TokenBucket {
+                    tokens: capacity,
+                    last_refill: now,
+                });
+            self.refill(bucket, class, now);
+            if bucket.tokens < 1.0 {
+                wait = wait.max((1.0 - bucket.tokens) * 60.0 / capacity);
+            }
+        }
+        if wait > 0.0 {
+            return Err(Duration::from_secs_f64(wait));
+        }
+
+        for key in keys.iter() {
+            if let Some(bucket) = buckets.get_mut(&(key.clone(), class)) {
+                bucket.tokens -= 1.0;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn snapshot(&self, key_filter: Option<&str>) -> Vec<RateLimitBucket> {
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap();
+        let mut result = Vec::new();
+        for ((key, class), bucket) in buckets.iter_mut() {
+            let (key_type, key_value) = key.parts();
+            if let Some(filter) = key_filter {
+                if filter != key_value {
+                    continue;
+                }
+            }
+            self.refill(bucket, *class, now);
+            result.push(RateLimitBucket {
+                key_type: key_type.into(),
+                key: key_value.into(),
+                route_class: class.as_str().into(),
+                available: bucket.tokens.floor() as u32,
+                capacity: self.capacity(*class) as u32,
+            });
+        }
+        result
+    }
+
+    /// Drop buckets that have refilled completely, they carry no state
+    pub fn prune(&self) {
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap();
+        self.retain_partial(&mut buckets, now);
+        *self.pruned_at.lock().unwrap() = now;
+    }
+
+    fn retain_partial(
+        &self,
+        buckets: &mut HashMap<(BucketKey, RouteClass), TokenBucket>,
+        now: Instant,
+    ) {
+        buckets.retain(|(_, class), bucket| {
+            let capacity = self.capacity(*class);
+            let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+            bucket.tokens + elapsed * capacity / 60.0 < capacity
+        });
+    }
+}
+
+/// Current bucket state, optionally filtered by key
+#[utoipa::path(
+    post,
+    path = "/admin/rate_limits",
+    tag = "admin",
+    request_body = GetRateLimitsInput,
+    responses(
+        (status = 200, body = GetRateLimitsOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["admin"]), ("bearer" = ["admin"])),
+)]
+pub async fn get_rate_limits(
+    data: web::Data<AppState>,
+    req: Json<GetRateLimitsInput>,
+) -> error::Result<HttpResponse> {
+    data.rate_limiter.prune();
+    Ok(HttpResponse::Ok().json(GetRateLimitsOutput {
+        buckets: data.rate_limiter.snapshot(req.key.as_deref()),
+    }))
+}
+
+/// Middleware charging the caller's API key, IP and (on signing routes) the
+/// signer account derived from the request `seed`
+pub struct RateLimit(pub RouteClass);
+
+impl<S, B> Transform<S, ServiceRequest> for RateLimit
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = RateLimitMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RateLimitMiddleware {
+            service: Rc::new(service),
+            class: self.0,
+        }))
+    }
+}
+
+pub struct RateLimitMiddleware<S> {
+    service: Rc<S>,
+    class: RouteClass,
+}
+
+impl<S, B> Service<ServiceRequest> for RateLimitMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, mut req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+        let class = self.class;
+
+        Box::pin(async move {
+            let data = match req.app_data::<web::Data<AppState>>() {
+                Some(data) => data.clone(),
+                None => {
+                    return service
+                        .call(req)
+                        .await
+                        .map(ServiceResponse::map_into_left_body)
+                }
+            };
+
+            let mut keys = Vec::new();
+            if let Some(identity) = req.extensions().get::<Identity>() {
+                keys.push(BucketKey::ApiKey(identity.subject.clone()));
+            }
+            if let Some(ip) = client_ip(req.request(), data.config.rate_limit.trust_forwarded_for) {
+                keys.push(BucketKey::Ip(ip));
+            }
+
+            if class == RouteClass::Sign {
+                let mut payload = req.take_payload();
+                let mut body = web::BytesMut::new();
+                while let Some(chunk) = payload.next().await {
+                    let chunk = chunk?;
+                    if body.len() + chunk.len() > MAX_SIGN_BODY {
+                        let response = HttpResponse::PayloadTooLarge().json(RequestError {
+                            message: json!("Request body too large"),
+                            description: format!("Bodies are limited to {} bytes", MAX_SIGN_BODY),
+                        });
+                        let (req, _) = req.into_parts();
+                        return Ok(ServiceResponse::new(req, response).map_into_right_body());
+                    }
+                    body.extend_from_slice(&chunk);
+                }
+                let body = body.freeze();
+                if let Some(account) = signer_account(&body) {
+                    keys.push(BucketKey::Account(account));
+                }
+                let (_, mut payload) = actix_http::h1::Payload::create(true);
+                payload.unread_data(body);
+                req.set_payload(payload.into());
+            }
+
+            if let Err(wait) = data.rate_limiter.acquire(&keys, class) {
+                let retry_after = wait.as_secs_f64().ceil() as u64;
+                let response = HttpResponse::TooManyRequests()
+                    .insert_header((http::header::RETRY_AFTER, retry_after.to_string()))
+                    .json(RequestError {
+                        message: json!("Rate limit exceeded"),
+                        description: format!("Retry after {} seconds", retry_after),
+                    });
+                let (req, _) = req.into_parts();
+                return Ok(ServiceResponse::new(req, response).map_into_right_body());
+            }
+
+            service
+                .call(req)
+                .await
+                .map(ServiceResponse::map_into_left_body)
+        })
+    }
+}
+
+/// IP of the caller, taken from `X-Forwarded-For` and `Forwarded` headers
+/// only when the gateway runs behind a trusted proxy
+pub fn client_ip(req: &HttpRequest, trust_forwarded_for: bool) -> Option<String> {
+    if trust_forwarded_for {
+        req.connection_info().realip_remote_addr().map(String::from)
+    } else {
+        req.peer_addr().map(|addr| addr.ip().to_string())
+    }
+}
+
+/// Account of the `seed` field of a JSON body, if there is one
+fn signer_account(body: &[u8]) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_slice(body).ok()?;
+    let seed = value.get("seed")?.as_str()?;
+    let pair = sp_core::sr25519::Pair::from_string(seed, None).ok()?;
+    Some(subxt::utils::AccountId32::from(pair.public().0).to_string())
+}
@@ -0,0 +1,355 @@
+//! Replication health of manifests.
+//!
+//! The monitor scans the manifests once, then rescans the pools and manifests
+//! named by the fula and pool events of every finalized block, and alerts the
+//! `/ws` clients about manifests that lost storers and are now below their
+//! replication factor.
+
+use crate::chain::{Block, Storage};
+use crate::follower::BlockIndex;
+use crate::state::*;
+use crate::util::*;
+use actix_web::{error, web, HttpResponse};
+use async_trait::async_trait;
+use codec::Decode;
+use futures::channel::mpsc;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::Mutex;
+use subxt::utils::AccountId32;
+use sugarfunge_api_types::primitives::*;
+use sugarfunge_api_types::replication::*;
+use sugarfunge_api_types::sugarfunge;
+use sugarfunge_api_types::sugarfunge::runtime_types::functionland_fula::Manifest as ManifestRuntime;
+
+/// Alerts buffered per listener, listeners falling further behind are dropped
+const LISTENER_BUFFER: usize = 64;
+
+/// Pool, cid and value of every manifest, or of the manifests of `pool_id`
+pub async fn fetch_manifests(
+    api: &ClientAPI,
+    pool_id: Option<u32>,
+) -> Result<Vec<(u32, String, ManifestRuntime<AccountId32, Vec<u8>>)>, subxt::Error> {
+    fetch_manifests_at(&api.storage().await?, pool_id).await
+}
+
+/// [`fetch_manifests`] read from a pinned block
+pub async fn fetch_manifests_at(
+    storage: &Storage<'_>,
+    pool_id: Option<u32>,
+) -> Result<Vec<(u32, String, ManifestRuntime<AccountId32, Vec<u8>>)>, subxt::Error> {
+    let mut query_key = sugarfunge::storage()
+        .fula()
+        .manifests_root()
+        .to_root_bytes();
+    if let Some(pool_id) = pool_id {
+        query_key.extend(blake2_128_concat(&pool_id));
+    }
+
+    let keys = storage.fetch_all_keys(&query_key).await?;
+
+    let mut result_array = Vec::new();
+    for key in keys.iter() {
+        let pool_id_idx = 48;
+        let pool_id = u32::decode(&mut &key.0[pool_id_idx..(pool_id_idx + 4)])?;
+
+        let cid_idx = 68;
+        let cid = String::decode(&mut &key.0[cid_idx..])?;
+
+        if let Some(storage_data) = storage.fetch_raw(&key.0).await? {
+            let value = ManifestRuntime::<AccountId32, Vec<u8>>::decode(&mut &storage_data[..])?;
+            result_array.push((pool_id, cid, value));
+        }
+    }
+    Ok(result_array)
+}
+
+/// Value of the manifest of `cid` in `pool_id`, read from a pinned block
+pub async fn fetch_manifest_at(
+    storage: &Storage<'_>,
+    pool_id: u32,
+    cid: &[u8],
+) -> Result<Option<ManifestRuntime<AccountId32, Vec<u8>>>, subxt::Error> {
+    let mut key = sugarfunge::storage()
+        .fula()
+        .manifests_root()
+        .to_root_bytes();
+    key.extend(blake2_128_concat(&pool_id));
+    key.extend(blake2_128_concat(&cid.to_vec()));
+    match storage.fetch_raw(&key).await? {
+        Some(value) => Ok(Some(Decode::decode(&mut &value[..])?)),
+        None => Ok(None),
+    }
+}
+
+/// Replication of every manifest, or of the manifests of `pool_id`
+pub async fn manifest_replication(
+    api: &ClientAPI,
+    pool_id: Option<u32>,
+) -> Result<Vec<ManifestReplication>, subxt::Error> {
+    let manifests = fetch_manifests(api, pool_id).await?;
+    Ok(manifests
+        .into_iter()
+        .map(|(pool_id, cid, value)| replication(pool_id, cid, &value))
+        .collect())
+}
+
+/// Storers against the replication factor of every uploader of a manifest
+pub fn replication(
+    pool_id: u32,
+    cid: String,
+    value: &ManifestRuntime<AccountId32, Vec<u8>>,
+) -> ManifestReplication {
+    let mut replication = ManifestReplication {
+        pool_id: pool_id.into(),
+        cid: Cid::from(cid),
+        replication_factor: 0,
+        storers: 0,
+        deficit: 0,
+    };
+    for data in value.users_data.iter() {
+        let storers = data.storers.len() as u64;
+        replication.replication_factor += data.replication_factor as u64;
+        replication.storers += storers;
+        replication.deficit += (data.replication_factor as u64).saturating_sub(storers);
+    }
+    replication
+}
+
+/// Manifests whose storers the events of a block may have changed
+#[derive(Default)]
+struct Changed {
+    /// Every manifest of these pools
+    pools: BTreeSet<u32>,
+    manifests: BTreeSet<(u32, Vec<u8>)>,
+}
+
+impl Changed {
+    fn from_events(block: &Block) -> Result<Changed, codec::Error> {
+        use sugarfunge::{fula::events as fula, pool::events as pool};
+
+        let mut changed = Changed::default();
+        for event in block.events.iter() {
+            if let Some(e) = event.as_event::<fula::ManifestOutput>()? {
+                changed.pools.insert(e.pool_id);
+            } else if let Some(e) = event.as_event::<fula::BatchManifestOutput>()? {
+                changed.pools.extend(e.pool_ids);
+            } else if let Some(e) = event.as_event::<fula::StorageManifestOutput>()? {
+                changed.manifests.insert((e.pool_id, e.cid));
+            } else if let Some(e) = event.as_event::<fula::BatchStorageManifestOutput>()? {
+                let pool_id = e.pool_id;
+                changed
+                    .manifests
+                    .extend(e.cids.into_iter().map(|cid| (pool_id, cid)));
+            } else if let Some(e) = event.as_event::<fula::RemoveStorerOutput>()? {
+                changed.manifests.insert((e.pool_id, e.cid));
+            } else if let Some(e) = event.as_event::<fula::BatchRemoveStorerOutput>()? {
+                let pool_id = e.pool_id;
+                changed
+                    .manifests
+                    .extend(e.cids.into_iter().map(|cid| (pool_id, cid)));
+            } else if let Some(e) = event.as_event::<fula::ManifestRemoved>()? {
+                changed.manifests.insert((e.pool_id, e.cid));
+            } else if let Some(e) = event.as_event::<fula::BatchManifestRemoved>()? {
+                changed
+                    .manifests
+                    .extend(e.pool_ids.into_iter().zip(e.cids.into_iter()));
+            } else if let Some(e) = event.as_event::<pool::ParticipantLeft>()? {
+                changed.pools.insert(e.pool_id);
+            }
+        }
+        // Already rescanned with their pool
+        let pools = &changed.pools;
+        changed
+            .manifests
+            .retain(|(pool_id, _)| !pools.contains(pool_id));
+        Ok(changed)
+    }
+}
+
+pub struct ReplicationMonitor {
+    /// Storers of every manifest at the last scan
+    storers: Mutex<HashMap<(u32, String), u64>>,
+    listeners: Mutex<Vec<mpsc::Sender<ReplicationAlert>>>,
+}
+
+impl Default for ReplicationMonitor {
+    fn default() -> Self {
+        ReplicationMonitor::new()
+    }
+}
+
+impl ReplicationMonitor {
+    pub fn new() -> ReplicationMonitor {
+        ReplicationMonitor {
+            storers: Mutex::new(HashMap::new()),
+            listeners: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Alerts raised from now on
+    pub fn subscribe(&self) -> mpsc::Receiver<ReplicationAlert> {
+        let (tx, rx) = mpsc::channel(LISTENER_BUFFER);
+        self.listeners.lock().unwrap().push(tx);
+        rx
+    }
+
+    /// Rescan every manifest and alert about the ones that lost storers
+    pub async fn check(&self, api: &ClientAPI) -> Result<Vec<ReplicationAlert>, subxt::Error> {
+        let manifests = manifest_replication(api, None).await?;
+        Ok(self.update(manifests, |_| true))
+    }
+
+    /// Rescan the manifests named by the events of `block`
+    async fn check_block(
+        &self,
+        api: &ClientAPI,
+        block: &Block,
+    ) -> Result<Vec<ReplicationAlert>, subxt::Error> {
+        let changed = Changed::from_events(block)?;
+        if changed.pools.is_empty() && changed.manifests.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let storage = api.storage().await?;
+        let mut manifests = Vec::new();
+        for pool_id in changed.pools.iter() {
+            for (pool_id, cid, value) in fetch_manifests_at(&storage, Some(*pool_id)).await? {
+                manifests.push(replication(pool_id, cid, &value));
+            }
+        }
+        for (pool_id, cid) in changed.manifests.iter() {
+            if let Some(value) = fetch_manifest_at(&storage, *pool_id, cid).await? {
+                let cid = String::from_utf8_lossy(cid).into_owned();
+                manifests.push(replication(*pool_id, cid, &value));
+            }
+        }
+
+        let manifest_keys: BTreeSet<(u32, String)> = changed
+            .manifests
+            .iter()
+            .map(|(pool_id, cid)| (*pool_id, String::from_utf8_lossy(cid).into_owned()))
+            .collect();
+        Ok(self.update(manifests, |key| {
+            changed.pools.contains(&key.0) || manifest_keys.contains(key)
+        }))
+    }
+
+    /// Record the storers of the `scanned` manifests, forget the manifests
+    /// in the `rescanned` scope that are gone, and alert about the ones that
+    /// lost storers
+    fn update(
+        &self,
+        scanned: Vec<ManifestReplication>,
+        rescanned: impl Fn(&(u32, String)) -> bool,
+    ) -> Vec<ReplicationAlert> {
+        let mut alerts = Vec::new();
+        let mut storers = self.storers.lock().unwrap();
+        let mut current = Vec::new();
+        for manifest in scanned {
+            let key = (
+                u32::from(manifest.pool_id),
+                manifest.cid.as_str().to_string(),
+            );
+            let previous_storers = storers.get(&key).copied();
+            current.push((key, manifest.storers));
+            // New manifests start without storers, only drops are alerted
+            let previous_storers = match previous_storers {
+                Some(previous) if manifest.storers < previous && manifest.deficit > 0 => previous,
+                _ => continue,
+            };
+            let kind = if manifest.storers == 0 {
+                ReplicationAlertKind::Unstored
+            } else {
+                ReplicationAlertKind::UnderReplicated
+            };
+            alerts.push(ReplicationAlert {
+                kind,
+                manifest,
+                previous_storers,
+            });
+        }
+        storers.retain(|key, _| !rescanned(key));
+        storers.extend(current);
+        drop(storers);
+
+        if !alerts.is_empty() {
+            self.listeners.lock().unwrap().retain_mut(|listener| {
+                alerts
+                    .iter()
+                    .all(|alert| match listener.try_send(alert.clone()) {
+                        Ok(()) => true,
+                        Err(e) => {
+                            if e.is_full() {
+                                log::warn!(
+                                    "Dropping a replication alert listener that fell behind"
+                                );
+                            }
+                            false
+                        }
+                    })
+            });
+        }
+        alerts
+    }
+}
+
+#[async_trait]
+impl BlockIndex for ReplicationMonitor {
+    const NAME: &'static str = "Replication monitor";
+
+    async fn start(&self, api: &ClientAPI) -> Result<(), subxt::Error> {
+        self.check(api).await.map(|_| ())
+    }
+
+    async fn index(&self, api: &ClientAPI, block: &Block) -> Result<(), subxt::Error> {
+        self.check_block(api, block).await.map(|_| ())
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/fula/replication/report",
+    tag = "fula",
+    request_body = GetReplicationReportInput,
+    responses(
+        (status = 200, body = GetReplicationReportOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["read"]), ("bearer" = ["read"])),
+)]
+pub async fn report(
+    data: web::Data<AppState>,
+    req: Json<GetReplicationReportInput>,
+) -> error::Result<HttpResponse> {
+    let manifests = manifest_replication(&data.api, req.pool_id.map(u32::from))
+        .await
+        .map_err(map_subxt_err)?;
+
+    let mut pools: BTreeMap<u32, PoolReplication> = BTreeMap::new();
+    for manifest in manifests.iter() {
+        let pool_id = u32::from(manifest.pool_id);
+        let pool = pools.entry(pool_id).or_insert(PoolReplication {
+            pool_id: pool_id.into(),
+            manifests: 0,
+            under_replicated: 0,
+            unstored: 0,
+            deficit: 0,
+        });
+        pool.manifests += 1;
+        pool.deficit += manifest.deficit;
+        if manifest.deficit > 0 {
+            pool.under_replicated += 1;
+        }
+        if manifest.storers == 0 {
+            pool.unstored += 1;
+        }
+    }
+
+    Ok(HttpResponse::Ok().json(GetReplicationReportOutput {
+        pools: pools.into_values().collect(),
+        manifests: manifests
+            .into_iter()
+            .filter(|manifest| manifest.deficit > 0)
+            .collect(),
+    }))
+}
@@ -0,0 +1,142 @@
+use crate::auth::{scoped, Scope};
+use crate::{
+    account, asset, bag, bundle, challenge, contract, fula, market, pool, rate_limit, replication,
+    session, subscription, util, validator,
+};
+use actix_web::web;
+use sugarfunge_api_types::endpoints::*;
+
+/// Binds each endpoint of `sugarfunge_api_types::endpoints` to its handler
+/// and the scope it requires
+macro_rules! routes {
+    ($(($endpoint:ident, $scope:ident, $handler:path)),* $(,)?) => {
+        /// Path, HTTP method and required scope of every route
+        pub const ROUTES: &[(&str, &str, Scope)] = &[
+            (EVENTS_PATH, "get", Scope::Read),
+            $((<$endpoint as Endpoint>::PATH, "post", Scope::$scope)),*
+        ];
+
+        pub fn configure(cfg: &mut web::ServiceConfig) {
+            cfg.service(scoped(EVENTS_PATH, Scope::Read, web::get().to(subscription::ws)));
+            $(cfg.service(scoped(
+                <$endpoint as Endpoint>::PATH,
+                Scope::$scope,
+                web::post().to($handler),
+            ));)*
+        }
+    };
+}
+
+routes! {
+    (Health, Read, util::health_check),
+    (AccountSeeded, Read, account::seeded),
+    (AccountExists, Read, account::exists),
+    (AccountCreate, Read, account::create),
+    (AccountFund, AccountWrite, account::fund),
+    (AccountTransferAll, AccountWrite, account::transfer_all),
+    (AccountBatchTransfer, AccountWrite, account::batch_transfer),
+    (AccountBalance, Read, account::balance),
+    (AccountPortfolio, Read, account::portfolio),
+    (AccountActivity, Read, account::activity),
+    (AccountSign, Read, account::sign),
+    (AccountVerify, Read, account::verify),
+    (SessionNonce, Public, session::nonce),
+    (SessionSignIn, Public, session::sign_in),
+    (SessionInfo, Session, session::info),
+    (AssetCreateClass, AssetWrite, asset::create_class),
+    (AssetClassInfo, Read, asset::class_info),
+    (AssetCreate, AssetWrite, asset::create),
+    (AssetInfo, Read, asset::info),
+    (AssetUpdateMetadata, AssetWrite, asset::update_metadata),
+    (AssetMint, AssetWrite, asset::mint),
+    (AssetBurn, AssetWrite, asset::burn),
+    (AssetBalance, Read, asset::balance),
+    (AssetBalances, Read, asset::balances),
+    (AssetTransferFrom, AssetWrite, asset::transfer_from),
+    (BagRegister, AssetWrite, bag::register),
+    (BagCreate, AssetWrite, bag::create),
+    (BagSweep, AssetWrite, bag::sweep),
+    (BagDeposit, AssetWrite, bag::deposit),
+    (BundleRegister, AssetWrite, bundle::register_bundle),
+    (BundleMint, AssetWrite, bundle::mint_bundle),
+    (BundleBurn, AssetWrite, bundle::burn_bundle),
+    (Bundles, Read, bundle::get_bundles_id),
+    (BundlesData, Read, bundle::get_bundles_data),
+    (ValidatorAdd, ValidatorAdmin, validator::add_validator),
+    (ValidatorRemove, ValidatorAdmin, validator::remove_validator),
+    (MarketCreate, AssetWrite, market::create_market),
+    (MarketCreateRate, AssetWrite, market::create_market_rate),
+    (MarketDepositAssets, AssetWrite, market::deposit_assets),
+    (MarketExchangeAssets, AssetWrite, market::exchange_assets),
+    (ManifestUpdate, FulaWrite, fula::update_manifest),
+    (Manifests, Read, fula::get_all_manifests),
+    (ManifestsAlter, FulaWrite, fula::get_all_manifests_alter),
+    (ManifestRemove, FulaWrite, fula::remove_manifest),
+    (ManifestBatchRemove, FulaWrite, fula::batch_remove_manifest),
+    (ManifestRemoveStored, FulaWrite, fula::remove_stored_manifest),
+    (ManifestBatchRemoveStored, FulaWrite, fula::batch_remove_stored_manifest),
+    (ManifestUpload, FulaWrite, fula::upload_manifest),
+    (ManifestBatchUpload, FulaWrite, fula::batch_upload_manifest),
+    (ManifestAvailable, Read, fula::get_available_manifests),
+    (ManifestAvailableAlter, FulaWrite, fula::get_all_available_manifests_alter),
+    (ManifestStorage, FulaWrite, fula::storage_manifest),
+    (ManifestBatchStorage, FulaWrite, fula::batch_storage_manifest),
+    (ManifestStorerData, Read, fula::get_all_manifests_storer_data),
+    (ManifestStorerDataAlter, FulaWrite, fula::get_all_manifests_storer_data_alter),
+    (ManifestVerify, FulaWrite, fula::verify_manifest),
+    (PoolCreate, PoolWrite, pool::create_pool),
+    (PoolLeave, PoolWrite, pool::leave_pool),
+    (PoolJoin, PoolWrite, pool::join_pool),
+    (PoolCancelJoin, PoolWrite, pool::cancel_join_pool),
+    (PoolVote, PoolWrite, pool::vote),
+    (Pools, Read, pool::get_all_pools),
+    (PoolStats, Read, pool::get_pool_stats),
+    (PoolTree, Read, pool::get_pool_tree),
+    (PoolAncestry, Read, pool::get_pool_ancestry),
+    (PoolPeer, Read, pool::get_peer),
+    (PoolPeers, Read, pool::get_pool_peers),
+    (PoolRequests, Read, pool::get_all_pool_requests),
+    (PoolGovernance, Read, pool::get_pool_governance),
+    (PoolPendingVotes, Read, pool::get_pending_votes),
+    (PoolUsers, Read, pool::get_all_pool_users),
+    (ContractMint, ContractAdmin, contract::contract_mint_to),
+    (ContractSupply, Read, contract::contract_total_supply),
+    (ContractAllowance, Read, contract::contract_allowance),
+    (ContractIncreaseAllowance, ContractAdmin, contract::contract_increase_allowance),
+    (ContractDecreaseAllowance, ContractAdmin, contract::contract_decrease_allowance),
+    (ContractBurn, ContractAdmin, contract::contract_burn_from),
+    (ContractTransfer, ContractAdmin, contract::contract_transfer),
+    (ChallengeGenerate, FulaWrite, challenge::generate_challenge),
+    (ChallengeVerify, FulaWrite, challenge::verify_challenge),
+    (MintLaborTokens, FulaWrite, challenge::mint_labor_tokens),
+    (ChallengePending, Read, challenge::verify_pending_challenge),
+    (ChallengeOpen, Read, challenge::get_open_challenges),
+    (ChallengeRespond, FulaWrite, challenge::respond_challenge),
+    (ChallengeReliability, Read, challenge::get_storer_reliability),
+    (FileVerify, Read, challenge::verify_file_size),
+    (FileProvide, FulaWrite, challenge::provide_file_size),
+    (Challenges, Read, challenge::get_challenges),
+    (Claims, Read, challenge::get_claims),
+    (Rewards, Read, challenge::get_rewards),
+    (ReplicationReport, Read, replication::report),
+    (GoerliConvertTokens, ContractAdmin, contract::goerli_convert_to_fula),
+    (MumbaiConvertTokens, ContractAdmin, contract::mumbai_convert_to_fula),
+    (RateLimits, Admin, rate_limit::get_rate_limits),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_endpoint_is_routed() {
+        for path in PATHS.iter() {
+            assert!(
+                ROUTES.iter().any(|(route, _, _)| route == path),
+                "endpoint {} has no handler",
+                path
+            );
+        }
+        assert_eq!(ROUTES.len(), PATHS.len() + 1);
+    }
+}
@@ -0,0 +1,237 @@
+//! Sign-in with an account: the gateway hands out a nonce, the account signs
+//! it as a wallet signs a raw message, and gets a short-lived bearer token
+//! whose subject is the account.
+
+use crate::account::verify_message;
+use crate::auth::{issue_session_token, Identity};
+use crate::rate_limit::client_ip;
+use crate::state::*;
+use crate::util::*;
+use actix_web::{error, web, HttpMessage, HttpRequest, HttpResponse};
+use rand::prelude::*;
+use serde_json::json;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use subxt::utils::AccountId32;
+use sugarfunge_api_types::primitives::*;
+use sugarfunge_api_types::session::*;
+
+/// Unsigned nonces a client can hold, asking for another drops its oldest one
+const MAX_PENDING_PER_CLIENT: usize = 8;
+
+/// Unsigned nonces held by every client together
+const MAX_PENDING: usize = 10_000;
+
+#[derive(Clone, Debug)]
+struct PendingSignIn {
+    account: Account,
+    /// IP the nonce was issued to
+    client: Option<String>,
+    message: String,
+    expires_at: u64,
+}
+
+/// Nonces waiting to be signed, forgotten once used or expired
+#[derive(Default)]
+pub struct SessionNonces {
+    pending: Mutex<HashMap<String, PendingSignIn>>,
+}
+
+impl SessionNonces {
+    pub fn new() -> SessionNonces {
+        SessionNonces::default()
+    }
+
+    /// None when every client together already holds [`MAX_PENDING`] nonces
+    fn issue(
+        &self,
+        account: &Account,
+        client: Option<String>,
+        issuer: &str,
+        ttl_secs: u64,
+    ) -> Option<(String, PendingSignIn)> {
+        let now = now();
+        let nonce = hex::encode(rand::thread_rng().gen::<[u8; 16]>());
+        let pending = PendingSignIn {
+            account: account.clone(),
+            client,
+            message: format!(
+                "{} wants you to sign in with your account:\n{}\n\nNonce: {}\nExpires at: {}",
+                issuer,
+                account.as_str(),
+                nonce,
+                now + ttl_secs
+            ),
+            expires_at: now + ttl_secs,
+        };
+        let mut nonces = self.pending.lock().unwrap();
+        nonces.retain(|_, pending| pending.expires_at > now);
+        let held = nonces
+            .iter()
+            .filter(|(_, held)| held.client == pending.client)
+            .map(|(nonce, held)| (held.expires_at, nonce.clone()))
+            .collect::<Vec<_>>();
+        if held.len() >= MAX_PENDING_PER_CLIENT {
+            if let Some((_, oldest)) = held.into_iter().min() {
+                nonces.remove(&oldest);
+            }
+        } else if nonces.len() >= MAX_PENDING {
+            return None;
+        }
+        nonces.insert(nonce.clone(), pending.clone());
+        Some((nonce, pending))
+    }
+
+    /// A nonce is used once, whether the signature is valid or not
+    fn take(&self, nonce: &str) -> Option<PendingSignIn> {
+        self.pending
+            .lock()
+            .unwrap()
+            .remove(nonce)
+            .filter(|pending| pending.expires_at > now())
+    }
+}
+
+/// Nonce message for an account to sign in with
+#[utoipa::path(
+    post,
+    path = "/session/nonce",
+    tag = "session",
+    request_body = SessionNonceInput,
+    responses(
+        (status = 200, body = SessionNonceOutput),
+        (status = 400, body = RequestError),
+        (status = 429, body = RequestError),
+    ),
+    security(()),
+)]
+pub async fn nonce(
+    data: web::Data<AppState>,
+    http: HttpRequest,
+    req: Json<SessionNonceInput>,
+) -> error::Result<HttpResponse> {
+    let auth = &data.config.auth;
+    // Without a secret no token can be issued on sign-in
+    if auth.jwt_secret.is_none() {
+        return Ok(HttpResponse::BadRequest().json(RequestError {
+            message: json!("Sessions are disabled"),
+            description: "Sessions need auth.jwt_secret to sign bearer tokens".into(),
+        }));
+    }
+    let issuer = auth.jwt_issuer.as_deref().unwrap_or("sugarfunge-api");
+    let client = client_ip(&http, data.config.rate_limit.trust_forwarded_for);
+    let issued = data
+        .session_nonces
+        .issue(&req.account, client, issuer, auth.nonce_ttl_secs);
+    let (nonce, pending) = match issued {
+        Some(issued) => issued,
+        None => {
+            return Ok(HttpResponse::TooManyRequests().json(RequestError {
+                message: json!("Too many pending sign-ins"),
+                description: "Sign in with a nonce issued earlier or retry once they expire".into(),
+            }))
+        }
+    };
+    Ok(HttpResponse::Ok().json(SessionNonceOutput {
+        account: req.account.clone(),
+        nonce,
+        message: pending.message,
+        expires_at: pending.expires_at,
+    }))
+}
+
+/// Exchange a signed nonce for a bearer token of the account
+#[utoipa::path(
+    post,
+    path = "/session/sign_in",
+    tag = "session",
+    request_body = SignInInput,
+    responses(
+        (status = 200, body = SignInOutput),
+        (status = 400, body = RequestError),
+        (status = 401, body = RequestError),
+    ),
+    security(()),
+)]
+pub async fn sign_in(
+    data: web::Data<AppState>,
+    req: Json<SignInInput>,
+) -> error::Result<HttpResponse> {
+    let account = AccountId32::try_from(&req.account).map_err(map_account_err)?;
+    let pending = match data.session_nonces.take(&req.nonce) {
+        Some(pending) if pending.account.as_str() == req.account.as_str() => pending,
+        _ => {
+            return Ok(HttpResponse::Unauthorized().json(RequestError {
+                message: json!("Unknown nonce"),
+                description: format!(
+                    "The nonce was not issued to {}, was used already or expired",
+                    req.account.as_str()
+                ),
+            }))
+        }
+    };
+    if !verify_message(
+        &account,
+        &pending.message,
+        &req.signature,
+        req.scheme,
+        false,
+    )? {
+        return Ok(HttpResponse::Unauthorized().json(RequestError {
+            message: json!("Invalid signature"),
+            description: format!(
+                "The nonce message was not signed by {}",
+                req.account.as_str()
+            ),
+        }));
+    }
+
+    let auth = &data.config.auth;
+    let issued_at = now();
+    let expires_at = issued_at + auth.session_ttl_secs;
+    match issue_session_token(auth, req.account.as_str(), issued_at, expires_at) {
+        Ok(token) => Ok(HttpResponse::Ok().json(SignInOutput {
+            account: req.account.clone(),
+            token,
+            expires_at,
+        })),
+        Err(description) => Ok(HttpResponse::BadRequest().json(RequestError {
+            message: json!("Sessions are disabled"),
+            description,
+        })),
+    }
+}
+
+/// Account of the bearer token issued on sign-in
+#[utoipa::path(
+    post,
+    path = "/session/info",
+    tag = "session",
+    responses(
+        (status = 200, body = SessionInfoOutput),
+        (status = 403, body = RequestError),
+    ),
+    security(("bearer" = ["session"])),
+)]
+pub async fn info(http: HttpRequest) -> error::Result<HttpResponse> {
+    let account = http
+        .extensions()
+        .get::<Identity>()
+        .and_then(|identity| Account::from_str(&identity.subject).ok());
+    match account {
+        Some(account) => Ok(HttpResponse::Ok().json(SessionInfoOutput { account })),
+        None => Ok(HttpResponse::Forbidden().json(RequestError {
+            message: json!("Not signed in"),
+            description: "The credentials were not issued by /session/sign_in".into(),
+        })),
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
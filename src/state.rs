@@ -1,9 +1,68 @@
+use crate::activity::ActivityIndex;
+use crate::agent::ipfs::{HttpIpfs, Ipfs};
+use crate::chain::{Chain, NodeChain};
+use crate::config::{Config, ConfigError};
+use crate::history::ChallengeHistory;
+use crate::join_requests::JoinRequestIndex;
+use crate::rate_limit::RateLimiter;
+use crate::region::RegionRegistry;
+use crate::replication::ReplicationMonitor;
+use crate::session::SessionNonces;
+use crate::util::url_to_string;
 use std::sync::Arc;
 use subxt::{client::OnlineClient, PolkadotConfig};
 
-pub type ClientAPI = Arc<OnlineClient<PolkadotConfig>>;
+pub type ClientAPI = Arc<dyn Chain>;
 
 #[derive(Clone)]
 pub struct AppState {
     pub api: ClientAPI,
+    pub config: Arc<Config>,
+    pub rate_limiter: Arc<RateLimiter>,
+    /// Local content checks when answering challenges
+    pub ipfs: Arc<dyn Ipfs>,
+    pub challenge_history: Arc<ChallengeHistory>,
+    pub replication: Arc<ReplicationMonitor>,
+    pub join_requests: Arc<JoinRequestIndex>,
+    pub regions: Arc<RegionRegistry>,
+    pub session_nonces: Arc<SessionNonces>,
+    pub activity: Arc<ActivityIndex>,
+}
+
+impl AppState {
+    pub fn new(api: ClientAPI, config: Config) -> Result<AppState, ConfigError> {
+        Ok(AppState {
+            api,
+            rate_limiter: Arc::new(RateLimiter::new(config.rate_limit.clone())),
+            ipfs: Arc::new(HttpIpfs::new(config.agent.ipfs_api.clone())),
+            challenge_history: Arc::new(ChallengeHistory::new()),
+            replication: Arc::new(ReplicationMonitor::new()),
+            join_requests: Arc::new(JoinRequestIndex::new()),
+            regions: Arc::new(RegionRegistry::new(&config.regions)?),
+            session_nonces: Arc::new(SessionNonces::new()),
+            activity: Arc::new(ActivityIndex::new()),
+            config: Arc::new(config),
+        })
+    }
+
+    /// Check local content with `ipfs` instead of the configured IPFS node
+    pub fn with_ipfs(mut self, ipfs: Arc<dyn Ipfs>) -> AppState {
+        self.ipfs = ipfs;
+        self
+    }
+
+    /// Connect to the first node in the configured list that accepts the connection
+    pub async fn connect(config: Config) -> std::io::Result<AppState> {
+        let mut last_error = String::new();
+        for url in config.node.urls.iter() {
+            match OnlineClient::<PolkadotConfig>::from_url(url_to_string(url.clone())).await {
+                Ok(api) => return Ok(AppState::new(Arc::new(NodeChain::new(api)), config)?),
+                Err(e) => {
+                    log::warn!("Could not connect to node {}: {}", url, e);
+                    last_error = e.to_string();
+                }
+            }
+        }
+        Err(std::io::Error::new(std::io::ErrorKind::Other, last_error))
+    }
 }
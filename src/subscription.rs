@@ -39,39 +39,24 @@ impl SubcriptionServiceWS {
 
         let balances_task = async move {
             // Subscribe to (in this case, finalized) blocks.
-            let mut block_sub = api.blocks().subscribe_finalized().await.unwrap();
-            while let Some(block) = block_sub.next().await {
-                let block = block.unwrap();
-                // Ask for the events for this block.
-                let events = block.events().await.unwrap();
-
-                for event in events.iter() {
-                    let event = event.unwrap();
-
-                    if event
+            let mut events_sub = api.watch_events().await.unwrap();
+            while let Some(events) = events_sub.next().await {
+                // Events of the finalized block
+                for event in events.unwrap() {
+                    if let Some(event) = event
                         .as_event::<sugarfunge::balances::events::Deposit>()
                         .unwrap()
-                        .is_some()
                     {
-                        let event = serde_json::to_string_pretty(
-                            &event
-                                .as_event::<sugarfunge::balances::events::Deposit>()
-                                .unwrap(),
-                        );
+                        let event = serde_json::to_string_pretty(&event);
                         if let Ok(event) = event {
                             let event_msg = String::from("Balance Deposit: ") + &event;
                             tx.send(event_msg).unwrap();
                         }
-                    } else if event
+                    } else if let Some(event) = event
                         .as_event::<sugarfunge::balances::events::Transfer>()
                         .unwrap()
-                        .is_some()
                     {
-                        let event = serde_json::to_string_pretty(
-                            &event
-                                .as_event::<sugarfunge::balances::events::Transfer>()
-                                .unwrap(),
-                        );
+                        let event = serde_json::to_string_pretty(&event);
                         if let Ok(event) = event {
                             let event_msg = String::from("Balance Transfer: ") + &event;
                             tx.send(event_msg).unwrap();
@@ -87,37 +72,23 @@ impl SubcriptionServiceWS {
         let tx = tx_origin.clone();
 
         let asset_task = async move {
-            let mut block_sub = api.blocks().subscribe_finalized().await.unwrap();
-            while let Some(block) = block_sub.next().await {
-                let block = block.unwrap();
-                // Ask for the events for this block.
-                let events = block.events().await.unwrap();
-
-                for event in events.iter() {
-                    let event = event.unwrap();
-
-                    if event
+            let mut events_sub = api.watch_events().await.unwrap();
+            while let Some(events) = events_sub.next().await {
+                // Events of the finalized block
+                for event in events.unwrap() {
+                    if let Some(event) = event
                         .as_event::<sugarfunge::asset::events::Transferred>()
                         .unwrap()
-                        .is_some()
                     {
-                        let event = serde_json::to_string_pretty(
-                            &event
-                                .as_event::<sugarfunge::asset::events::Transferred>()
-                                .unwrap(),
-                        );
+                        let event = serde_json::to_string_pretty(&event);
                         if let Ok(event) = event {
                             let event_msg = String::from("Asset Transferred: ") + &event;
                             tx.send(event_msg).unwrap();
                         }
-                    } else if event
-                        .as_event::<sugarfunge::asset::events::Mint>()
-                        .unwrap()
-                        .is_some()
+                    } else if let Some(event) =
+                        event.as_event::<sugarfunge::asset::events::Mint>().unwrap()
                     {
-                        let event = serde_json::to_string_pretty(
-                            &event.as_event::<sugarfunge::asset::events::Mint>().unwrap(),
-                        );
+                        let event = serde_json::to_string_pretty(&event);
                         if let Ok(event) = event {
                             let event_msg = String::from("Asset Minted: ") + &event;
                             tx.send(event_msg).unwrap();
@@ -128,42 +99,42 @@ impl SubcriptionServiceWS {
         }
         .into_actor(self);
 
-        let api = self.data.api.clone();
+        let mut alerts = self.data.replication.subscribe();
 
-        let bag_task = async move {
-            let mut block_sub = api.blocks().subscribe_finalized().await.unwrap();
-            while let Some(block) = block_sub.next().await {
-                let block = block.unwrap();
-                // Ask for the events for this block.
-                let events = block.events().await.unwrap();
+        let tx = tx_origin.clone();
 
-                for event in events.iter() {
-                    let event = event.unwrap();
+        let replication_task = async move {
+            while let Some(alert) = alerts.next().await {
+                let alert = serde_json::to_string_pretty(&alert);
+                if let Ok(alert) = alert {
+                    let alert_msg = String::from("Replication Alert: ") + &alert;
+                    tx.send(alert_msg).unwrap();
+                }
+            }
+        }
+        .into_actor(self);
+
+        let api = self.data.api.clone();
 
-                    if event
+        let bag_task = async move {
+            let mut events_sub = api.watch_events().await.unwrap();
+            while let Some(events) = events_sub.next().await {
+                // Events of the finalized block
+                for event in events.unwrap() {
+                    if let Some(event) = event
                         .as_event::<sugarfunge::bag::events::Created>()
                         .unwrap()
-                        .is_some()
                     {
-                        let event = serde_json::to_string_pretty(
-                            &event
-                                .as_event::<sugarfunge::bag::events::Created>()
-                                .unwrap(),
-                        );
+                        let event = serde_json::to_string_pretty(&event);
                         if let Ok(event) = event {
                             let event_msg = String::from("Bag Created: ") + &event;
                             tx_origin.send(event_msg).unwrap();
                         }
-                    } else if event
+                    } else if let Some(event) = event
                         .as_event::<sugarfunge::bag::events::Deposit>()
                         .unwrap()
-                        .is_some()
                     {
-                        let event = serde_json::to_string_pretty(
-                            &event
-                                .as_event::<sugarfunge::bag::events::Deposit>()
-                                .unwrap(),
-                        );
+                        let event = serde_json::to_string_pretty(&event);
                         if let Ok(event) = event {
                             let event_msg = String::from("Bag Deposit: ") + &event;
                             tx_origin.send(event_msg).unwrap();
@@ -186,6 +157,10 @@ impl SubcriptionServiceWS {
 
         self.subs.insert("bag_events".into(), sub);
 
+        let sub: SpawnHandle = ctx.spawn(replication_task);
+
+        self.subs.insert("replication_alerts".into(), sub);
+
         ctx.run_interval(HEARTBEAT_INTERVAL, move |_act, ctx| {
             if let Ok(event) = rx.try_recv() {
                 ctx.text(format!("{:#?}", event));
@@ -243,6 +218,15 @@ impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for SubcriptionServic
 }
 
 /// WebSocket handshake and start `SubcriptionServiceWS` actor.
+#[utoipa::path(
+    get,
+    path = "/ws",
+    tag = "subscription",
+    responses(
+        (status = 101, description = "Switching to the websocket event stream"),
+    ),
+    security(("api_key" = ["read"]), ("bearer" = ["read"])),
+)]
 pub async fn ws(
     data: web::Data<AppState>,
     req: HttpRequest,
@@ -1,19 +1,64 @@
-use actix_web::{error, web, HttpResponse};
-use derive_more::Display;
-use serde::{Deserialize, Serialize};
+use actix_web::{dev::Payload, error, web, FromRequest, HttpRequest, HttpResponse};
+use futures::future::LocalBoxFuture;
+use serde::de::DeserializeOwned;
 use serde_json::json;
 use sp_core::Pair;
 use subxt::rpc::types::Health;
 use sugarfunge_api_types::primitives::*;
 use url::Url;
 
+use crate::agent::ipfs::IpfsError;
 use crate::state::AppState;
 
-#[derive(Serialize, Deserialize, Debug, Display)]
-#[display(fmt = "{:?} {:?}", message, description)]
-pub struct RequestError {
-    pub message: serde_json::Value,
-    pub description: String,
+pub use sugarfunge_api_types::error::RequestError;
+
+/// Drop-in for `web::Json` whose errors name the field that failed to
+/// deserialize, e.g. `cid[2]` for the third cid of a batch. Accounts, cids
+/// and the other checked primitives are validated.
+pub struct Json<T>(pub T);
+
+impl<T> std::ops::Deref for Json<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: DeserializeOwned + 'static> FromRequest for Json<T> {
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let body = web::Bytes::from_request(req, payload);
+        Box::pin(async move {
+            let body = body.await?;
+            // An empty body reads as null, which `Option` inputs accept
+            let body = if body.iter().all(u8::is_ascii_whitespace) {
+                web::Bytes::from_static(b"null")
+            } else {
+                body
+            };
+            let deserializer = &mut serde_json::Deserializer::from_slice(&body);
+            validated(|| serde_path_to_error::deserialize(deserializer))
+                .map(Json)
+                .map_err(map_json_err)
+        })
+    }
+}
+
+pub fn map_json_err(e: serde_path_to_error::Error<serde_json::Error>) -> actix_web::Error {
+    let message = if e.path().iter().next().is_none() {
+        "Invalid request body".to_string()
+    } else {
+        format!("Invalid field {}", e.path())
+    };
+    let req_error = RequestError {
+        message: json!(message),
+        description: e.inner().to_string(),
+    };
+    let req_error = serde_json::to_string_pretty(&req_error).unwrap();
+    error::ErrorBadRequest(req_error)
 }
 
 pub fn map_subxt_err(e: subxt::Error) -> actix_web::Error {
@@ -48,6 +93,15 @@ pub fn map_account_err(e: sp_core::crypto::PublicError) -> actix_web::Error {
     error::ErrorBadRequest(req_error)
 }
 
+pub fn map_secret_err(e: sp_core::crypto::SecretStringError) -> actix_web::Error {
+    let req_error = RequestError {
+        message: json!("Invalid seed"),
+        description: format!("{:?}", e),
+    };
+    let req_error = serde_json::to_string_pretty(&req_error).unwrap();
+    error::ErrorBadRequest(req_error)
+}
+
 pub fn get_pair_from_seed(seed: &Seed) -> error::Result<sp_core::sr25519::Pair> {
     sp_core::sr25519::Pair::from_string(seed.as_str(), None).map_err(|e| {
         let req_error = RequestError {
@@ -89,8 +143,35 @@ pub fn map_fula_pool_err(e: subxt::Error) -> actix_web::Error {
     error::ErrorBadRequest(req_error)
 }
 
+pub fn map_ipfs_err(e: IpfsError) -> actix_web::Error {
+    let req_error = RequestError {
+        message: json!(e.to_string()),
+        description: "IPFS node error".into(),
+    };
+    let req_error = serde_json::to_string_pretty(&req_error).unwrap();
+    error::ErrorBadGateway(req_error)
+}
+
+/// `Blake2_128Concat` hashed part of a storage map key
+pub fn blake2_128_concat(value: &impl codec::Encode) -> Vec<u8> {
+    let value = value.encode();
+    let mut part = sp_core::blake2_128(&value).to_vec();
+    part.extend(value);
+    part
+}
+
+#[utoipa::path(
+    post,
+    path = "/health",
+    tag = "health",
+    responses(
+        (status = 200, description = "Node health", body = serde_json::Value),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["read"]), ("bearer" = ["read"])),
+)]
 pub async fn health_check(data: web::Data<AppState>) -> error::Result<HttpResponse> {
     let api = &data.api;
-    let health: Health = api.rpc().system_health().await.map_err(map_subxt_err)?;
+    let health: Health = api.health().await.map_err(map_subxt_err)?;
     Ok(HttpResponse::Ok().json(health))
 }
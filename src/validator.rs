@@ -9,9 +9,20 @@ use sugarfunge_api_types::primitives::*;
 use sugarfunge_api_types::sugarfunge;
 use sugarfunge_api_types::validator::*;
 
+#[utoipa::path(
+    post,
+    path = "/validator/add_validator",
+    tag = "validator",
+    request_body = AddValidatorInput,
+    responses(
+        (status = 200, body = AddValidatorOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["validator:admin"]), ("bearer" = ["validator:admin"])),
+)]
 pub async fn add_validator(
     data: web::Data<AppState>,
-    req: web::Json<AddValidatorInput>,
+    req: Json<AddValidatorInput>,
 ) -> error::Result<HttpResponse> {
     let pair = get_pair_from_seed(&req.seed)?;
     let signer = PairSigner::new(pair);
@@ -27,11 +38,7 @@ pub async fn add_validator(
     let call_value = sugarfunge::tx().sudo().sudo(call);
 
     let result = api
-        .tx()
-        .sign_and_submit_then_watch(&call_value, &signer, Default::default())
-        .await
-        .map_err(map_subxt_err)?
-        .wait_for_finalized_success()
+        .sign_and_submit(&call_value, &signer)
         .await
         .map_err(map_sf_err)?;
 
@@ -50,9 +57,20 @@ pub async fn add_validator(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/validator/remove_validator",
+    tag = "validator",
+    request_body = RemoveValidatorInput,
+    responses(
+        (status = 200, body = RemoveValidatorOutput),
+        (status = 400, body = RequestError),
+    ),
+    security(("api_key" = ["validator:admin"]), ("bearer" = ["validator:admin"])),
+)]
 pub async fn remove_validator(
     data: web::Data<AppState>,
-    req: web::Json<RemoveValidatorInput>,
+    req: Json<RemoveValidatorInput>,
 ) -> error::Result<HttpResponse> {
     let pair = get_pair_from_seed(&req.seed)?;
     let signer = PairSigner::new(pair);
@@ -69,11 +87,7 @@ pub async fn remove_validator(
     let call_value = sugarfunge::tx().sudo().sudo(call);
 
     let result = api
-        .tx()
-        .sign_and_submit_then_watch(&call_value, &signer, Default::default())
-        .await
-        .map_err(map_subxt_err)?
-        .wait_for_finalized_success()
+        .sign_and_submit(&call_value, &signer)
         .await
         .map_err(map_sf_err)?;
 
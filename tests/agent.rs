@@ -0,0 +1,181 @@
+//! Storage agent tests against the in-memory `MockChain` and a fake IPFS node.
+
+use async_trait::async_trait;
+use sp_core::Pair;
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use subxt::tx::PairSigner;
+use subxt::utils::AccountId32;
+use sugarfunge_api::agent::ipfs::{Ipfs, IpfsError};
+use sugarfunge_api::agent::Agent;
+use sugarfunge_api::chain::mock::MockChain;
+use sugarfunge_api::config::TokenConfig;
+use sugarfunge_api::state::ClientAPI;
+use sugarfunge_api_types::sugarfunge;
+use sugarfunge_api_types::sugarfunge::runtime_types::sp_core::bounded::bounded_vec::BoundedVec;
+
+const CIDS: [&str; 3] = [
+    "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi",
+    "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG",
+    "QmT78zSuBmuS4z925WZfrqQ1qHaJ56DQaTfyMUF7F8ff5o",
+];
+
+/// IPFS node that can fetch the content listed in `network`
+#[derive(Default)]
+struct FakeIpfs {
+    network: BTreeMap<String, u64>,
+    pinned: Mutex<BTreeSet<String>>,
+}
+
+impl FakeIpfs {
+    fn with_content(cids: &[&str]) -> FakeIpfs {
+        FakeIpfs {
+            network: cids.iter().map(|cid| (cid.to_string(), 1024)).collect(),
+            pinned: Mutex::default(),
+        }
+    }
+
+    fn unpin_all(&self) {
+        self.pinned.lock().unwrap().clear();
+    }
+}
+
+#[async_trait]
+impl Ipfs for FakeIpfs {
+    async fn pin(&self, cid: &str) -> Result<(), IpfsError> {
+        if !self.network.contains_key(cid) {
+            return Err(IpfsError(format!("{} not found", cid)));
+        }
+        self.pinned.lock().unwrap().insert(cid.to_string());
+        Ok(())
+    }
+
+    async fn has(&self, cid: &str) -> Result<bool, IpfsError> {
+        Ok(self.pinned.lock().unwrap().contains(cid))
+    }
+
+    async fn size(&self, cid: &str) -> Result<u64, IpfsError> {
+        self.network
+            .get(cid)
+            .copied()
+            .ok_or_else(|| IpfsError(format!("{} not found", cid)))
+    }
+}
+
+fn pair(seed: &str) -> sp_core::sr25519::Pair {
+    sp_core::sr25519::Pair::from_string(seed, None).unwrap()
+}
+
+fn account_id(seed: &str) -> AccountId32 {
+    AccountId32::from(pair(seed).public().0)
+}
+
+async fn submit<Call: subxt::tx::TxPayload>(api: &ClientAPI, seed: &str, call: Call) {
+    api.sign_and_submit(&call, &PairSigner::new(pair(seed)))
+        .await
+        .unwrap();
+}
+
+fn peer_id() -> BoundedVec<u8> {
+    BoundedVec(b"QmYyQSo1c1Ym7orWxLYvCrM2EmxFTANf8wXmmE7DWjhx5N".to_vec())
+}
+
+/// Pool 1 owned by Alice, with Bob as a member, and one manifest per cid
+async fn chain(cids: &[&str]) -> ClientAPI {
+    let api: ClientAPI = Arc::new(MockChain::new());
+    let pool = sugarfunge::tx()
+        .pool()
+        .create(b"pool".to_vec(), b"us-east".to_vec(), peer_id());
+    submit(&api, "//Alice", pool).await;
+    submit(&api, "//Bob", sugarfunge::tx().pool().join(1, peer_id())).await;
+    let vote = sugarfunge::tx()
+        .pool()
+        .vote(1, account_id("//Bob"), true, peer_id());
+    submit(&api, "//Alice", vote).await;
+    for cid in cids {
+        upload(&api, cid).await;
+    }
+    api
+}
+
+async fn upload(api: &ClientAPI, cid: &str) {
+    let call = sugarfunge::tx().fula().upload_manifest(
+        BoundedVec(b"{}".to_vec()),
+        BoundedVec(cid.as_bytes().to_vec()),
+        1,
+        1,
+    );
+    submit(api, "//Alice", call).await;
+}
+
+fn agent(api: &ClientAPI, ipfs: Arc<FakeIpfs>, capacity: usize) -> Agent {
+    Agent::new(
+        api.clone(),
+        ipfs,
+        pair("//Bob"),
+        1,
+        capacity,
+        TokenConfig::default(),
+    )
+}
+
+#[actix_web::test]
+async fn claims_up_to_capacity_and_reports_sizes() {
+    let api = chain(&CIDS).await;
+    let ipfs = Arc::new(FakeIpfs::with_content(&CIDS[1..]));
+    let agent = agent(&api, ipfs.clone(), 1);
+
+    // The first cid cannot be fetched, so one of the others is claimed
+    let report = agent.sync().await.unwrap();
+    assert_eq!(report.claimed.len(), 1);
+    assert_ne!(report.claimed[0], CIDS[0]);
+    assert_eq!(report.sized, report.claimed);
+
+    // At capacity, and the size is known now
+    assert!(agent.sync().await.unwrap().is_empty());
+}
+
+#[actix_web::test]
+async fn answers_challenges() {
+    let api = chain(&CIDS[..1]).await;
+    let ipfs = Arc::new(FakeIpfs::with_content(&CIDS));
+    let agent = agent(&api, ipfs.clone(), 10);
+    agent.sync().await.unwrap();
+
+    submit(
+        &api,
+        "//Alice",
+        sugarfunge::tx().fula().generate_challenge(),
+    )
+    .await;
+    let report = agent.sync().await.unwrap();
+    assert_eq!(report.successful, vec![CIDS[0].to_string()]);
+
+    ipfs.unpin_all();
+    submit(
+        &api,
+        "//Alice",
+        sugarfunge::tx().fula().generate_challenge(),
+    )
+    .await;
+    let report = agent.sync().await.unwrap();
+    assert_eq!(report.failed, vec![CIDS[0].to_string()]);
+}
+
+#[actix_web::test]
+async fn run_follows_new_manifests() {
+    let api = chain(&[]).await;
+    let ipfs = Arc::new(FakeIpfs::with_content(&CIDS));
+    actix_web::rt::spawn(agent(&api, ipfs.clone(), 10).run());
+    actix_web::rt::time::sleep(Duration::from_millis(50)).await;
+
+    upload(&api, CIDS[0]).await;
+    for _ in 0..50 {
+        if ipfs.has(CIDS[0]).await.unwrap() {
+            return;
+        }
+        actix_web::rt::time::sleep(Duration::from_millis(20)).await;
+    }
+    panic!("the agent did not claim the new manifest");
+}
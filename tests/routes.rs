@@ -0,0 +1,2106 @@
+//! Route tests running the actix app against the in-memory `MockChain`.
+//! Contract routes talk to Ethereum and are not covered.
+
+use actix_web::body::MessageBody;
+use actix_web::dev::{Service, ServiceResponse};
+use actix_web::http::StatusCode;
+use actix_web::{test, web::Data, App};
+use futures::StreamExt;
+use serde_json::{json, Value};
+use sp_core::Pair;
+use std::sync::Arc;
+use subxt::tx::PairSigner;
+use subxt::utils::AccountId32;
+use sugarfunge_api::auth::{Scope, API_KEY_HEADER};
+use sugarfunge_api::chain::mock::MockChain;
+use sugarfunge_api::config::{ApiKeyConfig, Config, CustomRegionConfig};
+use sugarfunge_api::follower::{self, Backfill};
+use sugarfunge_api::routes;
+use sugarfunge_api::state::AppState;
+use sugarfunge_api_types::replication::ReplicationAlertKind;
+use sugarfunge_api_types::sugarfunge;
+use sugarfunge_api_types::sugarfunge::runtime_types::sp_core::bounded::bounded_vec::BoundedVec;
+
+const ALICE: &str = "//Alice";
+const BOB: &str = "//Bob";
+const CHARLIE: &str = "//Charlie";
+
+const CID: &str = "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi";
+const CID_2: &str = "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG";
+const PEER_ID: &str = "QmYyQSo1c1Ym7orWxLYvCrM2EmxFTANf8wXmmE7DWjhx5N";
+const PEER_ID_2: &str = "QmNnooDu7bfjPFoTZYxMNLWUQJyrVwtbZg5gBMjTezGAJN";
+
+fn account_id(seed: &str) -> AccountId32 {
+    let pair = sp_core::sr25519::Pair::from_string(seed, None).unwrap();
+    AccountId32::from(pair.public().0)
+}
+
+fn account(seed: &str) -> String {
+    account_id(seed).to_string()
+}
+
+fn config() -> Config {
+    let mut config = Config::default();
+    config.auth.enabled = false;
+    config.refund.enabled = false;
+    config.rate_limit.read_per_minute = 100_000;
+    config.rate_limit.sign_per_minute = 100_000;
+    for (code, parent) in [
+        ("us-east", Some("US")),
+        ("us-west", Some("US")),
+        ("eu-west", None),
+    ] {
+        config.regions.custom.push(CustomRegionConfig {
+            code: code.into(),
+            parent: parent.map(String::from),
+        });
+    }
+    config
+}
+
+async fn state() -> AppState {
+    state_with(config()).await
+}
+
+async fn state_with(config: Config) -> AppState {
+    let chain = MockChain::new()
+        .endow(&account_id(ALICE), 1_000_000)
+        .endow(&account_id(BOB), 1_000_000);
+    let backfill = Backfill::from(&config.index);
+    let state = AppState::new(Arc::new(chain), config).unwrap();
+    follower::follow(state.challenge_history.clone(), state.api.clone(), backfill)
+        .await
+        .unwrap();
+    follower::follow(state.join_requests.clone(), state.api.clone(), backfill)
+        .await
+        .unwrap();
+    follower::follow(state.activity.clone(), state.api.clone(), backfill)
+        .await
+        .unwrap();
+    follower::follow(state.replication.clone(), state.api.clone(), Backfill::None)
+        .await
+        .unwrap();
+    state
+}
+
+async fn call<S, B>(app: &S, path: &str, body: Value) -> (StatusCode, Value)
+where
+    S: Service<actix_http::Request, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    B: MessageBody,
+{
+    let req = test::TestRequest::post()
+        .uri(path)
+        .set_json(body)
+        .to_request();
+    let res = test::call_service(app, req).await;
+    let status = res.status();
+    let body = test::read_body(res).await;
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+/// Call a route that is expected to succeed and return its output
+async fn post<S, B>(app: &S, path: &str, body: Value) -> Value
+where
+    S: Service<actix_http::Request, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    B: MessageBody,
+{
+    let (status, output) = call(app, path, body).await;
+    assert_eq!(status, StatusCode::OK, "{} failed: {}", path, output);
+    output
+}
+
+macro_rules! app {
+    () => {
+        app!(state().await)
+    };
+    ($state:expr) => {
+        test::init_service(
+            App::new()
+                .app_data(Data::new($state))
+                .configure(routes::configure),
+        )
+        .await
+    };
+}
+
+async fn create_class<S, B>(app: &S, class_id: u64)
+where
+    S: Service<actix_http::Request, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    B: MessageBody,
+{
+    post(
+        app,
+        "/asset/create_class",
+        json!({"seed": ALICE, "class_id": class_id, "metadata": {}, "owner": account(ALICE)}),
+    )
+    .await;
+}
+
+async fn mint<S, B>(app: &S, to: &str, class_id: u64, asset_id: u64, amount: u128)
+where
+    S: Service<actix_http::Request, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    B: MessageBody,
+{
+    post(
+        app,
+        "/asset/mint",
+        json!({"seed": ALICE, "to": account(to), "class_id": class_id, "asset_id": asset_id, "amount": amount}),
+    )
+    .await;
+}
+
+async fn asset_balance<S, B>(app: &S, of: &str, class_id: u64, asset_id: u64) -> Value
+where
+    S: Service<actix_http::Request, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    B: MessageBody,
+{
+    post(
+        app,
+        "/asset/balance",
+        json!({"account": account(of), "class_id": class_id, "asset_id": asset_id}),
+    )
+    .await["amount"]
+        .clone()
+}
+
+/// Pool created by Alice that Bob joined
+async fn create_pool<S, B>(app: &S) -> Value
+where
+    S: Service<actix_http::Request, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    B: MessageBody,
+{
+    let pool = post(
+        app,
+        "/fula/pool/create",
+        json!({"seed": ALICE, "pool_name": "pool", "peer_id": PEER_ID, "region": "us-east"}),
+    )
+    .await;
+    let pool_id = pool["pool_id"].clone();
+    post(
+        app,
+        "/fula/pool/join",
+        json!({"seed": BOB, "pool_id": pool_id, "peer_id": PEER_ID_2}),
+    )
+    .await;
+    post(
+        app,
+        "/fula/pool/vote",
+        json!({"seed": ALICE, "pool_id": pool_id, "account": account(BOB), "vote_value": true, "peer_id": PEER_ID_2}),
+    )
+    .await;
+    pool_id
+}
+
+#[actix_web::test]
+async fn health_reports_node() {
+    let app = app!();
+    post(&app, "/health", json!({})).await;
+}
+
+#[actix_web::test]
+async fn accounts() {
+    let app = app!();
+
+    let seeded = post(&app, "/account/seeded", json!({"seed": ALICE})).await;
+    assert_eq!(seeded["account"], account(ALICE));
+
+    let created = post(&app, "/account/create", json!({})).await;
+    let exists = post(
+        &app,
+        "/account/exists",
+        json!({"account": created["account"]}),
+    )
+    .await;
+    assert_eq!(exists["exists"], false);
+
+    let funded = post(
+        &app,
+        "/account/fund",
+        json!({"seed": ALICE, "to": created["account"], "amount": 500}),
+    )
+    .await;
+    assert_eq!(funded["amount"], 500);
+    let exists = post(
+        &app,
+        "/account/exists",
+        json!({"account": created["account"]}),
+    )
+    .await;
+    assert_eq!(exists["exists"], true);
+    let balance = post(
+        &app,
+        "/account/balance",
+        json!({"account": created["account"]}),
+    )
+    .await;
+    assert_eq!(balance["balance"], 500);
+
+    let (status, _) = call(
+        &app,
+        "/account/fund",
+        json!({"seed": CHARLIE, "to": account(ALICE), "amount": 1}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[actix_web::test]
+async fn mnemonic_accounts() {
+    let app = app!();
+    let phrase = "bottom drive obey lake curtain smoke basket hold race lonely fit walk";
+
+    let created = post(&app, "/account/create", json!({})).await;
+    assert_eq!(created["mnemonic"].as_str().unwrap().split(' ').count(), 12);
+    assert_eq!(created["seed"], created["mnemonic"]);
+    assert_eq!(created["address"], created["account"]);
+
+    let imported = post(
+        &app,
+        "/account/create",
+        json!({"mnemonic": phrase, "derivation_path": "//Alice", "ss58_prefix": 0}),
+    )
+    .await;
+    assert_eq!(imported["account"], account(ALICE));
+    assert_eq!(imported["derivation_path"], "//Alice");
+    assert_eq!(
+        imported["address"],
+        "15oF4uVJwmo4TdGW7VfQxNLavjCXviqxT9S1MgbjMNHr6Sp5"
+    );
+    let seeded = post(&app, "/account/seeded", json!({"seed": imported["seed"]})).await;
+    assert_eq!(seeded["account"], account(ALICE));
+
+    let soft = post(
+        &app,
+        "/account/create",
+        json!({"mnemonic": phrase, "derivation_path": "//Alice/0"}),
+    )
+    .await;
+    assert_ne!(soft["account"], account(ALICE));
+
+    let ed25519 = post(
+        &app,
+        "/account/seeded",
+        json!({"seed": ALICE, "scheme": "Ed25519"}),
+    )
+    .await;
+    assert_eq!(ed25519["scheme"], "Ed25519");
+    assert_ne!(ed25519["account"], account(ALICE));
+
+    let (status, _) = call(
+        &app,
+        "/account/seeded",
+        json!({"seed": "//Alice/0", "scheme": "Ed25519"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = call(
+        &app,
+        "/account/create",
+        json!({"mnemonic": phrase, "derivation_path": "Alice"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    // Only BIP39 phrases are imported, with a valid checksum
+    let checksum = format!("{} about", vec!["abandon"; 11].join(" "));
+    post(&app, "/account/create", json!({ "mnemonic": checksum })).await;
+    for mnemonic in [
+        "not a mnemonic".to_string(),
+        "//Alice".to_string(),
+        format!("0x{}", hex::encode([7u8; 32])),
+        vec!["abandon"; 12].join(" "),
+    ] {
+        let (status, _) = call(&app, "/account/create", json!({ "mnemonic": mnemonic })).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{} was imported", mnemonic);
+    }
+
+    // Without a body, as before mnemonics could be imported
+    post(&app, "/account/create", Value::Null).await;
+    let req = test::TestRequest::post()
+        .uri("/account/create")
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::OK);
+}
+
+#[actix_web::test]
+async fn message_signatures() {
+    let app = app!();
+
+    let signed = post(
+        &app,
+        "/account/sign",
+        json!({"seed": ALICE, "message": "authorize device"}),
+    )
+    .await;
+    assert_eq!(signed["account"], account(ALICE));
+    let verified = post(
+        &app,
+        "/account/verify",
+        json!({"account": account(ALICE), "message": "authorize device", "signature": signed["signature"]}),
+    )
+    .await;
+    assert_eq!(verified["valid"], true);
+
+    // A signature of the message as is is only accepted when asked for
+    let pair = sp_core::sr25519::Pair::from_string(ALICE, None).unwrap();
+    let raw = format!("0x{}", hex::encode(pair.sign(b"authorize device").0));
+    let verified = post(
+        &app,
+        "/account/verify",
+        json!({"account": account(ALICE), "message": "authorize device", "signature": raw}),
+    )
+    .await;
+    assert_eq!(verified["valid"], false);
+    let verified = post(
+        &app,
+        "/account/verify",
+        json!({"account": account(ALICE), "message": "authorize device", "signature": raw, "raw": true}),
+    )
+    .await;
+    assert_eq!(verified["valid"], true);
+
+    for (signer, message) in [
+        (account(ALICE), "another device"),
+        (account(BOB), "authorize device"),
+    ] {
+        let verified = post(
+            &app,
+            "/account/verify",
+            json!({"account": signer, "message": message, "signature": signed["signature"]}),
+        )
+        .await;
+        assert_eq!(verified["valid"], false);
+    }
+
+    let signed = post(
+        &app,
+        "/account/sign",
+        json!({"seed": ALICE, "message": "authorize device", "scheme": "Ed25519"}),
+    )
+    .await;
+    let verified = post(
+        &app,
+        "/account/verify",
+        json!({"account": signed["account"], "message": "authorize device", "signature": signed["signature"], "scheme": "Ed25519"}),
+    )
+    .await;
+    assert_eq!(verified["valid"], true);
+
+    let (status, _) = call(
+        &app,
+        "/account/verify",
+        json!({"account": account(ALICE), "message": "authorize device", "signature": "0x1234"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[actix_web::test]
+async fn session_sign_in() {
+    // No nonce is issued when no token could be signed
+    let (status, _) = call(
+        &app!(),
+        "/session/nonce",
+        json!({"account": account(ALICE)}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let mut config = config();
+    config.auth.enabled = true;
+    config.auth.jwt_secret = Some("session-test-secret-of-32-characters".into());
+    let app = app!(state_with(config).await);
+    let balance = json!({"account": account(ALICE)});
+    let (status, _) = call(&app, "/account/balance", balance.clone()).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let sign = |seed: &str, message: &Value| {
+        let pair = sp_core::sr25519::Pair::from_string(seed, None).unwrap();
+        let message = format!("<Bytes>{}</Bytes>", message.as_str().unwrap());
+        format!("0x{}", hex::encode(pair.sign(message.as_bytes()).0))
+    };
+
+    let nonce = post(&app, "/session/nonce", json!({"account": account(ALICE)})).await;
+    let (status, _) = call(
+        &app,
+        "/session/sign_in",
+        json!({"account": account(ALICE), "nonce": nonce["nonce"], "signature": sign(BOB, &nonce["message"])}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    // A nonce is used once, even by a wrong signature
+    let (status, _) = call(
+        &app,
+        "/session/sign_in",
+        json!({"account": account(ALICE), "nonce": nonce["nonce"], "signature": sign(ALICE, &nonce["message"])}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // A client holds a few nonces at a time, the oldest are dropped
+    let first = post(&app, "/session/nonce", json!({"account": account(ALICE)})).await;
+    let mut nonce = first.clone();
+    for _ in 0..8 {
+        nonce = post(&app, "/session/nonce", json!({"account": account(ALICE)})).await;
+    }
+    let (status, _) = call(
+        &app,
+        "/session/sign_in",
+        json!({"account": account(ALICE), "nonce": first["nonce"], "signature": sign(ALICE, &first["message"])}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let session = post(
+        &app,
+        "/session/sign_in",
+        json!({"account": account(ALICE), "nonce": nonce["nonce"], "signature": sign(ALICE, &nonce["message"])}),
+    )
+    .await;
+    let bearer = format!("Bearer {}", session["token"].as_str().unwrap());
+
+    let req = test::TestRequest::post()
+        .uri("/session/info")
+        .insert_header(("Authorization", bearer.clone()))
+        .to_request();
+    let info: Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(info["account"], account(ALICE));
+    // Sessions grant no access to the routes API keys are needed for
+    let req = test::TestRequest::post()
+        .uri("/account/balance")
+        .insert_header(("Authorization", bearer.clone()))
+        .set_json(balance)
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::FORBIDDEN
+    );
+    let req = test::TestRequest::post()
+        .uri("/account/fund")
+        .insert_header(("Authorization", bearer))
+        .set_json(json!({"seed": ALICE, "to": account(BOB), "amount": 500}))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::FORBIDDEN
+    );
+}
+
+#[actix_web::test]
+async fn scoped_routes() {
+    const SECRET: &str = "scope-test-secret-of-32-characters";
+    let mut config = config();
+    config.auth.enabled = true;
+    config.auth.jwt_secret = Some(SECRET.into());
+    config.auth.api_keys = vec![
+        ApiKeyConfig {
+            name: "reader".into(),
+            key: "reader-key".into(),
+            scopes: vec![Scope::Read],
+        },
+        ApiKeyConfig {
+            name: "operator".into(),
+            key: "admin-key".into(),
+            scopes: vec![Scope::Admin],
+        },
+    ];
+    let app = app!(state_with(config).await);
+    let app = &app;
+    let balance = json!({"account": account(ALICE)});
+    let fund = json!({"seed": ALICE, "to": account(BOB), "amount": 500});
+
+    let request = |path: &str, body: &Value, header: Option<(&str, String)>| {
+        let req = test::TestRequest::post().uri(path).set_json(body);
+        match header {
+            Some(header) => req.insert_header(header).to_request(),
+            None => req.to_request(),
+        }
+    };
+    let api_key = |key: &str| Some((API_KEY_HEADER, key.to_string()));
+    let bearer = |scope: &str| {
+        let exp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            + 60;
+        let token = jsonwebtoken::encode(
+            &jsonwebtoken::Header::default(),
+            &json!({"sub": "client", "scope": scope, "exp": exp}),
+            &jsonwebtoken::EncodingKey::from_secret(SECRET.as_bytes()),
+        )
+        .unwrap();
+        Some(("Authorization", format!("Bearer {}", token)))
+    };
+    let status =
+        |req: actix_http::Request| async move { test::call_service(app, req).await.status() };
+
+    // Missing or unknown credentials
+    assert_eq!(
+        status(request("/account/balance", &balance, None)).await,
+        StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(
+        status(request("/account/balance", &balance, api_key("wrong-key"))).await,
+        StatusCode::UNAUTHORIZED
+    );
+    let forged = jsonwebtoken::encode(
+        &jsonwebtoken::Header::default(),
+        &json!({"sub": "client", "scope": "admin", "exp": u32::MAX}),
+        &jsonwebtoken::EncodingKey::from_secret(b"another-secret-of-32-characters!"),
+    )
+    .unwrap();
+    assert_eq!(
+        status(request(
+            "/account/balance",
+            &balance,
+            Some(("Authorization", format!("Bearer {}", forged)))
+        ))
+        .await,
+        StatusCode::UNAUTHORIZED
+    );
+
+    // Credentials without the scope of the route
+    let (status_code, body) = {
+        let res =
+            test::call_service(app, request("/account/fund", &fund, api_key("reader-key"))).await;
+        let status = res.status();
+        let body: Value = serde_json::from_slice(&test::read_body(res).await).unwrap();
+        (status, body)
+    };
+    assert_eq!(status_code, StatusCode::FORBIDDEN);
+    assert_eq!(body["message"], "Missing scope account:write");
+    assert_eq!(
+        status(request("/account/fund", &fund, bearer("read asset:write"))).await,
+        StatusCode::FORBIDDEN
+    );
+
+    // Credentials granting the scope, or admin
+    assert_eq!(
+        status(request("/account/balance", &balance, api_key("reader-key"))).await,
+        StatusCode::OK
+    );
+    assert_eq!(
+        status(request("/account/balance", &balance, bearer("read"))).await,
+        StatusCode::OK
+    );
+    assert_eq!(
+        status(request(
+            "/account/fund",
+            &fund,
+            bearer("read account:write")
+        ))
+        .await,
+        StatusCode::OK
+    );
+    assert_eq!(
+        status(request("/account/fund", &fund, api_key("admin-key"))).await,
+        StatusCode::OK
+    );
+}
+
+#[actix_web::test]
+async fn rate_limits() {
+    let mut config = config();
+    config.rate_limit.read_per_minute = 2;
+    config.rate_limit.sign_per_minute = 1;
+    let app = app!(state_with(config).await);
+    let app = &app;
+    let peer: std::net::SocketAddr = "10.0.0.1:4000".parse().unwrap();
+
+    let balance = |peer| {
+        test::TestRequest::post()
+            .uri("/account/balance")
+            .peer_addr(peer)
+            .set_json(json!({"account": account(ALICE)}))
+            .to_request()
+    };
+    for _ in 0..2 {
+        let res = test::call_service(app, balance(peer)).await;
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+    let res = test::call_service(app, balance(peer)).await;
+    assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+    // Two tokens a minute, the next one is 30 seconds away
+    assert_eq!(res.headers().get("Retry-After").unwrap(), "30");
+    // Other clients have their own budget
+    let res = test::call_service(app, balance("10.0.0.2:4000".parse().unwrap())).await;
+    assert_eq!(res.status(), StatusCode::OK);
+
+    // Signing routes are charged to the signer account, whatever the client
+    let (status, _) = call(
+        app,
+        "/account/fund",
+        json!({"seed": ALICE, "to": account(BOB), "amount": 500}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let req = test::TestRequest::post()
+        .uri("/account/fund")
+        .set_json(json!({"seed": ALICE, "to": account(BOB), "amount": 500}))
+        .to_request();
+    let res = test::call_service(app, req).await;
+    assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(res.headers().get("Retry-After").unwrap(), "60");
+    let (status, _) = call(
+        app,
+        "/account/fund",
+        json!({"seed": BOB, "to": account(ALICE), "amount": 500}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    // Bodies are buffered to find the signer only up to a limit
+    let (status, _) = call(
+        app,
+        "/account/fund",
+        json!({"seed": CHARLIE, "to": account(BOB), "amount": 500, "padding": "x".repeat(300_000)}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+
+    let limits = post(app, "/admin/rate_limits", json!({"key": account(ALICE)})).await;
+    assert_eq!(limits["buckets"][0]["route_class"], "sign");
+    assert_eq!(limits["buckets"][0]["available"], 0);
+}
+
+#[actix_web::test]
+async fn native_transfers() {
+    let app = app!();
+
+    let balance = post(&app, "/account/balance", json!({"account": account(ALICE)})).await;
+    assert_eq!(balance["free"], 1_000_000);
+    assert_eq!(balance["reserved"], 0);
+    assert_eq!(balance["providers"], 1);
+    assert_eq!(balance["existential_deposit"], 500);
+
+    // Below the existential deposit of a new account, even when forced the
+    // chain refuses it
+    let (status, refused) = call(
+        &app,
+        "/account/fund",
+        json!({"seed": ALICE, "to": account(CHARLIE), "amount": 100}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(refused["message"], json!(["BelowExistentialDeposit"]));
+    let (status, _) = call(
+        &app,
+        "/account/fund",
+        json!({"seed": ALICE, "to": account(CHARLIE), "amount": 100, "force": true}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let batch = post(
+        &app,
+        "/account/batch_transfer",
+        json!({"seed": ALICE, "transfers": [
+            {"to": account(CHARLIE), "amount": 1000},
+            {"to": account("//Dave"), "amount": 2000},
+        ]}),
+    )
+    .await;
+    assert_eq!(batch["transfers"][1]["to"], account("//Dave"));
+    assert_eq!(batch["transfers"][1]["warnings"], json!([]));
+    // The second transfer would leave Charlie with less than the deposit
+    let (status, refused) = call(
+        &app,
+        "/account/batch_transfer",
+        json!({"seed": CHARLIE, "transfers": [
+            {"to": account(ALICE), "amount": 400},
+            {"to": account(ALICE), "amount": 400},
+        ]}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(refused["message"], json!(["ReapsSender"]));
+
+    let (status, refused) = call(
+        &app,
+        "/account/transfer_all",
+        json!({"seed": CHARLIE, "to": account(ALICE)}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(refused["message"], json!(["ReapsSender"]));
+    let kept = post(
+        &app,
+        "/account/transfer_all",
+        json!({"seed": CHARLIE, "to": account(ALICE), "keep_alive": true}),
+    )
+    .await;
+    assert_eq!(kept["amount"], 500);
+    let (status, _) = call(
+        &app,
+        "/account/fund",
+        json!({"seed": CHARLIE, "to": account(ALICE), "amount": 100, "keep_alive": true, "force": true}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let reaped = post(
+        &app,
+        "/account/fund",
+        json!({"seed": "//Dave", "to": account(ALICE), "amount": 1900, "force": true}),
+    )
+    .await;
+    assert_eq!(reaped["warnings"], json!(["ReapsSender"]));
+    let balance = post(
+        &app,
+        "/account/balance",
+        json!({"account": account("//Dave")}),
+    )
+    .await;
+    assert_eq!(balance["free"], 0);
+
+    // The transfers before a failed one stay applied and are returned
+    let (status, failed) = call(
+        &app,
+        "/account/batch_transfer",
+        json!({"seed": ALICE, "transfers": [
+            {"to": account(BOB), "amount": 1000},
+            {"to": account(BOB), "amount": 1_000_000_000},
+        ]}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(failed["message"]["failed_index"], 1);
+    assert_eq!(failed["message"]["transfers"][0]["amount"], 1000);
+}
+
+#[actix_web::test]
+async fn account_portfolio() {
+    let app = app!();
+    create_class(&app, 1).await;
+    mint(&app, ALICE, 1, 1, 500).await;
+    let pool_id = create_pool(&app).await;
+    post(
+        &app,
+        "/fula/manifest/upload",
+        json!({"seed": ALICE, "manifest_metadata": {}, "cid": CID, "pool_id": pool_id, "replication_factor": 1}),
+    )
+    .await;
+    post(
+        &app,
+        "/fula/manifest/storage",
+        json!({"seed": BOB, "cid": CID, "pool_id": pool_id}),
+    )
+    .await;
+    post(&app, "/fula/challenge/generate", json!({"seed": ALICE})).await;
+    post(
+        &app,
+        "/fula/pool/join",
+        json!({"seed": CHARLIE, "pool_id": pool_id, "peer_id": PEER_ID}),
+    )
+    .await;
+
+    let alice = post(
+        &app,
+        "/account/portfolio",
+        json!({"account": account(ALICE)}),
+    )
+    .await;
+    assert_eq!(alice["block_number"], 9);
+    assert!(alice["balance"]["free"].as_u64().unwrap() > 0);
+    assert_eq!(alice["balance"]["reserved"], 0);
+    assert_eq!(alice["assets"][0]["amount"], 500);
+    assert_eq!(alice["assets"][0]["class"]["owner"], account(ALICE));
+    assert_eq!(alice["pool"]["pool_id"], pool_id);
+    assert_eq!(alice["pool_request"], Value::Null);
+    assert_eq!(alice["uploaded_manifests"].as_array().unwrap().len(), 1);
+    assert_eq!(alice["uploaded_manifests"][0]["cid"], CID);
+    assert_eq!(alice["stored_manifests"], json!([]));
+    assert_eq!(alice["claim"], Value::Null);
+
+    let bob = post(&app, "/account/portfolio", json!({"account": account(BOB)})).await;
+    assert_eq!(bob["uploaded_manifests"], json!([]));
+    assert_eq!(bob["stored_manifests"][0]["pool_id"], pool_id);
+    assert_eq!(bob["stored_manifests"][0]["cid"], CID);
+    assert_eq!(bob["open_challenges"][0]["challenger"], account(ALICE));
+    assert_eq!(bob["open_challenges"][0]["cid"], CID);
+
+    let charlie = post(
+        &app,
+        "/account/portfolio",
+        json!({"account": account(CHARLIE)}),
+    )
+    .await;
+    assert_eq!(charlie["balance"]["free"], 0);
+    assert_eq!(charlie["assets"], json!([]));
+    assert_eq!(charlie["pool"]["request_pool_id"], pool_id);
+    assert_eq!(charlie["pool_request"]["pool_id"], pool_id);
+    assert_eq!(charlie["pool_request"]["positive_votes"], 0);
+}
+
+#[actix_web::test]
+async fn account_activity() {
+    let app = app!();
+    post(
+        &app,
+        "/account/fund",
+        json!({"seed": ALICE, "to": account(BOB), "amount": 1000}),
+    )
+    .await;
+    create_class(&app, 1).await;
+    mint(&app, BOB, 1, 1, 10).await;
+    let pool_id = create_pool(&app).await;
+    // Let the index see the last block
+    actix_web::rt::task::yield_now().await;
+
+    let page = post(
+        &app,
+        "/account/activity",
+        json!({"account": account(BOB), "limit": 2}),
+    )
+    .await;
+    // Six seconds per block
+    assert_eq!(page["indexed_since"], 6);
+    let activity = page["activity"].as_array().unwrap();
+    assert_eq!(activity.len(), 2);
+    assert_eq!(activity[0]["event_type"], "Pool.VotingResult");
+    assert_eq!(activity[0]["block_number"], 6);
+    assert_eq!(activity[0]["timestamp"], 36);
+    assert_eq!(activity[0]["extrinsic_index"], 1);
+    assert_eq!(
+        activity[0]["payload"],
+        json!({"JoinVoteResult": {"account": account(BOB), "pool_id": pool_id, "result": "Accepted"}})
+    );
+    assert_eq!(activity[1]["event_type"], "Pool.JoinRequested");
+
+    let page = post(
+        &app,
+        "/account/activity",
+        json!({"account": account(BOB), "limit": 2, "before": page["next"]}),
+    )
+    .await;
+    let activity = page["activity"].as_array().unwrap();
+    assert_eq!(activity.len(), 2);
+    assert_eq!(activity[0]["event_type"], "Asset.Mint");
+    assert_eq!(
+        activity[0]["payload"]["AssetMinted"]["amounts"],
+        json!([10])
+    );
+    assert_eq!(
+        activity[1]["payload"],
+        json!({"Transfer": {"from": account(ALICE), "to": account(BOB), "amount": 1000}})
+    );
+    assert_eq!(activity[1]["block_number"], 1);
+    assert_eq!(page["next"], Value::Null);
+
+    let alice = post(
+        &app,
+        "/account/activity",
+        json!({"account": account(ALICE)}),
+    )
+    .await;
+    assert_eq!(alice["activity"].as_array().unwrap().len(), 4);
+    let charlie = post(
+        &app,
+        "/account/activity",
+        json!({"account": account(CHARLIE)}),
+    )
+    .await;
+    assert_eq!(charlie["activity"], json!([]));
+}
+
+#[actix_web::test]
+async fn activity_backfill() {
+    let chain = Arc::new(MockChain::new().endow(&account_id(ALICE), 1_000_000));
+    let mut config = config();
+    config.index.backfill_blocks = Some(2);
+    let state = AppState::new(chain.clone(), config).unwrap();
+    let app = app!(state.clone());
+    let fund = |amount: u128| json!({"seed": ALICE, "to": account(BOB), "amount": amount});
+    let amounts = |page: &Value| -> Vec<Value> {
+        page["activity"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|entry| entry["payload"]["Transfer"]["amount"].clone())
+            .collect()
+    };
+
+    for amount in [1000, 2000, 3000] {
+        post(&app, "/account/fund", fund(amount)).await;
+    }
+    // The latest blocks finalized before the index started are backfilled
+    let backfill = Backfill::from(&state.config.index);
+    follower::follow(state.activity.clone(), state.api.clone(), backfill)
+        .await
+        .unwrap();
+    actix_web::rt::task::yield_now().await;
+    let page = post(&app, "/account/activity", json!({"account": account(BOB)})).await;
+    assert_eq!(page["indexed_since"], 12);
+    assert_eq!(amounts(&page), vec![json!(3000), json!(2000)]);
+
+    // Blocks the subscription misses are fetched by number
+    chain.mute_watchers(true);
+    post(&app, "/account/fund", fund(4000)).await;
+    post(&app, "/account/fund", fund(5000)).await;
+    chain.mute_watchers(false);
+    post(&app, "/account/fund", fund(6000)).await;
+    actix_web::rt::task::yield_now().await;
+    let page = post(&app, "/account/activity", json!({"account": account(BOB)})).await;
+    assert_eq!(
+        amounts(&page),
+        vec![6000, 5000, 4000, 3000, 2000]
+            .into_iter()
+            .map(|amount| json!(amount))
+            .collect::<Vec<_>>()
+    );
+}
+
+#[actix_web::test]
+async fn assets() {
+    let app = app!();
+
+    create_class(&app, 1).await;
+    let (status, _) = call(
+        &app,
+        "/asset/create_class",
+        json!({"seed": ALICE, "class_id": 1, "metadata": {}, "owner": account(ALICE)}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let class = post(&app, "/asset/class_info", json!({"class_id": 1})).await;
+    assert_eq!(class["owner"], account(ALICE));
+
+    post(
+        &app,
+        "/asset/create",
+        json!({"seed": ALICE, "class_id": 1, "asset_id": 1, "metadata": {"name": "gold"}}),
+    )
+    .await;
+    post(
+        &app,
+        "/asset/update_metadata",
+        json!({"seed": ALICE, "class_id": 1, "asset_id": 1, "metadata": {"name": "silver"}}),
+    )
+    .await;
+    let info = post(&app, "/asset/info", json!({"class_id": 1, "asset_id": 1})).await;
+    assert_eq!(info["metadata"]["name"], "silver");
+
+    mint(&app, BOB, 1, 1, 100).await;
+    post(
+        &app,
+        "/asset/transfer_from",
+        json!({"seed": BOB, "from": account(BOB), "to": account(CHARLIE), "class_id": 1, "asset_id": 1, "amount": 30}),
+    )
+    .await;
+    post(
+        &app,
+        "/asset/burn",
+        json!({"seed": ALICE, "from": account(CHARLIE), "class_id": 1, "asset_id": 1, "amount": 10}),
+    )
+    .await;
+    assert_eq!(asset_balance(&app, BOB, 1, 1).await, 70);
+    assert_eq!(asset_balance(&app, CHARLIE, 1, 1).await, 20);
+
+    let balances = post(
+        &app,
+        "/asset/balances",
+        json!({"account": account(BOB), "class_id": null, "asset_id": null}),
+    )
+    .await;
+    assert_eq!(
+        balances["balances"],
+        json!([{"class_id": 1, "asset_id": 1, "amount": 70}])
+    );
+
+    let (status, _) = call(
+        &app,
+        "/asset/mint",
+        json!({"seed": BOB, "to": account(BOB), "class_id": 1, "asset_id": 1, "amount": 1}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[actix_web::test]
+async fn bags() {
+    let app = app!();
+    create_class(&app, 1).await;
+    mint(&app, ALICE, 1, 1, 100).await;
+
+    post(
+        &app,
+        "/bag/register",
+        json!({"seed": ALICE, "class_id": 1000, "metadata": {}}),
+    )
+    .await;
+    let bag = post(
+        &app,
+        "/bag/create",
+        json!({"seed": ALICE, "class_id": 1000, "owners": [account(ALICE)], "shares": [10]}),
+    )
+    .await;
+    post(
+        &app,
+        "/bag/deposit",
+        json!({"seed": ALICE, "bag": bag["bag"], "class_ids": [1], "asset_ids": [[1]], "amounts": [[40]]}),
+    )
+    .await;
+    assert_eq!(asset_balance(&app, ALICE, 1, 1).await, 60);
+
+    let (status, _) = call(
+        &app,
+        "/bag/sweep",
+        json!({"seed": BOB, "bag": bag["bag"], "to": account(BOB)}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    post(
+        &app,
+        "/bag/sweep",
+        json!({"seed": ALICE, "bag": bag["bag"], "to": account(BOB)}),
+    )
+    .await;
+    assert_eq!(asset_balance(&app, BOB, 1, 1).await, 40);
+}
+
+#[actix_web::test]
+async fn bundles() {
+    let app = app!();
+    create_class(&app, 1).await;
+    mint(&app, ALICE, 1, 1, 10).await;
+    mint(&app, ALICE, 1, 2, 20).await;
+
+    let schema = json!({"class_ids": [1], "asset_ids": [[1, 2]], "amounts": [[1, 2]]});
+    let bundle = post(
+        &app,
+        "/bundle/register",
+        json!({"seed": ALICE, "class_id": 2, "asset_id": 1, "schema": schema, "metadata": {}}),
+    )
+    .await;
+    let bundle_id = bundle["bundle_id"].clone();
+
+    post(
+        &app,
+        "/bundle/mint",
+        json!({"seed": ALICE, "from": account(ALICE), "to": account(BOB), "bundle_id": bundle_id, "amount": 3}),
+    )
+    .await;
+    assert_eq!(asset_balance(&app, ALICE, 1, 2).await, 14);
+    assert_eq!(asset_balance(&app, BOB, 2, 1).await, 3);
+
+    post(
+        &app,
+        "/bundle/burn",
+        json!({"seed": ALICE, "from": account(BOB), "to": account(CHARLIE), "bundle_id": bundle_id, "amount": 1}),
+    )
+    .await;
+    assert_eq!(asset_balance(&app, CHARLIE, 1, 1).await, 1);
+    assert_eq!(asset_balance(&app, BOB, 2, 1).await, 2);
+
+    let bundles = post(&app, "/bundle", json!({})).await;
+    assert_eq!(bundles["bundles"][0]["bundle_id"], bundle_id);
+    let bundles = post(&app, "/bundle/data", json!({})).await;
+    assert_eq!(bundles["bundles"][0]["creator"], account(ALICE));
+}
+
+#[actix_web::test]
+async fn markets() {
+    let app = app!();
+    create_class(&app, 1).await;
+    mint(&app, ALICE, 1, 1, 100).await;
+    mint(&app, BOB, 1, 2, 5).await;
+
+    post(
+        &app,
+        "/market/create_market",
+        json!({"seed": ALICE, "market_id": 1}),
+    )
+    .await;
+    let rates = json!({
+        "rates": [
+            {"class_id": 1, "asset_id": 1, "action": {"Transfer": 10}, "from": "Market", "to": "Buyer"},
+            {"class_id": 1, "asset_id": 2, "action": {"Transfer": 1}, "from": "Buyer", "to": {"Account": account(ALICE)}},
+        ],
+        "metadata": {},
+    });
+    post(
+        &app,
+        "/market/create_market_rate",
+        json!({"seed": ALICE, "market_id": 1, "market_rate_id": 1, "rates": rates}),
+    )
+    .await;
+    let deposit = post(
+        &app,
+        "/market/deposit_assets",
+        json!({"seed": ALICE, "market_id": 1, "market_rate_id": 1, "amount": 2}),
+    )
+    .await;
+    assert_eq!(deposit["success"], true);
+    assert_eq!(asset_balance(&app, ALICE, 1, 1).await, 80);
+
+    let exchange = post(
+        &app,
+        "/market/exchange_assets",
+        json!({"seed": BOB, "market_id": 1, "market_rate_id": 1, "amount": 1}),
+    )
+    .await;
+    assert_eq!(exchange["buyer"], account(BOB));
+    assert_eq!(asset_balance(&app, BOB, 1, 1).await, 10);
+    assert_eq!(asset_balance(&app, ALICE, 1, 2).await, 1);
+
+    let (status, _) = call(
+        &app,
+        "/market/exchange_assets",
+        json!({"seed": CHARLIE, "market_id": 1, "market_rate_id": 1, "amount": 1}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[actix_web::test]
+async fn pools() {
+    let app = app!();
+    let pool_id = create_pool(&app).await;
+
+    let pools = post(&app, "/fula/pool", json!({"region": "us-east"})).await;
+    assert_eq!(
+        pools["pools"][0]["participants"],
+        json!([account(ALICE), account(BOB)])
+    );
+    let pools = post(&app, "/fula/pool", json!({"region": "eu-west"})).await;
+    assert_eq!(pools["pools"], json!([]));
+
+    post(
+        &app,
+        "/fula/pool/join",
+        json!({"seed": CHARLIE, "pool_id": pool_id, "peer_id": PEER_ID}),
+    )
+    .await;
+    let requests = post(
+        &app,
+        "/fula/pool/poolrequests",
+        json!({"pool_id": pool_id, "account": null}),
+    )
+    .await;
+    assert_eq!(requests["poolrequests"][0]["account"], account(CHARLIE));
+    post(
+        &app,
+        "/fula/pool/cancel_join",
+        json!({"seed": CHARLIE, "pool_id": pool_id}),
+    )
+    .await;
+    let requests = post(
+        &app,
+        "/fula/pool/poolrequests",
+        json!({"pool_id": pool_id, "account": null}),
+    )
+    .await;
+    assert_eq!(requests["poolrequests"], json!([]));
+
+    let users = post(&app, "/fula/pool/users", json!({"account": account(BOB)})).await;
+    assert_eq!(users["users"][0]["pool_id"], pool_id);
+    post(
+        &app,
+        "/fula/pool/leave",
+        json!({"seed": BOB, "pool_id": pool_id}),
+    )
+    .await;
+    let users = post(&app, "/fula/pool/users", json!({"account": account(BOB)})).await;
+    assert_eq!(users["users"], json!([]));
+
+    let (status, _) = call(
+        &app,
+        "/fula/pool/leave",
+        json!({"seed": BOB, "pool_id": pool_id}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[actix_web::test]
+async fn pool_regions() {
+    let app = app!();
+    for (seed, region) in [(ALICE, "ca-on"), (BOB, "Quebec"), (CHARLIE, "us-east")] {
+        post(
+            &app,
+            "/fula/pool/create",
+            json!({"seed": seed, "pool_name": "pool", "peer_id": PEER_ID, "region": region}),
+        )
+        .await;
+    }
+    // Subdivisions must be in the ISO 3166-2 list, not only well formed
+    for region in ["Ontaro", "CA-OM", "US-ZZ"] {
+        let (status, error) = call(
+            &app,
+            "/fula/pool/create",
+            json!({"seed": "//Dave", "pool_name": "pool", "peer_id": PEER_ID, "region": region}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{} was accepted", region);
+        assert_eq!(error["message"], "Unknown region");
+    }
+
+    let regions = |pools: &Value| -> Vec<Value> {
+        let mut regions: Vec<Value> = pools["pools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|pool| pool["region"].clone())
+            .collect();
+        regions.sort_by_key(|region| region.to_string());
+        regions
+    };
+    let pools = post(&app, "/fula/pool", json!({"region": "CA"})).await;
+    assert_eq!(regions(&pools), vec![json!("CA-ON"), json!("CA-QC")]);
+    let pools = post(&app, "/fula/pool", json!({"region": "Ontario"})).await;
+    assert_eq!(regions(&pools), vec![json!("CA-ON")]);
+    let pools = post(&app, "/fula/pool", json!({"region": "us"})).await;
+    assert_eq!(regions(&pools), vec![json!("us-east")]);
+    let pools = post(&app, "/fula/pool", json!({"region": "US-NY"})).await;
+    assert_eq!(pools["pools"], json!([]));
+}
+
+#[actix_web::test]
+async fn pool_stats() {
+    let app = app!();
+    let pool_id = create_pool(&app).await;
+    for (cid, replication_factor) in [(CID, 2), (CID_2, 1)] {
+        post(
+            &app,
+            "/fula/manifest/upload",
+            json!({"seed": ALICE, "manifest_metadata": {}, "cid": cid, "pool_id": pool_id, "replication_factor": replication_factor}),
+        )
+        .await;
+        post(
+            &app,
+            "/fula/manifest/storage",
+            json!({"seed": BOB, "cid": cid, "pool_id": pool_id}),
+        )
+        .await;
+    }
+    post(
+        &app,
+        "/fula/file/provide",
+        json!({"seed": BOB, "pool_id": pool_id, "cids": [CID], "sizes": [1024]}),
+    )
+    .await;
+    post(&app, "/fula/challenge/generate", json!({"seed": ALICE})).await;
+
+    post(
+        &app,
+        "/fula/pool/create",
+        json!({"seed": CHARLIE, "pool_name": "other", "peer_id": PEER_ID, "region": "eu-west"}),
+    )
+    .await;
+    post(
+        &app,
+        "/fula/pool/join",
+        json!({"seed": "//Dave", "pool_id": 2, "peer_id": PEER_ID_2}),
+    )
+    .await;
+
+    let stats = post(&app, "/fula/pool/stats", json!({"pool_id": pool_id})).await;
+    let pool = &stats["pools"][0];
+    assert_eq!(stats["pools"].as_array().unwrap().len(), 1);
+    assert_eq!(pool["participants"], 2);
+    assert_eq!(pool["pending_requests"], 0);
+    assert_eq!(pool["manifests"], 2);
+    assert_eq!(pool["total_size"], 1024);
+    assert_eq!(pool["unsized_manifests"], 1);
+    assert_eq!(pool["under_replicated"], 1);
+    assert_eq!(pool["open_challenges"], 1);
+    assert_eq!(pool["average_reliability"], Value::Null);
+
+    let pool_ids = |stats: &Value| -> Vec<Value> {
+        stats["pools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|pool| pool["pool_id"].clone())
+            .collect()
+    };
+    let stats = post(
+        &app,
+        "/fula/pool/stats",
+        json!({"sort_by": "PendingRequests", "descending": true}),
+    )
+    .await;
+    assert_eq!(pool_ids(&stats), vec![json!(2), json!(1)]);
+    let stats = post(&app, "/fula/pool/stats", json!({"sort_by": "Manifests"})).await;
+    assert_eq!(pool_ids(&stats), vec![json!(2), json!(1)]);
+    let stats = post(&app, "/fula/pool/stats", json!({"descending": true})).await;
+    assert_eq!(pool_ids(&stats), vec![json!(2), json!(1)]);
+    let stats = post(&app, "/fula/pool/stats", json!({})).await;
+    assert_eq!(pool_ids(&stats), vec![json!(1), json!(2)]);
+}
+
+#[actix_web::test]
+async fn pool_hierarchy() {
+    let chain = Arc::new(MockChain::new());
+    let app = app!(AppState::new(chain.clone(), config()).unwrap());
+    for (seed, region) in [(ALICE, "us"), (BOB, "us-east"), (CHARLIE, "us-west")] {
+        post(
+            &app,
+            "/fula/pool/create",
+            json!({"seed": seed, "pool_name": "pool", "peer_id": PEER_ID, "region": region}),
+        )
+        .await;
+    }
+    chain.set_pool_parent(2, Some(1)).unwrap();
+    chain.set_pool_parent(3, Some(2)).unwrap();
+
+    let tree = post(&app, "/fula/pool/tree", json!({})).await;
+    let roots = tree["roots"].as_array().unwrap();
+    assert_eq!(roots.len(), 1);
+    assert_eq!(roots[0]["pool"]["pool_id"], 1);
+    assert_eq!(roots[0]["subtree_pools"], 3);
+    assert_eq!(roots[0]["subtree_participants"], 3);
+    assert_eq!(roots[0]["children"][0]["pool"]["pool_id"], 2);
+    assert_eq!(roots[0]["children"][0]["children"][0]["pool"]["pool_id"], 3);
+
+    let tree = post(&app, "/fula/pool/tree", json!({"pool_id": 2})).await;
+    assert_eq!(tree["roots"][0]["pool"]["creator"], account(BOB));
+    assert_eq!(tree["roots"][0]["subtree_pools"], 2);
+
+    let ancestry = post(&app, "/fula/pool/ancestry", json!({"pool_id": 3})).await;
+    let ancestors: Vec<&Value> = ancestry["ancestors"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|pool| &pool["pool_id"])
+        .collect();
+    assert_eq!(ancestors, vec![&json!(2), &json!(1)]);
+    assert_eq!(ancestry["cyclic"], false);
+    assert_eq!(tree["cyclic"], json!([]));
+
+    // Pools in a parent cycle have no root, they are reported instead
+    chain.set_pool_parent(1, Some(3)).unwrap();
+    let tree = post(&app, "/fula/pool/tree", json!({})).await;
+    assert_eq!(tree["roots"], json!([]));
+    assert_eq!(tree["cyclic"], json!([1, 2, 3]));
+    let tree = post(&app, "/fula/pool/tree", json!({"pool_id": 2})).await;
+    assert_eq!(tree["roots"][0]["subtree_pools"], 3);
+    assert_eq!(tree["cyclic"], json!([1, 2, 3]));
+    let ancestry = post(&app, "/fula/pool/ancestry", json!({"pool_id": 3})).await;
+    assert_eq!(ancestry["ancestors"].as_array().unwrap().len(), 2);
+    assert_eq!(ancestry["cyclic"], true);
+
+    let (status, _) = call(&app, "/fula/pool/ancestry", json!({"pool_id": 99})).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[actix_web::test]
+async fn pool_governance() {
+    let app = app!();
+    let pool_id = create_pool(&app).await;
+    post(
+        &app,
+        "/fula/pool/join",
+        json!({"seed": CHARLIE, "pool_id": pool_id, "peer_id": PEER_ID}),
+    )
+    .await;
+    // A later block finalized before the index sees the request does not
+    // change the block it was made in
+    post(
+        &app,
+        "/account/fund",
+        json!({"seed": ALICE, "to": account(CHARLIE), "amount": 1000}),
+    )
+    .await;
+    actix_web::rt::task::yield_now().await;
+
+    let governance = post(&app, "/fula/pool/governance", json!({"pool_id": pool_id})).await;
+    let request = &governance["requests"][0];
+    assert_eq!(request["account"], account(CHARLIE));
+    assert_eq!(
+        request["eligible_voters"],
+        json!([account(ALICE), account(BOB)])
+    );
+    assert_eq!(request["approvals_needed"], 2);
+    assert_eq!(request["remaining_approvals"], 2);
+    // Pool creation, Bob joining and the vote on Bob come first
+    assert_eq!(request["requested_at"], 4);
+
+    // The vote does not settle the request, so no `VotingResult` is found
+    call(
+        &app,
+        "/fula/pool/vote",
+        json!({"seed": ALICE, "pool_id": pool_id, "account": account(CHARLIE), "vote_value": true, "peer_id": PEER_ID}),
+    )
+    .await;
+    let governance = post(&app, "/fula/pool/governance", json!({})).await;
+    let request = &governance["requests"][0];
+    assert_eq!(request["voted"], json!([account(ALICE)]));
+    assert_eq!(request["not_voted"], json!([account(BOB)]));
+    assert_eq!(request["positive_votes"], 1);
+    assert_eq!(request["negative_votes"], 0);
+    assert_eq!(request["remaining_approvals"], 1);
+
+    let pending = post(
+        &app,
+        "/fula/pool/pending_votes",
+        json!({"account": account(BOB)}),
+    )
+    .await;
+    assert_eq!(pending["requests"][0]["account"], account(CHARLIE));
+    let pending = post(
+        &app,
+        "/fula/pool/pending_votes",
+        json!({"account": account(ALICE)}),
+    )
+    .await;
+    assert_eq!(pending["requests"], json!([]));
+}
+
+#[actix_web::test]
+async fn peer_directory() {
+    let app = app!();
+    let pool_id = create_pool(&app).await;
+    // Charlie asks to join with the peer id Alice created the pool with
+    post(
+        &app,
+        "/fula/pool/join",
+        json!({"seed": CHARLIE, "pool_id": pool_id, "peer_id": PEER_ID}),
+    )
+    .await;
+
+    let peer = post(&app, "/fula/pool/peer", json!({"peer_id": PEER_ID})).await;
+    assert_eq!(peer["duplicate"], true);
+    let accounts = peer["accounts"].as_array().unwrap();
+    assert_eq!(accounts.len(), 2);
+    let alice = accounts
+        .iter()
+        .find(|record| record["account"] == account(ALICE))
+        .unwrap();
+    assert_eq!(alice["status"], "Participant");
+    assert_eq!(alice["pool_id"], pool_id);
+    assert_eq!(alice["positive_votes"], Value::Null);
+    let charlie = accounts
+        .iter()
+        .find(|record| record["account"] == account(CHARLIE))
+        .unwrap();
+    assert_eq!(charlie["status"], "Requested");
+    assert_eq!(charlie["positive_votes"], 0);
+
+    let peer = post(&app, "/fula/pool/peer", json!({"peer_id": PEER_ID_2})).await;
+    assert_eq!(peer["duplicate"], false);
+    assert_eq!(peer["accounts"][0]["account"], account(BOB));
+
+    let peers = post(&app, "/fula/pool/peers", json!({"pool_id": pool_id})).await;
+    assert_eq!(peers["peers"].as_array().unwrap().len(), 2);
+    assert_eq!(peers["duplicates"], json!([PEER_ID]));
+    let peers = post(
+        &app,
+        "/fula/pool/peers",
+        json!({"pool_id": pool_id, "include_requests": true}),
+    )
+    .await;
+    assert_eq!(peers["peers"].as_array().unwrap().len(), 3);
+
+    let (status, _) = call(&app, "/fula/pool/peers", json!({"pool_id": 99})).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[actix_web::test]
+async fn leave_pool_releases_storage() {
+    let app = app!();
+    let pool_id = create_pool(&app).await;
+    for cid in [CID, CID_2] {
+        post(
+            &app,
+            "/fula/manifest/upload",
+            json!({"seed": ALICE, "manifest_metadata": {}, "cid": cid, "pool_id": pool_id, "replication_factor": 1}),
+        )
+        .await;
+        post(
+            &app,
+            "/fula/manifest/storage",
+            json!({"seed": BOB, "cid": cid, "pool_id": pool_id}),
+        )
+        .await;
+    }
+
+    let leave = json!({"seed": BOB, "pool_id": pool_id, "release_storage": true, "preview": true});
+    let preview = post(&app, "/fula/pool/leave", leave).await;
+    assert_eq!(preview["preview"], true);
+    assert_eq!(preview["account"], account(BOB));
+    assert_eq!(preview["released_cids"].as_array().unwrap().len(), 2);
+    let users = post(&app, "/fula/pool/users", json!({"account": account(BOB)})).await;
+    assert_eq!(users["users"][0]["pool_id"], pool_id);
+
+    let leave = json!({"seed": BOB, "pool_id": pool_id, "release_storage": true});
+    let left = post(&app, "/fula/pool/leave", leave).await;
+    assert_eq!(left["preview"], false);
+    let mut released: Vec<Value> = left["released_cids"].as_array().unwrap().clone();
+    released.sort_by_key(|cid| cid.to_string());
+    assert_eq!(released, vec![json!(CID_2), json!(CID)]);
+    let users = post(&app, "/fula/pool/users", json!({"account": account(BOB)})).await;
+    assert_eq!(users["users"], json!([]));
+
+    let report = post(&app, "/fula/replication/report", json!({})).await;
+    assert_eq!(report["pools"][0]["unstored"], 2);
+}
+
+#[actix_web::test]
+async fn manifests() {
+    let app = app!();
+    let pool_id = create_pool(&app).await;
+
+    post(
+        &app,
+        "/fula/manifest/upload",
+        json!({"seed": ALICE, "manifest_metadata": {"job": {"uri": CID}}, "cid": CID, "pool_id": pool_id, "replication_factor": 1}),
+    )
+    .await;
+    post(
+        &app,
+        "/fula/manifest/batch_upload",
+        json!({"seed": ALICE, "manifest_metadata": [{"job": {"uri": CID_2}}], "cid": [CID_2], "pool_id": [pool_id], "replication_factor": [1]}),
+    )
+    .await;
+    let (status, _) = call(
+        &app,
+        "/fula/manifest/batch_upload",
+        json!({"seed": ALICE, "manifest_metadata": [{"job": {"uri": CID}}], "cid": [CID, CID_2], "pool_id": [pool_id, pool_id], "replication_factor": [1, 1]}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let available = post(
+        &app,
+        "/fula/manifest/available",
+        json!({"pool_id": pool_id}),
+    )
+    .await;
+    assert_eq!(available["manifests"].as_array().unwrap().len(), 2);
+    post(
+        &app,
+        "/fula/manifest/available/alter",
+        json!({"pool_id": pool_id}),
+    )
+    .await;
+
+    post(
+        &app,
+        "/fula/manifest/storage",
+        json!({"seed": BOB, "cid": CID, "pool_id": pool_id}),
+    )
+    .await;
+    post(
+        &app,
+        "/fula/manifest/batch_storage",
+        json!({"seed": BOB, "cid": [CID_2], "pool_id": pool_id}),
+    )
+    .await;
+    let (status, _) = call(
+        &app,
+        "/fula/manifest/storage",
+        json!({"seed": ALICE, "cid": CID, "pool_id": pool_id}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let manifests = post(
+        &app,
+        "/fula/manifest",
+        json!({"pool_id": pool_id, "uploader": null, "storer": account(BOB)}),
+    )
+    .await;
+    assert_eq!(manifests["manifests"].as_array().unwrap().len(), 2);
+    post(
+        &app,
+        "/fula/manifest/alter",
+        json!({"pool_id": pool_id, "uploader": null, "storer": account(BOB)}),
+    )
+    .await;
+
+    post(
+        &app,
+        "/fula/manifest/update",
+        json!({"seed": BOB, "cid": CID, "pool_id": pool_id, "active_days": 1, "active_cycles": 2, "missed_cycles": 0}),
+    )
+    .await;
+    let storer_data = post(
+        &app,
+        "/fula/manifest/storer_data",
+        json!({"pool_id": pool_id, "storer": account(BOB)}),
+    )
+    .await;
+    assert_eq!(storer_data["manifests"].as_array().unwrap().len(), 2);
+    post(
+        &app,
+        "/fula/manifest/storer_data/alter",
+        json!({"pool_id": pool_id, "storer": account(BOB)}),
+    )
+    .await;
+    let verified = post(&app, "/fula/manifest/verify", json!({"seed": BOB})).await;
+    assert_eq!(verified["valid_manifests"].as_array().unwrap().len(), 2);
+
+    post(
+        &app,
+        "/fula/manifest/remove_stored_manifest",
+        json!({"seed": BOB, "cid": CID, "pool_id": pool_id}),
+    )
+    .await;
+    post(
+        &app,
+        "/fula/manifest/batch_remove_stored_manifest",
+        json!({"seed": BOB, "cid": [CID_2], "pool_id": pool_id}),
+    )
+    .await;
+    post(
+        &app,
+        "/fula/manifest/remove",
+        json!({"seed": ALICE, "cid": CID, "pool_id": pool_id}),
+    )
+    .await;
+    post(
+        &app,
+        "/fula/manifest/batch_remove",
+        json!({"seed": ALICE, "cid": [CID_2], "pool_id": [pool_id]}),
+    )
+    .await;
+    let manifests = post(
+        &app,
+        "/fula/manifest",
+        json!({"pool_id": null, "uploader": null, "storer": null}),
+    )
+    .await;
+    assert_eq!(manifests["manifests"], json!([]));
+}
+
+#[actix_web::test]
+async fn challenges_and_file_sizes() {
+    let app = app!();
+    let pool_id = create_pool(&app).await;
+    post(
+        &app,
+        "/fula/manifest/upload",
+        json!({"seed": ALICE, "manifest_metadata": {}, "cid": CID, "pool_id": pool_id, "replication_factor": 1}),
+    )
+    .await;
+    post(
+        &app,
+        "/fula/manifest/storage",
+        json!({"seed": BOB, "cid": CID, "pool_id": pool_id}),
+    )
+    .await;
+
+    let missing = post(&app, "/fula/file/verify", json!({"account": account(BOB)})).await;
+    assert_eq!(missing["cids"], json!([CID]));
+    post(
+        &app,
+        "/fula/file/provide",
+        json!({"seed": BOB, "pool_id": pool_id, "cids": [CID], "sizes": [1024]}),
+    )
+    .await;
+    let missing = post(&app, "/fula/file/verify", json!({"account": account(BOB)})).await;
+    assert_eq!(missing["cids"], json!([]));
+
+    let challenge = post(&app, "/fula/challenge/generate", json!({"seed": ALICE})).await;
+    assert_eq!(challenge["challenged"], account(BOB));
+    assert_eq!(challenge["cid"], CID);
+    let pending = post(
+        &app,
+        "/fula/challenge/pending",
+        json!({"account": account(BOB)}),
+    )
+    .await;
+    assert_eq!(pending["pending"], true);
+    let challenges = post(&app, "/fula/challenge", json!({})).await;
+    assert_eq!(challenges["challenges"][0]["challenger"], account(ALICE));
+
+    let verified = post(
+        &app,
+        "/fula/challenge/verify",
+        json!({"seed": BOB, "pool_id": pool_id, "cids": [CID], "class_id": 110, "asset_id": 100}),
+    )
+    .await;
+    assert_eq!(verified["successful_cids"], json!([CID]));
+    let pending = post(
+        &app,
+        "/fula/challenge/pending",
+        json!({"account": account(BOB)}),
+    )
+    .await;
+    assert_eq!(pending["pending"], false);
+
+    let minted = post(
+        &app,
+        "/fula/mint_labor_tokens",
+        json!({"seed": BOB, "class_id": 100, "asset_id": 100, "amount": 1}),
+    )
+    .await;
+    assert_eq!(minted["calculated_amount"], 1);
+    let claims = post(&app, "/fula/claims", json!({})).await;
+    assert_eq!(claims["claims"][0]["minted_challenge_tokens"], 1);
+}
+
+#[actix_web::test]
+async fn challenge_responses() {
+    let app = app!();
+    let pool_id = create_pool(&app).await;
+    post(
+        &app,
+        "/fula/manifest/upload",
+        json!({"seed": ALICE, "manifest_metadata": {}, "cid": CID, "pool_id": pool_id, "replication_factor": 1}),
+    )
+    .await;
+    post(
+        &app,
+        "/fula/manifest/storage",
+        json!({"seed": BOB, "cid": CID, "pool_id": pool_id}),
+    )
+    .await;
+
+    let responded = post(&app, "/fula/challenge/respond", json!({"seed": BOB})).await;
+    assert_eq!(responded["successful_cids"], json!([]));
+    assert_eq!(responded["minted"], 0);
+
+    post(&app, "/fula/challenge/generate", json!({"seed": ALICE})).await;
+    let open = post(
+        &app,
+        "/fula/challenge/open",
+        json!({"account": account(BOB)}),
+    )
+    .await;
+    assert_eq!(
+        open["challenges"],
+        json!([{"challenger": account(ALICE), "cid": CID}])
+    );
+    let responded = post(
+        &app,
+        "/fula/challenge/respond",
+        json!({"seed": BOB, "held_cids": [CID]}),
+    )
+    .await;
+    assert_eq!(responded["pool_id"], pool_id);
+    assert_eq!(responded["successful_cids"], json!([CID]));
+    assert_eq!(responded["minted"], 1);
+    let open = post(
+        &app,
+        "/fula/challenge/open",
+        json!({"account": account(BOB)}),
+    )
+    .await;
+    assert_eq!(open["challenges"], json!([]));
+
+    post(&app, "/fula/challenge/generate", json!({"seed": ALICE})).await;
+    let responded = post(
+        &app,
+        "/fula/challenge/respond",
+        json!({"seed": BOB, "held_cids": []}),
+    )
+    .await;
+    assert_eq!(responded["failed_cids"], json!([CID]));
+    assert_eq!(responded["minted"], 0);
+
+    let (status, _) = call(&app, "/fula/challenge/respond", json!({"seed": CHARLIE})).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[actix_web::test]
+async fn manifest_metadata() {
+    let mut config = config();
+    config.manifest.strict_metadata = true;
+    let state = state_with(config).await;
+    let api = state.api.clone();
+    let app = app!(state);
+    let pool_id = create_pool(&app).await;
+    let job = json!({"work": "Storage", "engine": "IPFS", "uri": CID});
+
+    for manifest_metadata in [
+        json!({}),
+        json!({"job": {"work": "Storage", "engine": "IPFS", "uri": CID_2}}),
+        json!({"job": job, "content_type": "png"}),
+        json!({"job": job, "owner": "alice"}),
+    ] {
+        let (status, _) = call(
+            &app,
+            "/fula/manifest/upload",
+            json!({"seed": ALICE, "manifest_metadata": manifest_metadata, "cid": CID, "pool_id": pool_id, "replication_factor": 1}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{}", manifest_metadata);
+    }
+    let (status, error) = call(
+        &app,
+        "/fula/manifest/batch_upload",
+        json!({"seed": ALICE, "manifest_metadata": [{"job": job}, {}], "cid": [CID, CID_2], "pool_id": [pool_id, pool_id], "replication_factor": [1, 1]}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(error.to_string().contains("manifest_metadata[1]"));
+
+    let manifest_metadata = json!({
+        "job": job,
+        "name": "cat.png",
+        "size": 1024,
+        "content_type": "image/png",
+        "encryption": {"algorithm": "AES-256-GCM", "key_id": "k1"},
+    });
+    let uploaded = post(
+        &app,
+        "/fula/manifest/upload",
+        json!({"seed": ALICE, "manifest_metadata": manifest_metadata, "cid": CID, "pool_id": pool_id, "replication_factor": 1}),
+    )
+    .await;
+    assert_eq!(uploaded["manifest_metadata"], manifest_metadata);
+    assert_eq!(uploaded["metadata_undecodable"], false);
+
+    // Metadata written by another client that is not JSON
+    let upload = sugarfunge::tx().fula().upload_manifest(
+        BoundedVec(vec![0xde, 0xad]),
+        BoundedVec(CID_2.as_bytes().to_vec()),
+        1,
+        1,
+    );
+    let signer = PairSigner::new(sp_core::sr25519::Pair::from_string(ALICE, None).unwrap());
+    api.sign_and_submit(&upload, &signer).await.unwrap();
+
+    let manifests = post(&app, "/fula/manifest", json!({"pool_id": pool_id})).await;
+    let manifests = manifests["manifests"].as_array().unwrap();
+    let raw = manifests
+        .iter()
+        .find(|manifest| manifest["metadata_undecodable"] == true)
+        .unwrap();
+    assert_eq!(raw["manifest_metadata"], "0xdead");
+}
+
+#[actix_web::test]
+async fn storer_reliability() {
+    let app = app!();
+    let pool_id = create_pool(&app).await;
+    for cid in [CID, CID_2] {
+        post(
+            &app,
+            "/fula/manifest/upload",
+            json!({"seed": ALICE, "manifest_metadata": {}, "cid": cid, "pool_id": pool_id, "replication_factor": 1}),
+        )
+        .await;
+        post(
+            &app,
+            "/fula/manifest/storage",
+            json!({"seed": BOB, "cid": cid, "pool_id": pool_id}),
+        )
+        .await;
+    }
+    post(
+        &app,
+        "/fula/manifest/update",
+        json!({"seed": BOB, "cid": CID, "pool_id": pool_id, "active_days": 2, "active_cycles": 5, "missed_cycles": 1}),
+    )
+    .await;
+
+    for held_cids in [json!([CID, CID_2]), json!([])] {
+        post(&app, "/fula/challenge/generate", json!({"seed": ALICE})).await;
+        post(
+            &app,
+            "/fula/challenge/respond",
+            json!({"seed": BOB, "held_cids": held_cids}),
+        )
+        .await;
+    }
+    // Let the history index the last block
+    actix_web::rt::task::yield_now().await;
+
+    let reliability = post(&app, "/fula/challenge/reliability", json!({})).await;
+    let storers = reliability["storers"].as_array().unwrap();
+    assert_eq!(storers.len(), 1);
+    let bob = &storers[0];
+    assert_eq!(bob["account"], account(BOB));
+    assert_eq!(bob["challenges_received"], 2);
+    assert_eq!(bob["challenges_successful"], 1);
+    assert_eq!(bob["challenges_failed"], 1);
+    assert_eq!(bob["success_rate"], 0.5);
+    assert_eq!(bob["manifests_stored"], 2);
+    assert_eq!(bob["active_cycles"], 5);
+    assert_eq!(bob["missed_cycles"], 1);
+    assert_eq!(bob["active_days"], 2);
+    assert_eq!(bob["missed_cycles_trend"][0]["missed_cycles"], 1);
+    // Stamped with the time of the block of the update, the eighth one
+    assert_eq!(bob["missed_cycles_trend"][0]["timestamp"], 48);
+    assert_eq!(reliability["indexed_since"], 6);
+    assert_eq!(bob["failed_cids"].as_array().unwrap().len(), 1);
+
+    let reliability = post(
+        &app,
+        "/fula/challenge/reliability",
+        json!({"account": account(CHARLIE)}),
+    )
+    .await;
+    assert_eq!(reliability["storers"][0]["manifests_stored"], 0);
+    assert_eq!(reliability["storers"][0]["success_rate"], Value::Null);
+}
+
+#[actix_web::test]
+async fn rewards() {
+    let app = app!();
+    let pool_id = create_pool(&app).await;
+    for (cid, active_cycles) in [(CID, 5), (CID_2, 3)] {
+        post(
+            &app,
+            "/fula/manifest/upload",
+            json!({"seed": ALICE, "manifest_metadata": {}, "cid": cid, "pool_id": pool_id, "replication_factor": 1}),
+        )
+        .await;
+        post(
+            &app,
+            "/fula/manifest/storage",
+            json!({"seed": BOB, "cid": cid, "pool_id": pool_id}),
+        )
+        .await;
+        post(
+            &app,
+            "/fula/manifest/update",
+            json!({"seed": BOB, "cid": cid, "pool_id": pool_id, "active_days": 1, "active_cycles": active_cycles, "missed_cycles": 0}),
+        )
+        .await;
+    }
+    post(&app, "/fula/challenge/generate", json!({"seed": ALICE})).await;
+    post(
+        &app,
+        "/fula/challenge/respond",
+        json!({"seed": BOB, "held_cids": [CID, CID_2]}),
+    )
+    .await;
+    post(
+        &app,
+        "/fula/mint_labor_tokens",
+        json!({"seed": BOB, "class_id": 100, "asset_id": 100, "amount": 2}),
+    )
+    .await;
+
+    let rewards = post(&app, "/fula/rewards", json!({"account": account(BOB)})).await;
+    assert_eq!(rewards["expected_labor_tokens"], 8);
+    assert_eq!(rewards["expected_challenge_tokens"], 1);
+    assert_eq!(rewards["minted_labor_tokens"], 2);
+    assert_eq!(rewards["minted_challenge_tokens"], 1);
+    assert_eq!(rewards["pending_labor_tokens"], 6);
+    assert_eq!(rewards["pending_challenge_tokens"], 0);
+    assert_eq!(rewards["claimable_tokens"], 1);
+
+    let pools = rewards["pools"].as_array().unwrap();
+    assert_eq!(pools.len(), 1);
+    assert_eq!(pools[0]["pool_id"], pool_id);
+    assert_eq!(pools[0]["labor_tokens"], 8);
+    assert_eq!(pools[0]["challenge_tokens"], 1);
+    let cids = pools[0]["cids"].as_array().unwrap();
+    assert_eq!(cids.len(), 2);
+    let cid_rewards = |cid: &str| cids.iter().find(|entry| entry["cid"] == cid).unwrap();
+    assert_eq!(cid_rewards(CID)["labor_tokens"], 5);
+    assert_eq!(cid_rewards(CID)["active_cycles"], 5);
+    assert_eq!(cid_rewards(CID_2)["labor_tokens"], 3);
+    // Only the challenged cid earned a challenge token
+    let challenge_tokens: u64 = cids
+        .iter()
+        .map(|entry| entry["challenge_tokens"].as_u64().unwrap())
+        .sum();
+    assert_eq!(challenge_tokens, 1);
+
+    // What was minted matches the claim on chain
+    let claims = post(&app, "/fula/claims", json!({})).await;
+    let claim = claims["claims"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|claim| claim["account"] == account(BOB))
+        .unwrap();
+    assert_eq!(claim["minted_labor_tokens"], rewards["minted_labor_tokens"]);
+    assert_eq!(
+        claim["minted_challenge_tokens"],
+        rewards["minted_challenge_tokens"]
+    );
+
+    let rewards = post(
+        &app,
+        "/fula/rewards",
+        json!({"account": account(BOB), "pool_id": pool_id}),
+    )
+    .await;
+    assert_eq!(rewards["pools"][0]["cids"].as_array().unwrap().len(), 2);
+    assert_eq!(rewards["pending_labor_tokens"], 6);
+
+    let rewards = post(
+        &app,
+        "/fula/rewards",
+        json!({"account": account(ALICE), "pool_id": pool_id}),
+    )
+    .await;
+    assert_eq!(rewards["pools"], json!([]));
+
+    let rewards = post(
+        &app,
+        "/fula/rewards",
+        json!({"account": account(BOB), "pool_id": 99}),
+    )
+    .await;
+    assert_eq!(rewards["pools"], json!([]));
+    assert_eq!(rewards["pending_labor_tokens"], 0);
+}
+
+#[actix_web::test]
+async fn replication_report_and_alerts() {
+    let state = state().await;
+    let mut alerts = state.replication.subscribe();
+    let app = app!(state);
+    let pool_id = create_pool(&app).await;
+    post(
+        &app,
+        "/fula/manifest/upload",
+        json!({"seed": ALICE, "manifest_metadata": {}, "cid": CID, "pool_id": pool_id, "replication_factor": 2}),
+    )
+    .await;
+    for seed in [ALICE, BOB] {
+        post(
+            &app,
+            "/fula/manifest/storage",
+            json!({"seed": seed, "cid": CID, "pool_id": pool_id}),
+        )
+        .await;
+    }
+    // Let the monitor see both storers
+    actix_web::rt::task::yield_now().await;
+
+    let report = post(&app, "/fula/replication/report", json!({})).await;
+    assert_eq!(report["pools"][0]["manifests"], 1);
+    assert_eq!(report["pools"][0]["deficit"], 0);
+    assert_eq!(report["manifests"], json!([]));
+
+    let remove = json!({"seed": BOB, "cid": CID, "pool_id": pool_id});
+    post(&app, "/fula/manifest/remove_stored_manifest", remove).await;
+    let alert = alerts.next().await.unwrap();
+    assert_eq!(alert.kind, ReplicationAlertKind::UnderReplicated);
+    assert_eq!(alert.previous_storers, 2);
+    assert_eq!(alert.manifest.storers, 1);
+
+    let report = post(
+        &app,
+        "/fula/replication/report",
+        json!({"pool_id": pool_id}),
+    )
+    .await;
+    assert_eq!(report["pools"][0]["under_replicated"], 1);
+    assert_eq!(report["pools"][0]["unstored"], 0);
+    assert_eq!(report["manifests"][0]["cid"], CID);
+    assert_eq!(report["manifests"][0]["deficit"], 1);
+
+    let remove = json!({"seed": ALICE, "cid": CID, "pool_id": pool_id});
+    post(&app, "/fula/manifest/remove_stored_manifest", remove).await;
+    let alert = alerts.next().await.unwrap();
+    assert_eq!(alert.kind, ReplicationAlertKind::Unstored);
+
+    let report = post(&app, "/fula/replication/report", json!({"pool_id": 99})).await;
+    assert_eq!(report["pools"], json!([]));
+}
+
+#[actix_web::test]
+async fn validators() {
+    let app = app!();
+    let validator = json!({"seed": ALICE, "validator_id": account(BOB)});
+
+    let (status, _) = call(
+        &app,
+        "/validator/add_validator",
+        json!({"seed": BOB, "validator_id": account(BOB)}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let added = post(&app, "/validator/add_validator", validator.clone()).await;
+    assert_eq!(added["validator_id"], account(BOB));
+    post(&app, "/validator/remove_validator", validator).await;
+}